@@ -23,6 +23,7 @@ pub enum DaemonEvent {
     /// Show the launcher window
     Show {
         modes: Option<Vec<LauncherMode>>,
+        output: Option<String>,
         response_tx: oneshot::Sender<IpcResponse>,
     },
 
@@ -34,6 +35,7 @@ pub enum DaemonEvent {
     /// Toggle the launcher window visibility
     Toggle {
         modes: Option<Vec<LauncherMode>>,
+        output: Option<String>,
         response_tx: oneshot::Sender<IpcResponse>,
     },
 
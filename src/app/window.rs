@@ -1,17 +1,22 @@
 use crate::app::{DaemonEvent, DaemonEventSender, WindowEvent};
-use crate::compositor::Compositor;
-use crate::config::{ConfigModule, LauncherMode, get_combined_modules};
-use crate::items::{ApplicationItem, ListItem, WindowItem};
+use crate::compositor::{Compositor, WindowTracker};
+use crate::config::{
+    ConfigModule, LauncherAnchor, LauncherMode, PlacementConfig, get_combined_modules,
+};
+use crate::items::{ApplicationItem, ListItem, WindowItem, WorkspaceItem, apply_overrides};
 use crate::ui::LauncherView;
+use crate::ui::launcher::PanelPlacement;
 use gpui::{
-    App, AppContext, Bounds, Entity, WindowBackgroundAppearance, WindowBounds, WindowDecorations,
-    WindowHandle, WindowKind, WindowOptions,
+    App, AppContext, Bounds, Entity, PlatformDisplay, WindowBackgroundAppearance, WindowBounds,
+    WindowDecorations, WindowHandle, WindowKind, WindowOptions,
     layer_shell::{Anchor, KeyboardInteractivity, Layer, LayerShellOptions},
     point, px, size,
 };
 use gpui_component::Root;
+use std::rc::Rc;
 use std::sync::Arc;
 use tracing::warn;
+use uuid::Uuid;
 
 /// Handle to an open launcher window, containing both the window and view entity.
 pub struct LauncherWindow {
@@ -22,43 +27,104 @@ pub struct LauncherWindow {
 pub fn create_and_show_window(
     applications: Vec<ApplicationItem>,
     compositor: Arc<dyn Compositor>,
+    window_tracker: &WindowTracker,
     modes: Vec<LauncherMode>,
+    output: Option<String>,
     event_tx: DaemonEventSender,
     cx: &mut App,
 ) -> anyhow::Result<LauncherWindow> {
-    // Fetch open windows from compositor (if not disabled)
-    let combined_modules = get_combined_modules();
-    let windows = if combined_modules.contains(&ConfigModule::Windows) {
-        fetch_windows(compositor.as_ref())
+    // Hide the windows module if the compositor cannot list windows
+    let windows_supported = compositor.capabilities().window_list;
+    let modes = if windows_supported {
+        modes
     } else {
-        Vec::new()
+        without_window_modes(modes)
     };
-    create_and_show_window_impl(applications, compositor, windows, modes, event_tx, cx)
+
+    // Get open windows from the tracker and workspaces from the compositor (if not disabled)
+    let combined_modules = get_combined_modules();
+    let (windows, workspaces) =
+        if windows_supported && combined_modules.contains(&ConfigModule::Windows) {
+            let mut windows = fetch_windows(window_tracker);
+            let workspaces = fetch_workspaces(compositor.as_ref(), &windows);
+            describe_window_workspaces(&mut windows, &workspaces);
+            (windows, workspaces)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+    create_and_show_window_impl(
+        applications,
+        compositor,
+        window_tracker.clone(),
+        windows,
+        workspaces,
+        modes,
+        output,
+        event_tx,
+        cx,
+    )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_and_show_window_impl(
     applications: Vec<ApplicationItem>,
     compositor: Arc<dyn Compositor>,
+    window_tracker: WindowTracker,
     windows: Vec<WindowItem>,
+    workspaces: Vec<WorkspaceItem>,
     modes: Vec<LauncherMode>,
+    output: Option<String>,
     event_tx: DaemonEventSender,
     cx: &mut App,
 ) -> anyhow::Result<LauncherWindow> {
+    let config = crate::config::config();
+
+    // Without a matching display the compositor picks the output
+    let display = output.as_deref().and_then(|name| {
+        let display = find_display(name, cx);
+        if display.is_none() {
+            warn!(
+                output = name,
+                "Output not found, letting the compositor choose"
+            );
+        }
+        display
+    });
+
     // Combine windows and applications into items list
     // Built-in actions and submenus are added by the delegate
     // Order doesn't matter here - sort_priority in delegate handles display order
-    let mut items: Vec<ListItem> = Vec::with_capacity(windows.len() + applications.len());
+    let mut items: Vec<ListItem> =
+        Vec::with_capacity(windows.len() + workspaces.len() + applications.len());
     items.extend(windows.into_iter().map(ListItem::Window));
-    items.extend(applications.into_iter().map(ListItem::Application));
+    items.extend(workspaces.into_iter().map(ListItem::Workspace));
+    items.extend(
+        apply_overrides(applications, &config.applications)
+            .into_iter()
+            .map(ListItem::Application),
+    );
 
     // Get display size based on config
-    let config = crate::config::config();
-    let (launcher_w, launcher_h) = config.get_launcher_size();
+    let (launcher_w, launcher_h) = config.get_launcher_size_on(output.as_deref());
+    crate::config::set_active_output(output.clone());
+
+    let output_size = display.as_ref().map(|display| {
+        let bounds = display.bounds();
+        (f32::from(bounds.size.width), f32::from(bounds.size.height))
+    });
+    // Placed as configured for the mode the launcher opens in
+    let placement = resolve_placement(
+        compositor.as_ref(),
+        config.placement_for(&modes[0]),
+        (launcher_w, launcher_h),
+        output_size,
+    );
 
     let display_size = if !config.enable_backdrop {
         // No backdrop - window is exactly the launcher panel size
-        size(px(launcher_w), px(launcher_h))
-    } else if let Some((w, h)) = config.window_size {
+        let (w, h) = placement.window_size((launcher_w, launcher_h), output_size);
+        size(px(w), px(h))
+    } else if let Some((w, h)) = config.get_window_size_on(output.as_deref()) {
         // User-configured window size with backdrop - ensure it's at least as large as launcher panel
         let final_w = w.max(launcher_w);
         let final_h = h.max(launcher_h);
@@ -69,6 +135,9 @@ fn create_and_show_window_impl(
             );
         }
         size(px(final_w), px(final_h))
+    } else if let Some(display) = &display {
+        // Cover the output the launcher is shown on
+        display.bounds().size
     } else if compositor.name() == "KWin" {
         // For KDE/KWin, use fixed 1920x1080
         size(px(1920.0), px(1080.0))
@@ -77,6 +146,21 @@ fn create_and_show_window_impl(
         size(px(7680.0), px(4320.0))
     };
 
+    // No backdrop - the layer surface itself is placed on the output
+    let (anchor, margin) = if config.enable_backdrop {
+        // Anchor to all edges = fullscreen overlay
+        (
+            Anchor::TOP | Anchor::BOTTOM | Anchor::LEFT | Anchor::RIGHT,
+            None,
+        )
+    } else {
+        let (top, right, bottom, left) = placement.margin;
+        (
+            placement.layer_anchor(),
+            Some((px(top), px(right), px(bottom), px(left))),
+        )
+    };
+
     let fullscreen_bounds = Bounds {
         origin: point(px(0.0), px(0.0)),
         size: display_size,
@@ -87,14 +171,15 @@ fn create_and_show_window_impl(
         titlebar: None,
         focus: true,
         show: true,
+        display_id: display.as_ref().map(|display| display.id()),
         app_id: Some("zlaunch".to_string()),
         window_background: WindowBackgroundAppearance::Transparent,
         window_decorations: Some(WindowDecorations::Server),
         kind: WindowKind::LayerShell(LayerShellOptions {
             namespace: "zlaunch".to_string(),
             layer: Layer::Overlay,
-            // Anchor to all edges = fullscreen overlay
-            anchor: Anchor::TOP | Anchor::BOTTOM | Anchor::LEFT | Anchor::RIGHT,
+            anchor,
+            margin,
             // Exclusive keyboard so typing works immediately
             keyboard_interactivity: KeyboardInteractivity::Exclusive,
             ..Default::default()
@@ -110,8 +195,18 @@ fn create_and_show_window_impl(
         let on_hide = move || {
             let _ = event_tx.send(DaemonEvent::Window(WindowEvent::RequestHide));
         };
-        let view =
-            cx.new(|cx| LauncherView::new(items, compositor.clone(), modes, on_hide, window, cx));
+        let view = cx.new(|cx| {
+            LauncherView::new(
+                items,
+                compositor.clone(),
+                window_tracker,
+                modes,
+                placement,
+                on_hide,
+                window,
+                cx,
+            )
+        });
 
         // Auto-focus the list/search input
         view.update(cx, |launcher: &mut LauncherView, cx| {
@@ -138,15 +233,55 @@ fn create_and_show_window_impl(
     })
 }
 
+/// Remove the modes showing only windows, falling back to the combined view.
+fn without_window_modes(mut modes: Vec<LauncherMode>) -> Vec<LauncherMode> {
+    modes.retain(|mode| mode.to_module() != Some(ConfigModule::Windows));
+    if modes.is_empty() {
+        modes.push(LauncherMode::Combined);
+    }
+    modes
+}
+
+/// Resolve a panel placement, asking the compositor for the pointer position if needed.
+fn resolve_placement(
+    compositor: &dyn Compositor,
+    placement: &PlacementConfig,
+    launcher_size: (f32, f32),
+    output_size: Option<(f32, f32)>,
+) -> PanelPlacement {
+    let cursor = if placement.anchor == LauncherAnchor::Cursor {
+        compositor.cursor_position().unwrap_or_else(|e| {
+            warn!(%e, "Failed to get the pointer position");
+            None
+        })
+    } else {
+        None
+    };
+
+    PanelPlacement::resolve(placement, launcher_size, output_size, cursor)
+}
+
+/// Find the display of a compositor output by name.
+///
+/// gpui identifies Wayland outputs by a UUID derived from the output name.
+fn find_display(name: &str, cx: &App) -> Option<Rc<dyn PlatformDisplay>> {
+    let uuid = Uuid::new_v5(&Uuid::NAMESPACE_DNS, name.as_bytes());
+    cx.displays()
+        .into_iter()
+        .find(|display| display.uuid().is_ok_and(|id| id == uuid))
+}
+
 pub fn close_window(handle: &WindowHandle<Root>, cx: &mut App) {
     let _ = handle.update(cx, |_root, window, _cx| {
         window.remove_window();
     });
 }
 
-/// Fetch open windows from the compositor and convert to WindowItems.
-fn fetch_windows(compositor: &dyn Compositor) -> Vec<WindowItem> {
-    match compositor.list_windows() {
+/// Get open windows from the tracker and convert to WindowItems.
+///
+/// Windows are in most recently focused order.
+fn fetch_windows(window_tracker: &WindowTracker) -> Vec<WindowItem> {
+    match window_tracker.windows() {
         Ok(windows) => {
             windows
                 .into_iter()
@@ -164,6 +299,40 @@ fn fetch_windows(compositor: &dyn Compositor) -> Vec<WindowItem> {
     }
 }
 
+/// Fetch workspaces from the compositor and convert to WorkspaceItems.
+///
+/// Each workspace lists the applications of its windows.
+fn fetch_workspaces(compositor: &dyn Compositor, windows: &[WindowItem]) -> Vec<WorkspaceItem> {
+    match compositor.list_workspaces() {
+        Ok(workspaces) => workspaces
+            .into_iter()
+            .map(|info| {
+                let mut apps: Vec<&str> = Vec::new();
+                for window in windows.iter().filter(|w| w.workspace == info.id) {
+                    if !apps.contains(&window.app_id.as_str()) {
+                        apps.push(&window.app_id);
+                    }
+                }
+                WorkspaceItem::from_workspace_info(info, &apps)
+            })
+            .collect(),
+        Err(e) => {
+            warn!(%e, "Failed to list workspaces");
+            Vec::new()
+        }
+    }
+}
+
+/// Describe windows with the titles of their workspaces, as workspace IDs
+/// need not be the numbers or names users know.
+fn describe_window_workspaces(windows: &mut [WindowItem], workspaces: &[WorkspaceItem]) {
+    for window in windows {
+        if let Some(workspace) = workspaces.iter().find(|w| w.workspace == window.workspace) {
+            window.set_workspace_title(&workspace.title);
+        }
+    }
+}
+
 /// Try to resolve an icon path for a window based on its app class.
 fn resolve_window_icon(app_class: &str) -> Option<std::path::PathBuf> {
     use crate::ui::icon::resolve_icon_path;
@@ -191,3 +360,24 @@ fn resolve_window_icon(app_class: &str) -> Option<std::path::PathBuf> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_window_modes() {
+        assert_eq!(
+            without_window_modes(vec![
+                LauncherMode::Windows,
+                LauncherMode::Applications,
+                LauncherMode::WindowsMru,
+            ]),
+            vec![LauncherMode::Applications]
+        );
+        assert_eq!(
+            without_window_modes(vec![LauncherMode::WindowsMru]),
+            vec![LauncherMode::Combined]
+        );
+    }
+}
@@ -72,6 +72,7 @@ pub enum PhosphorIcon {
     YoutubeLogo,
     Brain,
     Palette,
+    SquaresFour,
 }
 
 impl PhosphorIcon {
@@ -97,6 +98,7 @@ impl PhosphorIcon {
             Self::YoutubeLogo => "icons/youtube-logo-bold.svg",
             Self::Brain => "icons/brain-bold.svg",
             Self::Palette => "icons/palette-bold.svg",
+            Self::SquaresFour => "icons/squares-four-bold.svg",
         }
     }
 
@@ -122,6 +124,7 @@ impl PhosphorIcon {
             "youtube-logo" => Some(Self::YoutubeLogo),
             "brain" => Some(Self::Brain),
             "palette" => Some(Self::Palette),
+            "squares-four" => Some(Self::SquaresFour),
             _ => None,
         }
     }
@@ -19,6 +19,9 @@ pub enum Commands {
         /// Modes to enable (can specify multiple with commas or repeated flags)
         #[arg(short, long, value_delimiter = ',')]
         modes: Option<Vec<LauncherMode>>,
+        /// Output to show the launcher on (default: the focused output)
+        #[arg(long)]
+        output: Option<String>,
     },
     /// Hide the launcher window
     Hide,
@@ -27,16 +30,31 @@ pub enum Commands {
         /// Modes to enable (can specify multiple with commas or repeated flags)
         #[arg(short, long, value_delimiter = ',')]
         modes: Option<Vec<LauncherMode>>,
+        /// Output to show the launcher on (default: the focused output)
+        #[arg(long)]
+        output: Option<String>,
     },
     /// Quit the daemon
     Quit,
     /// Reload the daemon (fully restart the process)
     Reload,
+    /// Show the daemon status and recent launch failures
+    Status,
     /// Theme management
     Theme {
         #[command(subcommand)]
         action: Option<ThemeCommands>,
     },
+    /// Inspect or reset items learned for typed queries
+    Selections {
+        #[command(subcommand)]
+        action: Option<SelectionCommands>,
+    },
+    /// Inspect or change the environment passed to launched applications
+    Env {
+        #[command(subcommand)]
+        action: Option<EnvCommands>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -50,6 +68,37 @@ pub enum ThemeCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum SelectionCommands {
+    /// List learned query selections
+    List,
+    /// Forget learned selections
+    Reset {
+        /// Only forget the selection for this query
+        query: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EnvCommands {
+    /// List the environment variables
+    List,
+    /// Set a variable until the daemon restarts
+    Set {
+        /// Name of the variable
+        name: String,
+        /// Value of the variable
+        value: String,
+    },
+    /// Unset a variable until the daemon restarts
+    Unset {
+        /// Name of the variable
+        name: String,
+    },
+    /// Re-read the systemd user environment
+    Refresh,
+}
+
 /// Handle a client command by sending it to the running daemon.
 pub fn handle_client_command(cmd: Commands) -> Result<()> {
     if !client::is_daemon_running() {
@@ -57,14 +106,14 @@ pub fn handle_client_command(cmd: Commands) -> Result<()> {
     }
 
     match cmd {
-        Commands::Show { modes } => {
-            client::show(modes)?;
+        Commands::Show { modes, output } => {
+            client::show(modes, output)?;
         }
         Commands::Hide => {
             client::hide()?;
         }
-        Commands::Toggle { modes } => {
-            client::toggle(modes)?;
+        Commands::Toggle { modes, output } => {
+            client::toggle(modes, output)?;
         }
         Commands::Quit => {
             client::quit()?;
@@ -73,6 +122,26 @@ pub fn handle_client_command(cmd: Commands) -> Result<()> {
             client::reload()?;
             println!("Daemon is reloading...");
         }
+        Commands::Status => {
+            let status = client::status()?;
+            println!("zlaunch daemon is running (pid {})", status.pid);
+            if status.failures.is_empty() {
+                println!("No recent launch failures");
+            } else {
+                println!("Recent launch failures:");
+            }
+            for failure in status.failures.iter().rev() {
+                println!(
+                    "  {} ago: {}",
+                    format_age(failure.age_secs),
+                    failure.command
+                );
+                println!("    {}", failure.reason);
+                for line in &failure.stderr {
+                    println!("    | {}", line);
+                }
+            }
+        }
         Commands::Theme { action } => match action {
             None => {
                 // No subcommand - show current theme
@@ -96,7 +165,52 @@ pub fn handle_client_command(cmd: Commands) -> Result<()> {
                 println!("Theme set to '{}'", name);
             }
         },
+        Commands::Selections { action } => match action {
+            None | Some(SelectionCommands::List) => {
+                let selections = client::list_selections()?;
+                if selections.is_empty() {
+                    println!("No learned selections");
+                }
+                for selection in selections {
+                    println!(
+                        "  {} -> {} ({}x)",
+                        selection.query, selection.item, selection.count
+                    );
+                }
+            }
+            Some(SelectionCommands::Reset { query }) => {
+                let removed = client::reset_selections(query.as_deref())?;
+                println!("Forgot {} learned selection(s)", removed);
+            }
+        },
+        Commands::Env { action } => match action {
+            None | Some(EnvCommands::List) => {
+                for (name, value) in client::list_env()? {
+                    println!("{}={}", name, value);
+                }
+            }
+            Some(EnvCommands::Set { name, value }) => {
+                client::set_env(&name, &value)?;
+            }
+            Some(EnvCommands::Unset { name }) => {
+                client::unset_env(&name)?;
+            }
+            Some(EnvCommands::Refresh) => {
+                let changed = client::refresh_env()?;
+                println!("Refreshed environment, {} variable(s) changed", changed);
+            }
+        },
     }
 
     Ok(())
 }
+
+/// Format a duration in seconds as a short human readable age.
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
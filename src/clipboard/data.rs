@@ -1,55 +1,222 @@
 //! Clipboard history data storage and search.
+//!
+//! The history is bounded by the `[clipboard]` limits. Once the number of
+//! items, their total size or their age exceeds a limit, the oldest items are
+//! evicted first. Pinned items are never evicted.
+//!
+//! Sensitive items are only kept in memory, until they expire.
 
-use super::item::{ClipboardContent, ClipboardItem};
+use super::item::{ClipboardContent, ClipboardItem, reserve_ids};
+use super::store::HistoryStore;
+use crate::config::ClipboardConfig;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use std::collections::VecDeque;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
 
 /// Global clipboard history storage.
 static CLIPBOARD_HISTORY: RwLock<Option<VecDeque<ClipboardItem>>> = RwLock::new(None);
 
+/// On-disk history, set once the persisted items are loaded.
+///
+/// Always locked after `CLIPBOARD_HISTORY` when both are needed.
+static STORE: Mutex<Option<HistoryStore>> = Mutex::new(None);
+
+/// Seconds per day, for `max_age_days`.
+const SECS_PER_DAY: u64 = 86_400;
+
 /// Initialize the clipboard history storage.
+///
+/// With `clipboard.persist` enabled, the stored history is loaded in the
+/// background and placed behind anything copied in the meantime.
 pub fn init() {
     let mut history = CLIPBOARD_HISTORY.write().unwrap();
     if history.is_none() {
         *history = Some(VecDeque::new());
+
+        if crate::config::config().clipboard.persist {
+            thread::spawn(load_store);
+        }
+    }
+}
+
+/// Load the persisted history and start writing new items to it.
+fn load_store() {
+    let Some(dir) = HistoryStore::default_dir() else {
+        warn!("No state directory, clipboard history is not persisted");
+        return;
+    };
+    let (mut store, stored) = match HistoryStore::open(&dir) {
+        Ok(opened) => opened,
+        Err(e) => {
+            warn!("Clipboard history is not persisted: {:#}", e);
+            return;
+        }
+    };
+    info!(count = stored.len(), "Loaded persisted clipboard history");
+    reserve_ids(store.last_id());
+
+    let limits = crate::config::config().clipboard;
+    let mut guard = CLIPBOARD_HISTORY.write().unwrap();
+    let history = guard.get_or_insert_with(VecDeque::new);
+
+    // Items copied while loading are newer than the stored ones
+    for item in history.iter().rev() {
+        if let Err(e) = store.append(item) {
+            warn!("Failed to persist clipboard item: {:#}", e);
+        }
     }
+    history.extend(stored);
+
+    // The limits may have been lowered since the history was stored
+    let evicted = evict(history, &limits, SystemTime::now());
+    let mut store = Some(store);
+    persist_to(&mut store, |store| {
+        store.remove(&evicted)?;
+        store.maybe_compact(history)
+    });
+
+    *STORE.lock().unwrap() = store;
 }
 
 /// Add a new item to clipboard history.
 /// If the item is identical to the most recent one, it won't be added.
+/// Items larger than `clipboard.max_item_bytes` are ignored.
 pub fn add_item(content: ClipboardContent) {
-    let mut history = CLIPBOARD_HISTORY.write().unwrap();
-    let history = history.as_mut().expect("Clipboard history not initialized");
+    insert(content, None);
+}
+
+/// Add a sensitive item that is removed after `ttl` and never persisted.
+pub fn add_sensitive_item(content: ClipboardContent, ttl: Duration) {
+    if insert(content, Some(ttl)) {
+        thread::spawn(move || {
+            thread::sleep(ttl);
+            remove_expired();
+        });
+    }
+}
+
+/// Insert an item, returning whether it was added.
+fn insert(content: ClipboardContent, ttl: Option<Duration>) -> bool {
+    let limits = crate::config::config().clipboard;
+    let size = content.byte_size();
+    if size > limits.max_item_bytes {
+        debug!(size, "Clipboard item exceeds max_item_bytes, not recorded");
+        return false;
+    }
+
+    let mut guard = CLIPBOARD_HISTORY.write().unwrap();
+    let history = guard.as_mut().expect("Clipboard history not initialized");
 
     // Don't add duplicate consecutive items
     if let Some(last) = history.front()
         && is_same_content(&last.content, &content)
     {
-        return;
+        return false;
     }
 
-    let item = ClipboardItem::new(content);
+    let mut item = ClipboardItem::new(content);
+    item.expires = ttl.map(|ttl| item.timestamp + ttl);
+    persist(|store| store.append(&item));
     history.push_front(item);
+
+    let evicted = evict(history, &limits, SystemTime::now());
+    persist(|store| {
+        store.remove(&evicted)?;
+        store.maybe_compact(history)
+    });
+    true
+}
+
+/// Pin or unpin an item, returning whether it is now pinned.
+pub fn toggle_pinned(id: u64) -> Option<bool> {
+    let mut guard = CLIPBOARD_HISTORY.write().unwrap();
+    let item = guard.as_mut()?.iter_mut().find(|item| item.id == id)?;
+    item.pinned = !item.pinned;
+
+    let pinned = item.pinned;
+    persist(|store| store.set_pinned(id, pinned));
+    Some(pinned)
+}
+
+/// Remove expired sensitive items and items that exceeded `clipboard.max_age_days`.
+fn remove_expired() {
+    let limits = crate::config::config().clipboard;
+    let mut guard = CLIPBOARD_HISTORY.write().unwrap();
+    let Some(history) = guard.as_mut() else {
+        return;
+    };
+    let evicted = evict(history, &limits, SystemTime::now());
+    if !evicted.is_empty() {
+        persist(|store| store.remove(&evicted));
+    }
+}
+
+/// Remove the oldest unpinned items until the history is within its limits.
+///
+/// Returns the ids of the removed items.
+fn evict(
+    history: &mut VecDeque<ClipboardItem>,
+    limits: &ClipboardConfig,
+    now: SystemTime,
+) -> Vec<u64> {
+    let max_age = limits
+        .max_age_days
+        .map(|days| Duration::from_secs(u64::from(days) * SECS_PER_DAY));
+    let mut count = history.len();
+    let mut bytes: usize = history.iter().map(ClipboardItem::byte_size).sum();
+    let mut evicted = Vec::new();
+
+    // The oldest items are at the back
+    for index in (0..history.len()).rev() {
+        let item = &history[index];
+        if item.pinned {
+            continue;
+        }
+        let expired = item.expires.is_some_and(|expires| expires <= now)
+            || max_age.is_some_and(|max_age| {
+                now.duration_since(item.timestamp)
+                    .is_ok_and(|age| age > max_age)
+            });
+
+        if expired || count > limits.max_entries || bytes > limits.max_total_bytes {
+            count -= 1;
+            bytes -= item.byte_size();
+            evicted.push(item.id);
+            history.remove(index);
+        }
+    }
+
+    if !evicted.is_empty() {
+        debug!(count = evicted.len(), "Evicted clipboard items");
+    }
+    evicted
+}
+
+/// Apply a change to the on-disk history, if it is persisted.
+fn persist(change: impl FnOnce(&mut HistoryStore) -> anyhow::Result<()>) {
+    persist_to(&mut STORE.lock().unwrap(), change);
+}
+
+fn persist_to(
+    store: &mut Option<HistoryStore>,
+    change: impl FnOnce(&mut HistoryStore) -> anyhow::Result<()>,
+) {
+    if let Some(store) = store.as_mut()
+        && let Err(e) = change(store)
+    {
+        warn!("Failed to update persisted clipboard history: {:#}", e);
+    }
 }
 
 /// Check if two clipboard contents are the same.
 fn is_same_content(a: &ClipboardContent, b: &ClipboardContent) -> bool {
     match (a, b) {
         (ClipboardContent::Text(a), ClipboardContent::Text(b)) => a == b,
-        (
-            ClipboardContent::Image {
-                width: w1,
-                height: h1,
-                rgba_bytes: b1,
-            },
-            ClipboardContent::Image {
-                width: w2,
-                height: h2,
-                rgba_bytes: b2,
-            },
-        ) => w1 == w2 && h1 == h2 && b1 == b2,
+        (ClipboardContent::Image(a), ClipboardContent::Image(b)) => a == b,
         (ClipboardContent::FilePaths(a), ClipboardContent::FilePaths(b)) => a == b,
         (
             ClipboardContent::RichText {
@@ -67,6 +234,8 @@ fn is_same_content(a: &ClipboardContent, b: &ClipboardContent) -> bool {
 
 /// Get all clipboard items, optionally filtered by a search query.
 pub fn search_items(query: &str) -> Vec<ClipboardItem> {
+    remove_expired();
+
     let history = CLIPBOARD_HISTORY.read().unwrap();
     let history = history.as_ref().expect("Clipboard history not initialized");
 
@@ -80,7 +249,7 @@ pub fn search_items(query: &str) -> Vec<ClipboardItem> {
         .filter_map(|item| {
             let search_text = match &item.content {
                 ClipboardContent::Text(text) => text.clone(),
-                ClipboardContent::Image { .. } => "image".to_string(),
+                ClipboardContent::Image(_) => "image".to_string(),
                 ClipboardContent::FilePaths(paths) => paths
                     .iter()
                     .filter_map(|p| p.to_str())
@@ -112,4 +281,112 @@ pub fn clear_history() {
     if let Some(h) = history.as_mut() {
         h.clear();
     }
+
+    if let Some(store) = STORE.lock().unwrap().as_mut()
+        && let Err(e) = store.clear()
+    {
+        warn!("Failed to clear persisted clipboard history: {:#}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn history(sizes: &[usize]) -> VecDeque<ClipboardItem> {
+        // Newest first, one minute apart
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| ClipboardItem {
+                id: (sizes.len() - i) as u64,
+                content: ClipboardContent::Text("x".repeat(size)),
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(NOW - i as u64 * 60),
+                pinned: false,
+                expires: None,
+            })
+            .collect()
+    }
+
+    fn ids(history: &VecDeque<ClipboardItem>) -> Vec<u64> {
+        history.iter().map(|item| item.id).collect()
+    }
+
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(NOW)
+    }
+
+    #[test]
+    fn test_evict_within_limits() {
+        let mut items = history(&[10, 10, 10]);
+        let evicted = evict(&mut items, &ClipboardConfig::default(), now());
+        assert!(evicted.is_empty());
+        assert_eq!(ids(&items), [3, 2, 1]);
+    }
+
+    #[test]
+    fn test_evict_max_entries() {
+        let mut items = history(&[10, 10, 10, 10]);
+        let limits = ClipboardConfig {
+            max_entries: 2,
+            ..ClipboardConfig::default()
+        };
+        assert_eq!(evict(&mut items, &limits, now()), [1, 2]);
+        assert_eq!(ids(&items), [4, 3]);
+    }
+
+    #[test]
+    fn test_evict_max_total_bytes() {
+        let mut items = history(&[40, 30, 20, 10]);
+        let limits = ClipboardConfig {
+            max_total_bytes: 75,
+            ..ClipboardConfig::default()
+        };
+        assert_eq!(evict(&mut items, &limits, now()), [1, 2]);
+        assert_eq!(ids(&items), [4, 3]);
+    }
+
+    #[test]
+    fn test_evict_max_age() {
+        let mut items = history(&[10; 4]);
+        items[2].timestamp = now() - Duration::from_secs(2 * SECS_PER_DAY);
+        let limits = ClipboardConfig {
+            max_age_days: Some(1),
+            ..ClipboardConfig::default()
+        };
+        assert_eq!(evict(&mut items, &limits, now()), [2]);
+        assert_eq!(ids(&items), [4, 3, 1]);
+    }
+
+    #[test]
+    fn test_evict_sensitive() {
+        let mut items = history(&[10; 3]);
+        items[0].expires = Some(now() + Duration::from_secs(30));
+        items[1].expires = Some(now());
+        assert_eq!(evict(&mut items, &ClipboardConfig::default(), now()), [2]);
+        assert_eq!(ids(&items), [3, 1]);
+    }
+
+    #[test]
+    fn test_evict_skips_pinned() {
+        let mut items = history(&[10; 4]);
+        items[3].pinned = true;
+        let limits = ClipboardConfig {
+            max_entries: 2,
+            ..ClipboardConfig::default()
+        };
+        assert_eq!(evict(&mut items, &limits, now()), [2, 3]);
+        assert_eq!(ids(&items), [4, 1]);
+
+        // Pinned items stay even beyond the limits
+        items[0].pinned = true;
+        let limits = ClipboardConfig {
+            max_entries: 1,
+            ..ClipboardConfig::default()
+        };
+        assert!(evict(&mut items, &limits, now()).is_empty());
+        assert_eq!(ids(&items), [4, 1]);
+    }
 }
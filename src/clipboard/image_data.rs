@@ -0,0 +1,163 @@
+//! Compressed clipboard images.
+//!
+//! Copied images arrive as raw RGBA pixels, which take tens of megabytes for
+//! a single 4K screenshot. They are kept PNG-encoded instead, along with a
+//! downscaled thumbnail for display, and only decoded again when copied.
+
+use anyhow::{Context, Result};
+use image::{ImageFormat, RgbaImage, imageops};
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// Longest side of image thumbnails in pixels.
+const THUMBNAIL_SIZE: usize = 512;
+
+/// An image in the clipboard history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    /// Full image, PNG-encoded.
+    png: Arc<[u8]>,
+    /// Downscaled image, PNG-encoded. Shares `png` for small images.
+    thumbnail: Arc<[u8]>,
+}
+
+impl ClipboardImage {
+    /// Compress raw RGBA pixels.
+    pub fn from_rgba(width: usize, height: usize, rgba_bytes: &[u8]) -> Result<Self> {
+        let image = RgbaImage::from_raw(width as u32, height as u32, rgba_bytes.to_vec())
+            .context("Image data does not match its size")?;
+        Self::from_image(&image)
+    }
+
+    /// Compress an image copied in an encoded format.
+    pub fn decode(data: &[u8], format: ImageFormat) -> Result<Self> {
+        let image = image::load_from_memory_with_format(data, format)?.into_rgba8();
+        Self::from_image(&image)
+    }
+
+    fn from_image(image: &RgbaImage) -> Result<Self> {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let png: Arc<[u8]> = encode_png(image)?.into();
+
+        let thumbnail = match thumbnail_size(width, height) {
+            Some((w, h)) => encode_png(&imageops::thumbnail(image, w, h))?.into(),
+            None => png.clone(),
+        };
+
+        Ok(Self {
+            width,
+            height,
+            png,
+            thumbnail,
+        })
+    }
+
+    /// Create an image from already encoded PNG data.
+    ///
+    /// Without a thumbnail, the full image is displayed.
+    pub fn from_png(width: usize, height: usize, png: Vec<u8>, thumbnail: Option<Vec<u8>>) -> Self {
+        let png: Arc<[u8]> = png.into();
+        let thumbnail = thumbnail.map_or_else(|| png.clone(), Into::into);
+        Self {
+            width,
+            height,
+            png,
+            thumbnail,
+        }
+    }
+
+    /// The full image, PNG-encoded.
+    pub fn png(&self) -> &[u8] {
+        &self.png
+    }
+
+    /// The image for display, PNG-encoded.
+    pub fn thumbnail(&self) -> &[u8] {
+        &self.thumbnail
+    }
+
+    /// Whether the image has a thumbnail separate from the full image.
+    pub fn has_thumbnail(&self) -> bool {
+        !Arc::ptr_eq(&self.png, &self.thumbnail)
+    }
+
+    /// Decode the full image to raw RGBA pixels.
+    pub fn to_rgba(&self) -> Result<Vec<u8>> {
+        let image = image::load_from_memory_with_format(&self.png, ImageFormat::Png)?;
+        Ok(image.into_rgba8().into_raw())
+    }
+
+    /// Memory used by the encoded image data.
+    pub fn byte_size(&self) -> usize {
+        if self.has_thumbnail() {
+            self.png.len() + self.thumbnail.len()
+        } else {
+            self.png.len()
+        }
+    }
+}
+
+/// Size of the thumbnail of an image, or `None` if it is small enough.
+fn thumbnail_size(width: usize, height: usize) -> Option<(u32, u32)> {
+    let longest = width.max(height);
+    if longest <= THUMBNAIL_SIZE {
+        return None;
+    }
+    let scale = |side: usize| (side * THUMBNAIL_SIZE / longest).max(1) as u32;
+    Some((scale(width), scale(height)))
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_size() {
+        assert_eq!(thumbnail_size(512, 300), None);
+        assert_eq!(thumbnail_size(3840, 2160), Some((512, 288)));
+        assert_eq!(thumbnail_size(100, 4000), Some((12, 512)));
+        assert_eq!(thumbnail_size(10_000, 1), Some((512, 1)));
+    }
+
+    #[test]
+    fn test_small_image_roundtrip() {
+        let rgba = vec![255, 0, 0, 255, 0, 0, 255, 128];
+        let image = ClipboardImage::from_rgba(2, 1, &rgba).unwrap();
+        assert!(!image.has_thumbnail());
+        assert_eq!(image.byte_size(), image.png().len());
+        assert_eq!(image.to_rgba().unwrap(), rgba);
+    }
+
+    #[test]
+    fn test_large_image_thumbnail() {
+        let rgba = vec![200; 1024 * 4 * 4];
+        let image = ClipboardImage::from_rgba(1024, 4, &rgba).unwrap();
+        assert!(image.has_thumbnail());
+
+        let thumbnail = image::load_from_memory(image.thumbnail()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (512, 2));
+        assert_eq!(image.to_rgba().unwrap(), rgba);
+    }
+
+    #[test]
+    fn test_decode() {
+        let rgba = vec![10, 20, 30, 255, 40, 50, 60, 255];
+        let png = ClipboardImage::from_rgba(1, 2, &rgba).unwrap();
+        let image = ClipboardImage::decode(png.png(), ImageFormat::Png).unwrap();
+        assert_eq!((image.width, image.height), (1, 2));
+        assert_eq!(image.to_rgba().unwrap(), rgba);
+    }
+
+    #[test]
+    fn test_mismatched_size_is_rejected() {
+        assert!(ClipboardImage::from_rgba(2, 2, &[0; 4]).is_err());
+    }
+}
@@ -1,13 +1,24 @@
 //! Clipboard item data structures.
 
+use super::image_data::ClipboardImage;
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most recently assigned item id.
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
 
 /// Represents a single clipboard history entry.
 #[derive(Clone, Debug)]
 pub struct ClipboardItem {
+    /// Unique id, increasing with the copy time.
+    pub id: u64,
     pub content: ClipboardContent,
     pub timestamp: SystemTime,
+    /// Pinned items are never evicted from the history.
+    pub pinned: bool,
+    /// When a sensitive item is removed. Such items are never persisted.
+    pub expires: Option<SystemTime>,
 }
 
 /// The content type of a clipboard item.
@@ -15,24 +26,36 @@ pub struct ClipboardItem {
 pub enum ClipboardContent {
     /// Plain text content
     Text(String),
-    /// Image data with dimensions (raw RGBA pixel data)
-    Image {
-        width: usize,
-        height: usize,
-        rgba_bytes: Vec<u8>,
-    },
+    /// Image data, compressed with a thumbnail
+    Image(ClipboardImage),
     /// File path(s) copied from file manager
     FilePaths(Vec<PathBuf>),
     /// Rich text / HTML content
     RichText { plain: String, html: String },
 }
 
+impl ClipboardContent {
+    /// Memory used by the content, in bytes.
+    pub fn byte_size(&self) -> usize {
+        match self {
+            Self::Text(text) => text.len(),
+            Self::Image(image) => image.byte_size(),
+            Self::FilePaths(paths) => paths.iter().map(|p| p.as_os_str().len()).sum(),
+            Self::RichText { plain, html } => plain.len() + html.len(),
+        }
+    }
+}
+
 impl ClipboardItem {
     /// Create a new clipboard item with the current timestamp.
     pub fn new(content: ClipboardContent) -> Self {
+        let timestamp = SystemTime::now();
         Self {
+            id: next_id(timestamp),
             content,
-            timestamp: SystemTime::now(),
+            timestamp,
+            pinned: false,
+            expires: None,
         }
     }
 
@@ -45,7 +68,7 @@ impl ClipboardItem {
                 let first_line = text.lines().next().unwrap_or("");
                 truncate_preview_line(first_line, MAX_LENGTH)
             }
-            ClipboardContent::Image { .. } => "[Image]".to_string(),
+            ClipboardContent::Image(_) => "[Image]".to_string(),
             ClipboardContent::FilePaths(paths) => {
                 if paths.len() == 1 {
                     paths[0]
@@ -68,7 +91,7 @@ impl ClipboardItem {
     pub fn full_content(&self) -> String {
         match &self.content {
             ClipboardContent::Text(text) => text.clone(),
-            ClipboardContent::Image { .. } => "[Image preview]".to_string(),
+            ClipboardContent::Image(_) => "[Image preview]".to_string(),
             ClipboardContent::FilePaths(paths) => paths
                 .iter()
                 .filter_map(|p| p.to_str())
@@ -78,6 +101,11 @@ impl ClipboardItem {
         }
     }
 
+    /// Memory used by the content, in bytes.
+    pub fn byte_size(&self) -> usize {
+        self.content.byte_size()
+    }
+
     /// Check if this item is a text file that can be previewed.
     pub fn is_previewable_file(&self) -> bool {
         if let ClipboardContent::FilePaths(paths) = &self.content
@@ -105,6 +133,31 @@ impl ClipboardItem {
     }
 }
 
+/// Make ids assigned from now on higher than `id`.
+///
+/// Used with the highest stored id, so items copied after a restart sort
+/// after the restored ones even if the clock went back.
+pub(crate) fn reserve_ids(id: u64) {
+    LAST_ID.fetch_max(id, Ordering::Relaxed);
+}
+
+/// Get an id for an item copied at `timestamp`.
+///
+/// Ids are based on the time in milliseconds, so items restored from disk
+/// keep ids lower than the ones copied after a restart.
+fn next_id(timestamp: SystemTime) -> u64 {
+    let millis = timestamp
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let previous = LAST_ID
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(millis.max(last + 1))
+        })
+        .unwrap_or_else(|last| last);
+    millis.max(previous + 1)
+}
+
 /// Truncate wihtout splitting emojis
 fn truncate_preview_line(line: &str, max: usize) -> String {
     let truncated: String = line.chars().take(max).collect();
@@ -0,0 +1,179 @@
+//! Choosing among the MIME types offered for a copy.
+//!
+//! Applications offer the same content in several representations. Images are
+//! preferred, then file lists, then text, which is kept with its HTML markup
+//! when that is offered too.
+
+use image::ImageFormat;
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
+
+/// HTML markup, recorded alongside the plain text.
+pub const HTML: &str = "text/html";
+
+/// File lists, as offered by GNOME and most other file managers.
+const GNOME_COPIED_FILES: &str = "x-special/gnome-copied-files";
+const URI_LIST: &str = "text/uri-list";
+
+/// Plain text types, most preferred first.
+const TEXT_TYPES: &[&str] = &[
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "STRING",
+    "TEXT",
+];
+
+/// The best offered image type, preferring PNG.
+pub fn image_type(mime_types: &[String]) -> Option<(&str, ImageFormat)> {
+    let decodable: Vec<(&str, ImageFormat)> = mime_types
+        .iter()
+        .filter_map(|mime_type| {
+            let format = ImageFormat::from_mime_type(mime_type)?;
+            format
+                .reading_enabled()
+                .then_some((mime_type.as_str(), format))
+        })
+        .collect();
+    decodable
+        .iter()
+        .find(|(_, format)| *format == ImageFormat::Png)
+        .or(decodable.first())
+        .copied()
+}
+
+/// The offered file list type, if any.
+pub fn file_list_type(mime_types: &[String]) -> Option<&str> {
+    [GNOME_COPIED_FILES, URI_LIST]
+        .into_iter()
+        .find(|wanted| mime_types.iter().any(|m| m == wanted))
+}
+
+/// The best offered plain text type, as spelled in the offer.
+pub fn text_type(mime_types: &[String]) -> Option<&str> {
+    TEXT_TYPES
+        .iter()
+        .find_map(|wanted| mime_types.iter().find(|m| m.eq_ignore_ascii_case(wanted)))
+        .map(String::as_str)
+}
+
+/// Parse a file list received as `mime_type`.
+///
+/// Returns `None` unless every entry is a local file, so that lists of web
+/// links are recorded as text instead.
+pub fn parse_file_list(mime_type: &str, data: &[u8]) -> Option<Vec<PathBuf>> {
+    let data = std::str::from_utf8(data).ok()?;
+    let mut lines = data
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    // GNOME lists start with the operation
+    if mime_type == GNOME_COPIED_FILES {
+        lines.next_if(|line| *line == "copy" || *line == "cut");
+    }
+
+    let paths = lines.map(file_uri_to_path).collect::<Option<Vec<_>>>()?;
+    (!paths.is_empty()).then_some(paths)
+}
+
+/// Convert a local `file://` URI to a path.
+fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = match rest.find('/')? {
+        0 => rest,
+        host_end if &rest[..host_end] == "localhost" => &rest[host_end..],
+        _ => return None,
+    };
+    Some(PathBuf::from(OsString::from_vec(percent_decode(path)?)))
+}
+
+fn percent_decode(text: &str) -> Option<Vec<u8>> {
+    let bytes = text.as_bytes();
+    let hex = |byte: Option<&u8>| char::from(*byte?).to_digit(16);
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let value = hex(bytes.get(i + 1))? * 16 + hex(bytes.get(i + 2))?;
+            decoded.push(value as u8);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(mime_types: &[&str]) -> Vec<String> {
+        mime_types.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_image_type() {
+        let offered = types(&["text/html", "image/jpeg", "image/png"]);
+        assert_eq!(image_type(&offered), Some(("image/png", ImageFormat::Png)));
+
+        let offered = types(&["text/html", "image/jpeg"]);
+        assert_eq!(
+            image_type(&offered),
+            Some(("image/jpeg", ImageFormat::Jpeg))
+        );
+
+        assert_eq!(image_type(&types(&["text/plain", "image/x-unknown"])), None);
+    }
+
+    #[test]
+    fn test_text_type() {
+        let offered = types(&["TEXT", "text/plain", "text/plain;charset=UTF-8"]);
+        assert_eq!(text_type(&offered), Some("text/plain;charset=UTF-8"));
+        assert_eq!(text_type(&types(&["STRING"])), Some("STRING"));
+        assert_eq!(text_type(&types(&["image/png"])), None);
+    }
+
+    #[test]
+    fn test_file_list_type() {
+        let offered = types(&["text/uri-list", GNOME_COPIED_FILES, "text/plain"]);
+        assert_eq!(file_list_type(&offered), Some(GNOME_COPIED_FILES));
+        assert_eq!(file_list_type(&types(&["text/plain"])), None);
+    }
+
+    #[test]
+    fn test_parse_uri_list() {
+        let data = b"# comment\r\nfile:///home/user/My%20File.txt\r\nfile://localhost/tmp/a\r\n";
+        assert_eq!(
+            parse_file_list(URI_LIST, data),
+            Some(vec![
+                PathBuf::from("/home/user/My File.txt"),
+                PathBuf::from("/tmp/a"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_gnome_copied_files() {
+        let data = b"cut\nfile:///home/user/a.png\nfile:///home/user/%C3%A9.txt";
+        assert_eq!(
+            parse_file_list(GNOME_COPIED_FILES, data),
+            Some(vec![
+                PathBuf::from("/home/user/a.png"),
+                PathBuf::from("/home/user/é.txt"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_non_file_uris() {
+        assert_eq!(parse_file_list(URI_LIST, b"https://example.com/\n"), None);
+        assert_eq!(parse_file_list(URI_LIST, b"file://host/share/a\n"), None);
+        assert_eq!(parse_file_list(URI_LIST, b"file:///bad%2"), None);
+        assert_eq!(parse_file_list(URI_LIST, b"# only a comment\n"), None);
+    }
+}
@@ -2,8 +2,13 @@
 
 mod copy;
 pub mod data;
+mod filter;
+mod image_data;
 pub mod item;
+mod mime;
 pub mod monitor;
+mod store;
 
 pub use copy::{copy_image_to_clipboard, copy_to_clipboard};
+pub use image_data::ClipboardImage;
 pub use item::{ClipboardContent, ClipboardItem};
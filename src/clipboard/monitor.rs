@@ -1,15 +1,23 @@
 //! Clipboard monitoring using Wayland data-control protocol.
 
 use super::data;
+use super::filter::{self, ClipboardFilter, Sensitivity};
+use super::image_data::ClipboardImage;
 use super::item::ClipboardContent;
-use arboard::Clipboard;
+use super::mime;
+use crate::compositor::WindowTracker;
+use crate::config::ClipboardConfig;
+use std::collections::HashMap;
+use std::io::{self, PipeReader, Read};
+use std::os::fd::{AsFd, AsRawFd};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::Duration;
 use tracing::{debug, error, info};
+use wayland_client::backend::ObjectId;
 use wayland_client::protocol::{wl_registry, wl_seat};
-use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
 use wayland_protocols_wlr::data_control::v1::client::{
     zwlr_data_control_device_v1, zwlr_data_control_manager_v1, zwlr_data_control_offer_v1,
     zwlr_data_control_source_v1,
@@ -20,18 +28,28 @@ struct ClipboardMonitorState {
     manager: Option<zwlr_data_control_manager_v1::ZwlrDataControlManagerV1>,
     seat: Option<wl_seat::WlSeat>,
     device: Option<zwlr_data_control_device_v1::ZwlrDataControlDeviceV1>,
+    /// MIME types of the current data offers
+    offers: HashMap<ObjectId, Vec<String>>,
+    /// Open windows, to find the focused application for `clipboard.ignore_apps`
+    window_tracker: WindowTracker,
     running: Arc<AtomicBool>,
 }
 
+/// How long to wait for the source application to send offered data.
+const RECEIVE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Size limit for the password manager hint.
+const MAX_HINT_BYTES: usize = 64;
+
 /// Start monitoring clipboard changes in a background thread.
-pub fn start_monitor() -> Arc<AtomicBool> {
+pub fn start_monitor(window_tracker: WindowTracker) -> Arc<AtomicBool> {
     let running = Arc::new(AtomicBool::new(true));
     let running_clone = running.clone();
 
     thread::spawn(move || {
         info!("Starting clipboard monitor");
 
-        if let Err(e) = run_monitor(running_clone) {
+        if let Err(e) = run_monitor(window_tracker, running_clone) {
             error!("Clipboard monitor error: {}", e);
         }
     });
@@ -39,7 +57,10 @@ pub fn start_monitor() -> Arc<AtomicBool> {
     running
 }
 
-fn run_monitor(running: Arc<AtomicBool>) -> Result<(), Box<dyn std::error::Error>> {
+fn run_monitor(
+    window_tracker: WindowTracker,
+    running: Arc<AtomicBool>,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Connect to Wayland
     let conn = Connection::connect_to_env()?;
     let display = conn.display();
@@ -53,6 +74,8 @@ fn run_monitor(running: Arc<AtomicBool>) -> Result<(), Box<dyn std::error::Error
         manager: None,
         seat: None,
         device: None,
+        offers: HashMap::new(),
+        window_tracker,
         running,
     };
 
@@ -152,25 +175,29 @@ impl Dispatch<zwlr_data_control_manager_v1::ZwlrDataControlManagerV1, ()>
 
 impl Dispatch<zwlr_data_control_device_v1::ZwlrDataControlDeviceV1, ()> for ClipboardMonitorState {
     fn event(
-        _: &mut Self,
+        state: &mut Self,
         _: &zwlr_data_control_device_v1::ZwlrDataControlDeviceV1,
         event: zwlr_data_control_device_v1::Event,
         _: &(),
-        _: &Connection,
+        conn: &Connection,
         _: &QueueHandle<Self>,
     ) {
         match event {
             zwlr_data_control_device_v1::Event::Selection { id } => {
-                if id.is_some() {
+                if let Some(offer) = id {
                     debug!("Clipboard selection changed");
+                    let mime_types = state.offers.remove(&offer.id()).unwrap_or_default();
                     // Clipboard changed, read the new content
-                    if let Err(e) = read_clipboard_content() {
-                        error!("Failed to read clipboard: {}", e);
-                    }
+                    state.handle_selection(conn, &offer, &mime_types);
+                    offer.destroy();
                 }
             }
-            zwlr_data_control_device_v1::Event::PrimarySelection { .. } => {
+            zwlr_data_control_device_v1::Event::PrimarySelection { id } => {
                 // Ignore primary selection for now
+                if let Some(offer) = id {
+                    state.offers.remove(&offer.id());
+                    offer.destroy();
+                }
             }
             _ => {}
         }
@@ -191,13 +218,16 @@ impl Dispatch<zwlr_data_control_device_v1::ZwlrDataControlDeviceV1, ()> for Clip
 
 impl Dispatch<zwlr_data_control_offer_v1::ZwlrDataControlOfferV1, ()> for ClipboardMonitorState {
     fn event(
-        _: &mut Self,
-        _: &zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
-        _: zwlr_data_control_offer_v1::Event,
+        state: &mut Self,
+        offer: &zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+        event: zwlr_data_control_offer_v1::Event,
         _: &(),
         _: &Connection,
         _: &QueueHandle<Self>,
     ) {
+        if let zwlr_data_control_offer_v1::Event::Offer { mime_type } = event {
+            state.offers.entry(offer.id()).or_default().push(mime_type);
+        }
     }
 }
 
@@ -213,40 +243,204 @@ impl Dispatch<zwlr_data_control_source_v1::ZwlrDataControlSourceV1, ()> for Clip
     }
 }
 
-/// Read the current clipboard content and add it to history.
-fn read_clipboard_content() -> Result<(), Box<dyn std::error::Error>> {
-    // Small delay to let clipboard settle
-    thread::sleep(Duration::from_millis(50));
+impl ClipboardMonitorState {
+    /// Record a new selection unless it is excluded from the history.
+    fn handle_selection(
+        &self,
+        conn: &Connection,
+        offer: &zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+        mime_types: &[String],
+    ) {
+        if is_marked_secret(conn, offer, mime_types) {
+            debug!("Clipboard content is marked as secret, not recorded");
+            return;
+        }
+
+        let config = crate::config::config().clipboard;
+        let filter = ClipboardFilter::new(&config);
+        if !config.ignore_apps.is_empty()
+            && let Some(app) = self.focused_app()
+            && filter.ignores_app(&app)
+        {
+            debug!(
+                app,
+                "Clipboard content copied from ignored app, not recorded"
+            );
+            return;
+        }
+
+        if let Err(e) = read_offer(conn, offer, mime_types, &filter, &config) {
+            match e.downcast_ref::<io::Error>() {
+                Some(e) if e.kind() == io::ErrorKind::FileTooLarge => {
+                    debug!("Clipboard item exceeds max_item_bytes, not recorded");
+                }
+                _ => error!("Failed to read clipboard: {}", e),
+            }
+        }
+    }
 
-    let mut clipboard = Clipboard::new()?;
+    /// Application ID of the focused window, which made the copy.
+    ///
+    /// The data-control protocol does not identify the source client.
+    fn focused_app(&self) -> Option<String> {
+        let windows = self.window_tracker.windows().ok()?;
+        windows
+            .into_iter()
+            .find(|window| window.focused)
+            .map(|window| window.class)
+    }
+}
 
-    // Try to get image first - browsers often put both image data and HTML markup
-    // on the clipboard, and we prefer the actual image over the HTML representation
-    if let Ok(image) = clipboard.get_image()
-        && !image.bytes.is_empty()
+/// Check if the source application marked the content as secret.
+///
+/// A password manager hint that cannot be read counts as secret.
+fn is_marked_secret(
+    conn: &Connection,
+    offer: &zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+    mime_types: &[String],
+) -> bool {
+    if mime_types.iter().any(|m| m == filter::CONCEALED_TYPE) {
+        return true;
+    }
+    if !mime_types
+        .iter()
+        .any(|m| m == filter::PASSWORD_MANAGER_HINT)
     {
+        return false;
+    }
+
+    match receive(conn, offer, filter::PASSWORD_MANAGER_HINT, MAX_HINT_BYTES) {
+        Ok(hint) => hint.trim_ascii() == b"secret",
+        Err(e) => {
+            debug!("Failed to read password manager hint: {}", e);
+            true
+        }
+    }
+}
+
+/// Read the selection in its best offered representation and add it to history.
+fn read_offer(
+    conn: &Connection,
+    offer: &zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+    mime_types: &[String],
+    filter: &ClipboardFilter,
+    config: &ClipboardConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let limit = config.max_item_bytes;
+
+    // Images first - browsers often offer both image data and HTML markup
+    // for a copied image, and we prefer the actual image
+    if let Some((mime_type, format)) = mime::image_type(mime_types) {
+        let image = ClipboardImage::decode(&receive(conn, offer, mime_type, limit)?, format)?;
         debug!(
-            "Adding image to clipboard history: {}×{} ({} bytes)",
-            image.width,
-            image.height,
-            image.bytes.len()
+            "Adding image to clipboard history: {}×{}",
+            image.width, image.height
         );
-        data::add_item(ClipboardContent::Image {
-            width: image.width,
-            height: image.height,
-            rgba_bytes: image.bytes.to_vec(),
-        });
+        data::add_item(ClipboardContent::Image(image));
         return Ok(());
     }
 
-    // Try to get text
-    if let Ok(text) = clipboard.get_text()
-        && !text.is_empty()
+    if let Some(mime_type) = mime::file_list_type(mime_types)
+        && let Some(paths) =
+            mime::parse_file_list(mime_type, &receive(conn, offer, mime_type, limit)?)
     {
-        debug!("Adding text to clipboard history: {} chars", text.len());
-        data::add_item(ClipboardContent::Text(text));
+        debug!("Adding {} files to clipboard history", paths.len());
+        data::add_item(ClipboardContent::FilePaths(paths));
+        return Ok(());
+    }
+
+    let Some(text_type) = mime::text_type(mime_types) else {
+        return Ok(());
+    };
+    let text = String::from_utf8_lossy(&receive(conn, offer, text_type, limit)?).into_owned();
+    if text.is_empty() {
         return Ok(());
     }
 
+    let sensitivity = filter.classify(&text);
+    if sensitivity == Sensitivity::Ignored {
+        debug!("Clipboard text matches an ignore rule, not recorded");
+        return Ok(());
+    }
+
+    // Keep the markup when offered, falling back to plain text
+    let html = if mime_types.iter().any(|m| m == mime::HTML) {
+        match receive(conn, offer, mime::HTML, limit.saturating_sub(text.len())) {
+            Ok(html) => Some(String::from_utf8_lossy(&html).into_owned()),
+            Err(e) => {
+                debug!("Failed to read HTML from clipboard: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let content = match html {
+        Some(html) if !html.is_empty() => ClipboardContent::RichText { plain: text, html },
+        _ => ClipboardContent::Text(text),
+    };
+
+    if sensitivity == Sensitivity::Sensitive {
+        debug!("Adding sensitive text to clipboard history");
+        let ttl = Duration::from_secs(config.sensitive_expiry_secs);
+        data::add_sensitive_item(content, ttl);
+    } else {
+        debug!(
+            "Adding text to clipboard history: {} bytes",
+            content.byte_size()
+        );
+        data::add_item(content);
+    }
     Ok(())
 }
+
+/// Read the data of one offered MIME type from the source application.
+///
+/// Fails with `FileTooLarge` once more than `limit` bytes are sent.
+fn receive(
+    conn: &Connection,
+    offer: &zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+    mime_type: &str,
+    limit: usize,
+) -> io::Result<Vec<u8>> {
+    let (mut reader, writer) = io::pipe()?;
+    offer.receive(mime_type.to_string(), writer.as_fd());
+    // Only the source application may hold the write end, or reading never ends
+    drop(writer);
+    conn.flush().map_err(io::Error::other)?;
+    read_with_timeout(&mut reader, RECEIVE_TIMEOUT, limit)
+}
+
+/// Read a pipe to its end, failing if the writer stalls for `timeout`.
+fn read_with_timeout(
+    reader: &mut PipeReader,
+    timeout: Duration,
+    limit: usize,
+) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 8192];
+
+    loop {
+        let mut pollfd = libc::pollfd {
+            fd: reader.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pollfd is a single valid entry that outlives the call
+        let ready = unsafe { libc::poll(&mut pollfd, 1, timeout.as_millis() as libc::c_int) };
+        match ready {
+            0 => return Err(io::ErrorKind::TimedOut.into()),
+            n if n < 0 => {
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            _ => match reader.read(&mut buf)? {
+                0 => return Ok(data),
+                n if data.len() + n > limit => return Err(io::ErrorKind::FileTooLarge.into()),
+                n => data.extend_from_slice(&buf[..n]),
+            },
+        }
+    }
+}
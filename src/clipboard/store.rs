@@ -0,0 +1,729 @@
+//! On-disk clipboard history.
+//!
+//! The history lives in `~/.local/state/zlaunch/clipboard/`. `history.jsonl`
+//! starts with a header line holding the format version, followed by one
+//! record per line that is appended as items are copied, pinned or evicted.
+//! Images are stored as PNG files in `images/`, named after the entry id,
+//! with a `.thumb.png` next to them for large images.
+//!
+//! The log is compacted when it is loaded, and while running once most of
+//! its records describe items that are gone.
+
+use super::image_data::ClipboardImage;
+use super::item::{ClipboardContent, ClipboardItem};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fs::{self, DirBuilder, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Current history log format version.
+///
+/// Version 2 added pinned items, removals and image thumbnails.
+const STORE_VERSION: u32 = 2;
+
+/// Name of the history log inside the store directory.
+const LOG_FILE: &str = "history.jsonl";
+
+/// Name of the image directory inside the store directory.
+const IMAGE_DIR: &str = "images";
+
+/// Records beyond twice the live items tolerated before compacting.
+const COMPACT_SLACK: usize = 64;
+
+/// First line of the history log.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    version: u32,
+}
+
+/// A line of the history log.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Record {
+    /// An item was copied.
+    Add(StoredItem),
+    /// Items were evicted.
+    Remove { ids: Vec<u64> },
+    /// An item was pinned or unpinned.
+    Pin { id: u64, pinned: bool },
+    /// The history was cleared.
+    Clear,
+}
+
+/// A clipboard item as stored in the log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct StoredItem {
+    id: u64,
+    /// Unix timestamp in milliseconds.
+    timestamp: u64,
+    content: StoredContent,
+    #[serde(default)]
+    pinned: bool,
+}
+
+/// Clipboard content as stored in the log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum StoredContent {
+    Text(String),
+    /// The pixels are stored in `images/<id>.png`.
+    Image {
+        width: usize,
+        height: usize,
+        /// Whether `images/<id>.thumb.png` exists.
+        #[serde(default)]
+        thumbnail: bool,
+    },
+    FilePaths(Vec<PathBuf>),
+    RichText {
+        plain: String,
+        html: String,
+    },
+}
+
+impl StoredItem {
+    fn new(item: &ClipboardItem) -> Self {
+        let content = match &item.content {
+            ClipboardContent::Text(text) => StoredContent::Text(text.clone()),
+            ClipboardContent::Image(image) => StoredContent::Image {
+                width: image.width,
+                height: image.height,
+                thumbnail: image.has_thumbnail(),
+            },
+            ClipboardContent::FilePaths(paths) => StoredContent::FilePaths(paths.clone()),
+            ClipboardContent::RichText { plain, html } => StoredContent::RichText {
+                plain: plain.clone(),
+                html: html.clone(),
+            },
+        };
+        Self {
+            id: item.id,
+            timestamp: unix_millis(item.timestamp),
+            content,
+            pinned: item.pinned,
+        }
+    }
+}
+
+/// Append handle to the on-disk clipboard history.
+pub struct HistoryStore {
+    dir: PathBuf,
+    log: File,
+    /// Id of the newest item written to the log.
+    last_id: u64,
+    /// Number of records in the log.
+    records: usize,
+}
+
+impl HistoryStore {
+    /// Get the default store directory.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::state_dir().map(|d| d.join("zlaunch").join("clipboard"))
+    }
+
+    /// Open the store in `dir` and load the persisted items, newest first.
+    ///
+    /// Fails if the directory or log can be accessed by other users, or was
+    /// written by a newer version of zlaunch.
+    pub fn open(dir: &Path) -> Result<(Self, Vec<ClipboardItem>)> {
+        if dir.exists() {
+            check_private(dir)?;
+        } else {
+            DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let log_path = dir.join(LOG_FILE);
+        let (stored, compact) = if log_path.exists() {
+            check_private(&log_path)?;
+            read_log(&log_path)?
+        } else {
+            (Vec::new(), true)
+        };
+
+        if compact {
+            write_log(&log_path, &stored)?;
+            remove_orphan_images(dir, &stored);
+        }
+
+        let log = open_log(&log_path)?;
+        let last_id = stored.iter().map(|item| item.id).max().unwrap_or(0);
+        let records = stored.len();
+
+        let mut items = Vec::with_capacity(stored.len());
+        for item in stored.into_iter().rev() {
+            match load_item(dir, item) {
+                Ok(item) => items.push(item),
+                Err(e) => warn!("Skipping stored clipboard item: {:#}", e),
+            }
+        }
+        debug!("Loaded {} clipboard items", items.len());
+
+        let store = Self {
+            dir: dir.to_path_buf(),
+            log,
+            last_id,
+            records,
+        };
+        Ok((store, items))
+    }
+
+    /// Get the highest id of the stored items.
+    pub fn last_id(&self) -> u64 {
+        self.last_id
+    }
+
+    /// Append an item to the log.
+    ///
+    /// Sensitive items are skipped, as are items with an id not above the
+    /// stored ones, which would be restored out of order.
+    pub fn append(&mut self, item: &ClipboardItem) -> Result<()> {
+        if item.expires.is_some() {
+            return Ok(());
+        }
+        if item.id <= self.last_id {
+            warn!(
+                id = item.id,
+                last_id = self.last_id,
+                "Not persisting clipboard item with an id below the stored ones"
+            );
+            return Ok(());
+        }
+
+        if let ClipboardContent::Image(image) = &item.content {
+            write_private(&image_path(&self.dir, item.id), image.png())?;
+            if image.has_thumbnail() {
+                write_private(&thumbnail_path(&self.dir, item.id), image.thumbnail())?;
+            }
+        }
+
+        self.write_record(&Record::Add(StoredItem::new(item)))?;
+        self.last_id = item.id;
+        Ok(())
+    }
+
+    /// Forget evicted items.
+    pub fn remove(&mut self, ids: &[u64]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.write_record(&Record::Remove { ids: ids.to_vec() })?;
+        for &id in ids {
+            remove_images(&self.dir, id);
+        }
+        Ok(())
+    }
+
+    /// Record an item being pinned or unpinned.
+    pub fn set_pinned(&mut self, id: u64, pinned: bool) -> Result<()> {
+        self.write_record(&Record::Pin { id, pinned })
+    }
+
+    /// Forget all stored items.
+    pub fn clear(&mut self) -> Result<()> {
+        self.write_record(&Record::Clear)?;
+        remove_orphan_images(&self.dir, &[]);
+        Ok(())
+    }
+
+    /// Rewrite the log from the history once most records are obsolete.
+    pub fn maybe_compact(&mut self, history: &VecDeque<ClipboardItem>) -> Result<()> {
+        if self.records <= history.len() * 2 + COMPACT_SLACK {
+            return Ok(());
+        }
+
+        let stored: Vec<StoredItem> = history
+            .iter()
+            .rev()
+            .filter(|item| item.expires.is_none())
+            .map(StoredItem::new)
+            .collect();
+        let log_path = self.dir.join(LOG_FILE);
+        write_log(&log_path, &stored)?;
+        self.log = open_log(&log_path)?;
+        self.records = stored.len();
+        remove_orphan_images(&self.dir, &stored);
+        debug!("Compacted clipboard history to {} items", stored.len());
+        Ok(())
+    }
+
+    fn write_record(&mut self, record: &Record) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        self.log.write_all(line.as_bytes())?;
+        self.records += 1;
+        Ok(())
+    }
+}
+
+/// Refuse to use files that other users can access.
+fn check_private(path: &Path) -> Result<()> {
+    let metadata = fs::metadata(path)?;
+    // SAFETY: getuid has no preconditions and cannot fail
+    let uid = unsafe { libc::getuid() };
+
+    if metadata.uid() != uid {
+        bail!("{} is owned by another user", path.display());
+    }
+    if metadata.mode() & 0o077 != 0 {
+        bail!(
+            "{} is accessible by other users (mode {:o}), restrict it with chmod go-rwx",
+            path.display(),
+            metadata.mode() & 0o777
+        );
+    }
+    Ok(())
+}
+
+/// Read the log, returning the live items, oldest first, and whether the log
+/// should be compacted.
+fn read_log(path: &Path) -> Result<(Vec<StoredItem>, bool)> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut lines = BufReader::new(file).lines();
+
+    let Some(header) = lines.next().transpose()? else {
+        return Ok((Vec::new(), true));
+    };
+    let version = match serde_json::from_str::<Header>(&header) {
+        Ok(header) => header.version,
+        Err(e) => bail!("Invalid clipboard history header: {}", e),
+    };
+    if version > STORE_VERSION {
+        bail!(
+            "Clipboard history was written by a newer zlaunch (format version {})",
+            version
+        );
+    }
+
+    let mut items: Vec<StoredItem> = Vec::new();
+    let mut records = 0;
+    let mut damaged = false;
+    for line in lines {
+        let line = line?;
+        let record = serde_json::from_str(&line)
+            .map_err(anyhow::Error::from)
+            .and_then(|value| migrate(version, value))
+            .and_then(|value| Ok(serde_json::from_value::<Record>(value)?));
+        records += 1;
+
+        match record {
+            Ok(Record::Add(item)) => items.push(item),
+            Ok(Record::Remove { ids }) => items.retain(|item| !ids.contains(&item.id)),
+            Ok(Record::Pin { id, pinned }) => {
+                if let Some(item) = items.iter_mut().find(|item| item.id == id) {
+                    item.pinned = pinned;
+                }
+            }
+            Ok(Record::Clear) => items.clear(),
+            Err(e) => {
+                // A crash while appending leaves a partial last line
+                warn!("Ignoring damaged clipboard history record: {}", e);
+                damaged = true;
+            }
+        }
+    }
+
+    let compact = damaged || version != STORE_VERSION || records > items.len();
+    Ok((items, compact))
+}
+
+/// Upgrade a record written by an older format version to the current one.
+fn migrate(version: u32, record: serde_json::Value) -> Result<serde_json::Value> {
+    match version {
+        // Version 1 records are valid version 2 records without pins or thumbnails
+        1 | STORE_VERSION => Ok(record),
+        _ => bail!("Unsupported clipboard history format version {}", version),
+    }
+}
+
+/// Atomically replace the log with the given items.
+fn write_log(path: &Path, items: &[StoredItem]) -> Result<()> {
+    let mut data = serde_json::to_string(&Header {
+        version: STORE_VERSION,
+    })?;
+    data.push('\n');
+    for item in items {
+        data.push_str(&serde_json::to_string(&Record::Add(item.clone()))?);
+        data.push('\n');
+    }
+
+    let tmp = path.with_extension("jsonl.tmp");
+    write_private(&tmp, data.as_bytes())?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+fn open_log(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Remove stored images that no item refers to.
+fn remove_orphan_images(dir: &Path, items: &[StoredItem]) {
+    let Ok(entries) = fs::read_dir(dir.join(IMAGE_DIR)) else {
+        return;
+    };
+    let live: HashSet<PathBuf> = items
+        .iter()
+        .filter(|item| matches!(item.content, StoredContent::Image { .. }))
+        .flat_map(|item| [image_path(dir, item.id), thumbnail_path(dir, item.id)])
+        .collect();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !live.contains(&path)
+            && let Err(e) = fs::remove_file(&path)
+        {
+            warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Remove the image files of an item, if any.
+fn remove_images(dir: &Path, id: u64) {
+    for path in [image_path(dir, id), thumbnail_path(dir, id)] {
+        if let Err(e) = fs::remove_file(&path)
+            && e.kind() != ErrorKind::NotFound
+        {
+            warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Turn a stored item back into a clipboard item.
+///
+/// Images are read in their compressed form and only decoded when copied.
+fn load_item(dir: &Path, item: StoredItem) -> Result<ClipboardItem> {
+    let content = match item.content {
+        StoredContent::Text(text) => ClipboardContent::Text(text),
+        StoredContent::Image {
+            width,
+            height,
+            thumbnail,
+        } => {
+            let png = read_image(&image_path(dir, item.id))?;
+            let thumbnail = thumbnail
+                .then(|| read_image(&thumbnail_path(dir, item.id)))
+                .transpose()?;
+            ClipboardContent::Image(ClipboardImage::from_png(width, height, png, thumbnail))
+        }
+        StoredContent::FilePaths(paths) => ClipboardContent::FilePaths(paths),
+        StoredContent::RichText { plain, html } => ClipboardContent::RichText { plain, html },
+    };
+
+    Ok(ClipboardItem {
+        id: item.id,
+        content,
+        timestamp: UNIX_EPOCH + Duration::from_millis(item.timestamp),
+        pinned: item.pinned,
+        expires: None,
+    })
+}
+
+fn read_image(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Write a file only the current user can read.
+fn write_private(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    file.write_all(data)?;
+    Ok(())
+}
+
+fn image_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(IMAGE_DIR).join(format!("{}.png", id))
+}
+
+fn thumbnail_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(IMAGE_DIR).join(format!("{}.thumb.png", id))
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "zlaunch-clipboard-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn item(id: u64, content: ClipboardContent) -> ClipboardItem {
+        ClipboardItem {
+            id,
+            content,
+            timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_000 + id),
+            pinned: false,
+            expires: None,
+        }
+    }
+
+    fn text_item(id: u64, text: &str) -> ClipboardItem {
+        item(id, ClipboardContent::Text(text.to_string()))
+    }
+
+    fn text(item: &ClipboardItem) -> &str {
+        match &item.content {
+            ClipboardContent::Text(text) => text,
+            _ => panic!("not a text item"),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = test_dir("roundtrip");
+        let (mut store, items) = HistoryStore::open(&dir).unwrap();
+        assert!(items.is_empty());
+
+        let rgba = vec![255, 0, 0, 255, 0, 0, 255, 128];
+        let image = ClipboardImage::from_rgba(2, 1, &rgba).unwrap();
+        store.append(&text_item(1, "first")).unwrap();
+        store
+            .append(&item(2, ClipboardContent::Image(image)))
+            .unwrap();
+        store.append(&text_item(3, "third")).unwrap();
+        // Not above the stored ids
+        store.append(&text_item(2, "again")).unwrap();
+        drop(store);
+
+        let (store, items) = HistoryStore::open(&dir).unwrap();
+        assert_eq!(store.last_id(), 3);
+        assert_eq!(items.len(), 3);
+        assert_eq!(text(&items[0]), "third");
+        assert_eq!(items[0].id, 3);
+        assert_eq!(items[0].timestamp, text_item(3, "").timestamp);
+        match &items[1].content {
+            ClipboardContent::Image(image) => {
+                assert_eq!((image.width, image.height), (2, 1));
+                assert_eq!(image.to_rgba().unwrap(), rgba);
+            }
+            _ => panic!("not an image item"),
+        }
+        assert_eq!(text(&items[2]), "first");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_thumbnail_roundtrip() {
+        let dir = test_dir("thumbnail");
+        let (mut store, _) = HistoryStore::open(&dir).unwrap();
+        let image = ClipboardImage::from_rgba(1024, 2, &vec![90; 1024 * 2 * 4]).unwrap();
+        assert!(image.has_thumbnail());
+        store
+            .append(&item(1, ClipboardContent::Image(image.clone())))
+            .unwrap();
+        drop(store);
+
+        let (_, items) = HistoryStore::open(&dir).unwrap();
+        match &items[0].content {
+            ClipboardContent::Image(loaded) => assert_eq!(loaded, &image),
+            _ => panic!("not an image item"),
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_remove_and_pin() {
+        let dir = test_dir("remove");
+        let (mut store, _) = HistoryStore::open(&dir).unwrap();
+        let image = ClipboardImage::from_rgba(1, 1, &[0, 0, 0, 255]).unwrap();
+        store
+            .append(&item(1, ClipboardContent::Image(image)))
+            .unwrap();
+        store.append(&text_item(2, "pinned")).unwrap();
+        store.append(&text_item(3, "kept")).unwrap();
+        store.set_pinned(2, true).unwrap();
+        store.remove(&[1]).unwrap();
+        drop(store);
+        assert!(!image_path(&dir, 1).exists());
+
+        let (_, items) = HistoryStore::open(&dir).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(text(&items[0]), "kept");
+        assert!(!items[0].pinned);
+        assert_eq!(text(&items[1]), "pinned");
+        assert!(items[1].pinned);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clear_compacts() {
+        let dir = test_dir("clear");
+        let (mut store, _) = HistoryStore::open(&dir).unwrap();
+        let image = ClipboardImage::from_rgba(1, 1, &[0, 0, 0, 255]).unwrap();
+        store
+            .append(&item(1, ClipboardContent::Image(image)))
+            .unwrap();
+        store.clear().unwrap();
+        store.append(&text_item(2, "kept")).unwrap();
+        drop(store);
+        assert!(!image_path(&dir, 1).exists());
+
+        let (_, items) = HistoryStore::open(&dir).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(text(&items[0]), "kept");
+
+        // The cleared records were dropped from the log
+        let log = fs::read_to_string(dir.join(LOG_FILE)).unwrap();
+        assert_eq!(log.lines().count(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_compact_from_history() {
+        let dir = test_dir("compact");
+        let (mut store, _) = HistoryStore::open(&dir).unwrap();
+        let mut history = VecDeque::new();
+        for id in 1..=100 {
+            let item = text_item(id, "text");
+            store.append(&item).unwrap();
+            history.push_front(item);
+            if history.len() > 10 {
+                let evicted = history.pop_back().unwrap();
+                store.remove(&[evicted.id]).unwrap();
+            }
+            store.maybe_compact(&history).unwrap();
+        }
+        assert!(store.records <= history.len() * 2 + COMPACT_SLACK);
+        drop(store);
+
+        let (_, items) = HistoryStore::open(&dir).unwrap();
+        let ids: Vec<u64> = items.iter().map(|item| item.id).collect();
+        assert_eq!(ids, (91..=100).rev().collect::<Vec<u64>>());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sensitive_items_are_not_stored() {
+        let dir = test_dir("sensitive");
+        let (mut store, _) = HistoryStore::open(&dir).unwrap();
+        let mut sensitive = text_item(2, "123456");
+        sensitive.expires = Some(sensitive.timestamp + Duration::from_secs(60));
+        let history = VecDeque::from([sensitive, text_item(1, "kept")]);
+        for item in history.iter().rev() {
+            store.append(item).unwrap();
+        }
+        store.records = COMPACT_SLACK + 10;
+        store.maybe_compact(&history).unwrap();
+        drop(store);
+
+        let (_, items) = HistoryStore::open(&dir).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(text(&items[0]), "kept");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_damaged_tail_is_ignored() {
+        let dir = test_dir("damaged");
+        let (mut store, _) = HistoryStore::open(&dir).unwrap();
+        store.append(&text_item(1, "whole")).unwrap();
+        store.log.write_all(br#"{"add":{"id":2,"time"#).unwrap();
+        drop(store);
+
+        let (mut store, items) = HistoryStore::open(&dir).unwrap();
+        assert_eq!(items.len(), 1);
+        // Appending after a compaction starts on a fresh line
+        store.append(&text_item(2, "next")).unwrap();
+        drop(store);
+
+        let (_, items) = HistoryStore::open(&dir).unwrap();
+        assert_eq!(items.len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_version_1_is_migrated() {
+        let dir = test_dir("migrate");
+        HistoryStore::open(&dir).unwrap();
+        let log = concat!(
+            "{\"version\":1}\n",
+            "{\"add\":{\"id\":1,\"timestamp\":1700000000000,\"content\":{\"text\":\"old\"}}}\n",
+        );
+        fs::write(dir.join(LOG_FILE), log).unwrap();
+
+        let (_, items) = HistoryStore::open(&dir).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(text(&items[0]), "old");
+        assert!(!items[0].pinned);
+
+        // Rewritten in the current format
+        let log = fs::read_to_string(dir.join(LOG_FILE)).unwrap();
+        assert!(log.starts_with("{\"version\":2}\n"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let dir = test_dir("version");
+        HistoryStore::open(&dir).unwrap();
+        fs::write(dir.join(LOG_FILE), "{\"version\":99}\n").unwrap();
+
+        assert!(HistoryStore::open(&dir).is_err());
+        // The newer log is left untouched
+        let log = fs::read_to_string(dir.join(LOG_FILE)).unwrap();
+        assert_eq!(log, "{\"version\":99}\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_shared_directory_is_rejected() {
+        let dir = test_dir("permissions");
+        HistoryStore::open(&dir).unwrap();
+        let log = dir.join(LOG_FILE);
+        assert_eq!(fs::metadata(&dir).unwrap().mode() & 0o777, 0o700);
+        assert_eq!(fs::metadata(&log).unwrap().mode() & 0o777, 0o600);
+
+        fs::set_permissions(&log, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(HistoryStore::open(&dir).is_err());
+
+        fs::set_permissions(&log, fs::Permissions::from_mode(0o600)).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(HistoryStore::open(&dir).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,8 @@
 //! Common functionality shared across compositor implementations.
 
 use super::WindowInfo;
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
 
 /// Describes the capabilities of a compositor implementation.
 #[derive(Debug, Clone, Default)]
@@ -15,6 +17,20 @@ pub struct CompositorCapabilities {
     pub workspace_info: bool,
     /// Whether focus state tracking is accurate.
     pub focus_tracking: bool,
+    /// Whether windows can be closed.
+    pub close_window: bool,
+    /// Whether windows can be moved to another workspace.
+    pub move_window: bool,
+    /// Whether windows can be toggled floating.
+    pub toggle_floating: bool,
+    /// Whether windows can be toggled fullscreen.
+    pub toggle_fullscreen: bool,
+    /// Whether windows can be pinned to all workspaces.
+    pub pin_window: bool,
+    /// Whether open windows can be listed.
+    pub window_list: bool,
+    /// Whether window changes are reported through an event stream.
+    pub event_stream: bool,
 }
 
 impl CompositorCapabilities {
@@ -26,6 +42,13 @@ impl CompositorCapabilities {
             window_switching: true,
             workspace_info: true,
             focus_tracking: true,
+            close_window: true,
+            move_window: true,
+            toggle_floating: true,
+            toggle_fullscreen: true,
+            pin_window: true,
+            window_list: true,
+            event_stream: true,
         }
     }
 
@@ -37,6 +60,13 @@ impl CompositorCapabilities {
             window_switching: true,
             workspace_info: false,
             focus_tracking: false,
+            close_window: true,
+            move_window: false,
+            toggle_floating: false,
+            toggle_fullscreen: true,
+            pin_window: false,
+            window_list: true,
+            event_stream: false,
         }
     }
 
@@ -46,6 +76,24 @@ impl CompositorCapabilities {
     }
 }
 
+/// Get the IPC socket path of a compositor.
+///
+/// Uses the configured path, or the one in the given environment variable.
+/// Fails if neither is set or the socket does not exist, e.g. when the
+/// variable leaked from another session.
+pub fn socket_path(configured: Option<&Path>, env_var: &str) -> Result<PathBuf> {
+    let path = match configured {
+        Some(path) => path.to_path_buf(),
+        None => std::env::var_os(env_var)
+            .map(PathBuf::from)
+            .with_context(|| format!("{} is not set", env_var))?,
+    };
+    if !path.exists() {
+        bail!("Socket {:?} does not exist", path);
+    }
+    Ok(path)
+}
+
 /// Get the display title for a window, falling back to class if title is empty.
 ///
 /// Both Hyprland and Niri use this pattern: if a window has no title,
@@ -85,6 +133,29 @@ mod tests {
         assert_eq!(get_display_title("", ""), "");
     }
 
+    #[test]
+    fn test_window_action_capabilities() {
+        let full = CompositorCapabilities::full();
+        assert!(full.close_window && full.move_window && full.pin_window);
+
+        let limited = CompositorCapabilities::limited();
+        assert!(limited.close_window && limited.toggle_fullscreen);
+        assert!(!limited.move_window && !limited.toggle_floating && !limited.pin_window);
+
+        let none = CompositorCapabilities::none();
+        assert!(!none.close_window && !none.toggle_fullscreen && !none.window_list);
+    }
+
+    #[test]
+    fn test_socket_path() {
+        let configured = Path::new("/");
+        assert_eq!(socket_path(Some(configured), "UNUSED").unwrap(), configured);
+
+        let missing = Path::new("/nonexistent/zlaunch.sock");
+        assert!(socket_path(Some(missing), "UNUSED").is_err());
+        assert!(socket_path(None, "ZLAUNCH_TEST_UNSET_SOCKET").is_err());
+    }
+
     #[test]
     fn test_is_launcher_window() {
         assert!(is_launcher_window("zlaunch"));
@@ -103,6 +174,8 @@ mod tests {
                 class: "firefox".to_string(),
                 workspace: 1,
                 focused: false,
+                urgent: false,
+                last_focused: None,
             },
             WindowInfo {
                 address: "2".to_string(),
@@ -110,6 +183,8 @@ mod tests {
                 class: "zlaunch".to_string(),
                 workspace: 1,
                 focused: true,
+                urgent: false,
+                last_focused: None,
             },
         ];
 
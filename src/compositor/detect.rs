@@ -1,42 +1,90 @@
 //! Compositor detection logic.
 
 use super::Compositor;
+use super::foreign_toplevel::ForeignToplevelCompositor;
 use super::hyprland::HyprlandCompositor;
 use super::kwin::KwinCompositor;
 use super::niri::NiriCompositor;
 use super::noop::NoopCompositor;
+use super::sway::SwayCompositor;
+use crate::config::{AppConfig, CompositorBackend};
+use anyhow::{Result, bail};
 use tracing::{info, warn};
 
+/// Backends tried in order when the compositor is detected automatically.
+const DETECTION_ORDER: [CompositorBackend; 5] = [
+    CompositorBackend::Hyprland,
+    CompositorBackend::Kwin,
+    CompositorBackend::Niri,
+    CompositorBackend::Sway,
+    CompositorBackend::Wlr,
+];
+
 /// Detect and create the appropriate compositor client.
 ///
-/// Detection order:
+/// With `compositor = "auto"`, backends are tried in this order:
 /// 1. Hyprland (via HYPRLAND_INSTANCE_SIGNATURE env var)
 /// 2. KDE/KWin (via KDE_SESSION_VERSION env var)
 /// 3. Niri     (via NIRI_SOCKET env var)
-/// 3. Fallback to NoopCompositor
+/// 4. Sway     (via SWAYSOCK env var)
+/// 5. Any compositor with the foreign-toplevel protocols (river, labwc, ...)
+/// 6. Fallback to NoopCompositor
+///
+/// Otherwise only the configured backend is tried. The reason each backend
+/// is rejected is logged.
 ///
 /// The NoopCompositor allows the launcher to function (with applications only)
 /// even on unsupported compositors.
-pub fn detect_compositor() -> Box<dyn Compositor> {
-    // Try Hyprland first
-    if let Some(compositor) = HyprlandCompositor::new() {
-        info!("Detected Hyprland compositor");
-        return Box::new(compositor);
-    }
-
-    // Try KWin
-    if let Some(compositor) = KwinCompositor::new() {
-        info!("Detected KWin compositor");
-        return Box::new(compositor);
-    }
+pub fn detect_compositor(config: &AppConfig) -> Box<dyn Compositor> {
+    let candidates: &[CompositorBackend] = match config.compositor {
+        CompositorBackend::Auto => &DETECTION_ORDER,
+        CompositorBackend::None => {
+            info!("Compositor integration disabled in config, window switching disabled");
+            return Box::new(NoopCompositor);
+        }
+        ref backend => std::slice::from_ref(backend),
+    };
+    let auto = config.compositor == CompositorBackend::Auto;
 
-    // Try Niri
-    if let Some(compositor) = NiriCompositor::new() {
-        info!("Detected Niri compositor");
-        return Box::new(compositor);
+    for &backend in candidates {
+        match connect(backend, config, auto) {
+            Ok(compositor) => {
+                info!("Using {} compositor", compositor.name());
+                return compositor;
+            }
+            Err(e) if auto => info!(?backend, "Compositor backend rejected: {:#}", e),
+            Err(e) => warn!(?backend, "Configured compositor backend failed: {:#}", e),
+        }
     }
 
     // Fallback to no-op
     warn!("No supported compositor detected, window switching disabled");
     Box::new(NoopCompositor)
 }
+
+/// Create the client for a backend.
+///
+/// `auto` is set when detecting, where KWin is only tried in KDE sessions.
+fn connect(
+    backend: CompositorBackend,
+    config: &AppConfig,
+    auto: bool,
+) -> Result<Box<dyn Compositor>> {
+    Ok(match backend {
+        CompositorBackend::Hyprland => Box::new(HyprlandCompositor::new(
+            config.hyprland.instance_signature.as_deref(),
+        )?),
+        CompositorBackend::Kwin => {
+            if auto && std::env::var_os("KDE_SESSION_VERSION").is_none() {
+                bail!("KDE_SESSION_VERSION is not set");
+            }
+            Box::new(KwinCompositor::new()?)
+        }
+        CompositorBackend::Niri => Box::new(NiriCompositor::new(config.niri.socket.as_deref())?),
+        CompositorBackend::Sway => Box::new(SwayCompositor::new(config.sway.socket.as_deref())?),
+        CompositorBackend::Wlr => Box::new(ForeignToplevelCompositor::new()?),
+        CompositorBackend::Auto | CompositorBackend::None => {
+            bail!("{:?} is not a compositor backend", backend)
+        }
+    })
+}
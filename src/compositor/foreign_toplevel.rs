@@ -0,0 +1,495 @@
+//! Generic compositor implementation using the foreign-toplevel protocols.
+//!
+//! Works on wlroots-based compositors without a bespoke IPC (river, labwc,
+//! wayfire, cosmic, ...). `zwlr_foreign_toplevel_management_v1` lists and
+//! activates windows; compositors that only offer `ext_foreign_toplevel_list_v1`
+//! get a read-only window list.
+//!
+//! A background thread keeps a Wayland connection open and follows toplevel
+//! events, so the window list is always current.
+
+use super::base::{CompositorCapabilities, get_display_title, is_launcher_window};
+use super::{Compositor, WindowInfo};
+use anyhow::{Context, Result, anyhow, bail};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tracing::{debug, warn};
+use wayland_client::globals::{GlobalListContents, registry_queue_init};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::{
+    ext_foreign_toplevel_handle_v1, ext_foreign_toplevel_list_v1,
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1, zwlr_foreign_toplevel_manager_v1,
+};
+
+/// Which foreign-toplevel protocol is in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    /// `zwlr_foreign_toplevel_management_v1`: list, activate and close
+    Wlr,
+    /// `ext_foreign_toplevel_list_v1`: list only
+    Ext,
+}
+
+/// Compositor client using the foreign-toplevel protocols.
+pub struct ForeignToplevelCompositor {
+    conn: Connection,
+    seat: Option<wl_seat::WlSeat>,
+    protocol: Protocol,
+    toplevels: Arc<Mutex<Vec<Toplevel>>>,
+}
+
+/// A toplevel window advertised by the compositor.
+#[derive(Debug)]
+struct Toplevel {
+    info: ToplevelInfo,
+    /// Handle for window actions (wlr protocol only)
+    wlr_handle: Option<zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1>,
+    /// Handle for the ext protocol, which only lists windows
+    ext_handle: Option<ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1>,
+}
+
+/// Window state received for a toplevel.
+#[derive(Debug, Clone, Default)]
+struct ToplevelInfo {
+    /// Our ID for the toplevel, used as the window address
+    id: u64,
+    title: String,
+    app_id: String,
+    activated: bool,
+    fullscreen: bool,
+    /// Whether the initial state is complete (first `done` event received)
+    ready: bool,
+}
+
+impl ToplevelInfo {
+    /// Convert to WindowInfo, or None if the window should not be listed.
+    fn to_window_info(&self) -> Option<WindowInfo> {
+        if !self.ready || is_launcher_window(&self.app_id) {
+            return None;
+        }
+
+        Some(WindowInfo {
+            address: self.id.to_string(),
+            title: get_display_title(&self.title, &self.app_id),
+            class: self.app_id.clone(),
+            workspace: 1, // The protocols don't expose workspaces
+            focused: self.activated,
+            urgent: false,
+            last_focused: None,
+        })
+    }
+}
+
+/// Parse the state array of a wlr toplevel, returning (activated, fullscreen).
+fn parse_state(state: &[u8]) -> (bool, bool) {
+    let states: Vec<u32> = state
+        .chunks_exact(4)
+        .map(|chunk| u32::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect();
+
+    let has = |s: zwlr_foreign_toplevel_handle_v1::State| states.contains(&(s as u32));
+    (
+        has(zwlr_foreign_toplevel_handle_v1::State::Activated),
+        has(zwlr_foreign_toplevel_handle_v1::State::Fullscreen),
+    )
+}
+
+/// Event queue state, shared with the compositor client through `toplevels`.
+struct ToplevelState {
+    toplevels: Arc<Mutex<Vec<Toplevel>>>,
+    next_id: u64,
+}
+
+impl ToplevelState {
+    /// Record a new toplevel.
+    fn add(&mut self, mut toplevel: Toplevel) {
+        toplevel.info.id = self.next_id;
+        self.next_id += 1;
+        self.toplevels.lock().unwrap().push(toplevel);
+    }
+
+    /// Update the toplevel with the given handle.
+    fn update(&self, handle: &impl Proxy, f: impl FnOnce(&mut ToplevelInfo)) {
+        let id = handle.id();
+        let mut toplevels = self.toplevels.lock().unwrap();
+        let toplevel = toplevels.iter_mut().find(|t| {
+            t.wlr_handle.as_ref().map(Proxy::id).as_ref() == Some(&id)
+                || t.ext_handle.as_ref().map(Proxy::id).as_ref() == Some(&id)
+        });
+        if let Some(toplevel) = toplevel {
+            f(&mut toplevel.info);
+        }
+    }
+
+    /// Forget a closed toplevel.
+    fn remove(&self, handle: &impl Proxy) {
+        let id = handle.id();
+        self.toplevels.lock().unwrap().retain(|t| {
+            t.wlr_handle.as_ref().map(Proxy::id).as_ref() != Some(&id)
+                && t.ext_handle.as_ref().map(Proxy::id).as_ref() != Some(&id)
+        });
+    }
+}
+
+impl ForeignToplevelCompositor {
+    /// Create a new foreign-toplevel compositor client.
+    ///
+    /// Fails if there is no Wayland connection or the compositor supports
+    /// neither protocol.
+    pub fn new() -> Result<Self> {
+        let conn = Connection::connect_to_env().context("Failed to connect to Wayland")?;
+        let (globals, mut queue) =
+            registry_queue_init::<ToplevelState>(&conn).context("Failed to get Wayland globals")?;
+        let qh = queue.handle();
+
+        let mut state = ToplevelState {
+            toplevels: Arc::new(Mutex::new(Vec::new())),
+            next_id: 1,
+        };
+
+        // Prefer the wlr protocol, which can also activate windows. Binding
+        // both would list every window twice.
+        let protocol = if globals
+            .bind::<zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1, _, _>(
+                &qh,
+                1..=3,
+                (),
+            )
+            .is_ok()
+        {
+            Protocol::Wlr
+        } else if globals
+            .bind::<ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1, _, _>(&qh, 1..=1, ())
+            .is_ok()
+        {
+            Protocol::Ext
+        } else {
+            bail!("Compositor supports no foreign-toplevel protocol");
+        };
+        let seat = globals.bind::<wl_seat::WlSeat, _, _>(&qh, 1..=1, ()).ok();
+
+        // Receive the initial toplevels before the first list request
+        queue
+            .roundtrip(&mut state)
+            .context("Failed to receive foreign toplevels")?;
+
+        let toplevels = state.toplevels.clone();
+        thread::spawn(move || {
+            loop {
+                if let Err(e) = queue.blocking_dispatch(&mut state) {
+                    warn!(%e, "Foreign toplevel event queue failed");
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            conn,
+            seat,
+            protocol,
+            toplevels,
+        })
+    }
+
+    /// Get the wlr handle and state of a window.
+    fn wlr_toplevel(
+        &self,
+        window_id: &str,
+    ) -> Result<(
+        zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+        ToplevelInfo,
+    )> {
+        let id: u64 = window_id
+            .parse()
+            .map_err(|_| anyhow!("Invalid foreign toplevel ID: {}", window_id))?;
+
+        let toplevels = self.toplevels.lock().unwrap();
+        let toplevel = toplevels
+            .iter()
+            .find(|t| t.info.id == id)
+            .ok_or_else(|| anyhow!("Window not found: {}", window_id))?;
+        let handle = toplevel
+            .wlr_handle
+            .clone()
+            .ok_or_else(|| anyhow!("{} cannot manage windows", self.name()))?;
+        Ok((handle, toplevel.info.clone()))
+    }
+
+    /// Send pending requests to the compositor.
+    fn flush(&self) -> Result<()> {
+        self.conn.flush()?;
+        Ok(())
+    }
+}
+
+impl Compositor for ForeignToplevelCompositor {
+    fn list_windows(&self) -> Result<Vec<WindowInfo>> {
+        Ok(self
+            .toplevels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|t| t.info.to_window_info())
+            .collect())
+    }
+
+    fn focus_window(&self, window_id: &str) -> Result<()> {
+        let (handle, _) = self.wlr_toplevel(window_id)?;
+        let Some(seat) = &self.seat else {
+            bail!("No Wayland seat to activate the window on");
+        };
+        handle.activate(seat);
+        self.flush()
+    }
+
+    fn close_window(&self, window_id: &str) -> Result<()> {
+        let (handle, _) = self.wlr_toplevel(window_id)?;
+        handle.close();
+        self.flush()
+    }
+
+    fn toggle_fullscreen(&self, window_id: &str) -> Result<()> {
+        let (handle, info) = self.wlr_toplevel(window_id)?;
+        if info.fullscreen {
+            handle.unset_fullscreen();
+        } else {
+            handle.set_fullscreen(None);
+        }
+        self.flush()
+    }
+
+    fn name(&self) -> &'static str {
+        match self.protocol {
+            Protocol::Wlr => "wlr-foreign-toplevel",
+            Protocol::Ext => "ext-foreign-toplevel-list",
+        }
+    }
+
+    fn capabilities(&self) -> CompositorCapabilities {
+        match self.protocol {
+            Protocol::Wlr => CompositorCapabilities {
+                layer_shell: true,
+                window_list: true,
+                window_switching: self.seat.is_some(),
+                focus_tracking: true,
+                close_window: true,
+                toggle_fullscreen: true,
+                ..CompositorCapabilities::none()
+            },
+            Protocol::Ext => CompositorCapabilities {
+                layer_shell: true,
+                window_list: true,
+                ..CompositorCapabilities::none()
+            },
+        }
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for ToplevelState {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for ToplevelState {
+    fn event(
+        _: &mut Self,
+        _: &wl_seat::WlSeat,
+        _: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1, ()>
+    for ToplevelState
+{
+    fn event(
+        state: &mut Self,
+        _: &zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } => {
+                state.add(Toplevel {
+                    info: ToplevelInfo::default(),
+                    wlr_handle: Some(toplevel),
+                    ext_handle: None,
+                });
+            }
+            zwlr_foreign_toplevel_manager_v1::Event::Finished => {
+                debug!("Foreign toplevel manager finished");
+            }
+            _ => {}
+        }
+    }
+
+    fn event_created_child(
+        opcode: u16,
+        qhandle: &QueueHandle<Self>,
+    ) -> std::sync::Arc<dyn wayland_client::backend::ObjectData> {
+        match opcode {
+            zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => qhandle
+                .make_data::<zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1, _>(()),
+            _ => panic!(
+                "Unknown opcode {} for zwlr_foreign_toplevel_manager_v1",
+                opcode
+            ),
+        }
+    }
+}
+
+impl Dispatch<zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1, ()> for ToplevelState {
+    fn event(
+        state: &mut Self,
+        handle: &zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
+                state.update(handle, |info| info.title = title);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                state.update(handle, |info| info.app_id = app_id);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::State { state: raw } => {
+                let (activated, fullscreen) = parse_state(&raw);
+                state.update(handle, |info| {
+                    info.activated = activated;
+                    info.fullscreen = fullscreen;
+                });
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Done => {
+                state.update(handle, |info| info.ready = true);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                state.remove(handle);
+                handle.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1, ()> for ToplevelState {
+    fn event(
+        state: &mut Self,
+        _: &ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1,
+        event: ext_foreign_toplevel_list_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_foreign_toplevel_list_v1::Event::Toplevel { toplevel } => {
+                state.add(Toplevel {
+                    info: ToplevelInfo::default(),
+                    wlr_handle: None,
+                    ext_handle: Some(toplevel),
+                });
+            }
+            ext_foreign_toplevel_list_v1::Event::Finished => {
+                debug!("Foreign toplevel list finished");
+            }
+            _ => {}
+        }
+    }
+
+    fn event_created_child(
+        opcode: u16,
+        qhandle: &QueueHandle<Self>,
+    ) -> std::sync::Arc<dyn wayland_client::backend::ObjectData> {
+        match opcode {
+            ext_foreign_toplevel_list_v1::EVT_TOPLEVEL_OPCODE => {
+                qhandle
+                    .make_data::<ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1, _>(())
+            }
+            _ => panic!("Unknown opcode {} for ext_foreign_toplevel_list_v1", opcode),
+        }
+    }
+}
+
+impl Dispatch<ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1, ()> for ToplevelState {
+    fn event(
+        state: &mut Self,
+        handle: &ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1,
+        event: ext_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_foreign_toplevel_handle_v1::Event::Title { title } => {
+                state.update(handle, |info| info.title = title);
+            }
+            ext_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                state.update(handle, |info| info.app_id = app_id);
+            }
+            ext_foreign_toplevel_handle_v1::Event::Done => {
+                state.update(handle, |info| info.ready = true);
+            }
+            ext_foreign_toplevel_handle_v1::Event::Closed => {
+                state.remove(handle);
+                handle.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_bytes(states: &[u32]) -> Vec<u8> {
+        states.iter().flat_map(|s| s.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(parse_state(&[]), (false, false));
+        // maximized, activated
+        assert_eq!(parse_state(&state_bytes(&[0, 2])), (true, false));
+        // minimized, fullscreen
+        assert_eq!(parse_state(&state_bytes(&[1, 3])), (false, true));
+    }
+
+    #[test]
+    fn test_to_window_info() {
+        let mut info = ToplevelInfo {
+            id: 4,
+            title: String::new(),
+            app_id: "org.gnome.Nautilus".to_string(),
+            activated: true,
+            ..Default::default()
+        };
+        // Not listed until the initial state is complete
+        assert!(info.to_window_info().is_none());
+
+        info.ready = true;
+        let window = info.to_window_info().unwrap();
+        assert_eq!(window.address, "4");
+        assert_eq!(window.title, "org.gnome.Nautilus");
+        assert!(window.focused);
+
+        info.app_id = "zlaunch".to_string();
+        assert!(info.to_window_info().is_none());
+    }
+}
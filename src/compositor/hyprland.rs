@@ -1,29 +1,44 @@
 //! Hyprland compositor implementation using IPC socket.
 
 use super::base::{CompositorCapabilities, get_display_title, is_launcher_window};
-use super::{Compositor, WindowInfo};
+use super::{Compositor, CompositorEvent, WindowInfo, WorkspaceInfo, WorkspaceTarget};
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::io::{Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
 
 /// Hyprland compositor client using IPC socket communication.
 pub struct HyprlandCompositor {
     socket_path: PathBuf,
+    event_socket_path: PathBuf,
 }
 
 impl HyprlandCompositor {
     /// Create a new Hyprland compositor client.
     ///
-    /// Returns None if the required environment variables are not set.
-    pub fn new() -> Option<Self> {
-        let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+    /// Uses the given instance signature, or HYPRLAND_INSTANCE_SIGNATURE.
+    /// Fails if neither is set or the instance's socket does not exist.
+    pub fn new(instance_signature: Option<&str>) -> Result<Self> {
+        let signature = match instance_signature {
+            Some(signature) => signature.to_string(),
+            None => std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+                .context("HYPRLAND_INSTANCE_SIGNATURE is not set")?,
+        };
         let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
 
-        let socket_path = PathBuf::from(format!("{}/hypr/{}/.socket.sock", runtime_dir, signature));
+        let socket_dir = PathBuf::from(format!("{}/hypr/{}", runtime_dir, signature));
+        let socket_path = socket_dir.join(".socket.sock");
 
-        Some(Self { socket_path })
+        // A signature leaked from another session has no socket
+        if !socket_path.exists() {
+            anyhow::bail!("Hyprland socket {:?} does not exist", socket_path);
+        }
+
+        Ok(Self {
+            socket_path,
+            event_socket_path: socket_dir.join(".socket2.sock"),
+        })
     }
 
     /// Send a command to Hyprland and receive the response.
@@ -46,15 +61,43 @@ impl HyprlandCompositor {
 
         Ok(response)
     }
+
+    /// Run a dispatcher, failing if Hyprland does not acknowledge it.
+    fn dispatch(&self, args: &str) -> Result<()> {
+        let response = self.send_command(&format!("dispatch {}", args))?;
+        if response.trim() != "ok" {
+            anyhow::bail!("Hyprland dispatch '{}' failed: {}", args, response.trim());
+        }
+        Ok(())
+    }
+
+    /// Get the focused monitor.
+    fn focused_monitor(&self) -> Result<Option<HyprlandMonitor>> {
+        let json = self.send_command("j/monitors")?;
+        let monitors: Vec<HyprlandMonitor> =
+            serde_json::from_str(&json).context("Failed to parse Hyprland monitors JSON")?;
+        Ok(monitors.into_iter().find(|m| m.focused))
+    }
+
+    /// Get the ID of the active workspace.
+    fn active_workspace(&self) -> Result<i32> {
+        let json = self.send_command("j/activeworkspace")?;
+        let workspace: HyprlandWorkspace =
+            serde_json::from_str(&json).context("Failed to parse Hyprland workspace JSON")?;
+        Ok(workspace.id)
+    }
 }
 
 impl Compositor for HyprlandCompositor {
     fn list_windows(&self) -> Result<Vec<WindowInfo>> {
         // j/clients returns JSON output
         let json = self.send_command("j/clients")?;
-        let clients: Vec<HyprlandClient> =
+        let mut clients: Vec<HyprlandClient> =
             serde_json::from_str(&json).context("Failed to parse Hyprland clients JSON")?;
 
+        // Most recently focused first
+        clients.sort_by_key(|c| c.focus_history_id);
+
         let windows = clients
             .into_iter()
             // Filter out special windows
@@ -82,6 +125,8 @@ impl Compositor for HyprlandCompositor {
                     class: c.class,
                     workspace,
                     focused,
+                    urgent: false,
+                    last_focused: None,
                 }
             })
             .collect();
@@ -89,12 +134,95 @@ impl Compositor for HyprlandCompositor {
         Ok(windows)
     }
 
+    fn subscribe(&self, on_event: &mut dyn FnMut(CompositorEvent)) -> Result<()> {
+        let stream = UnixStream::connect(&self.event_socket_path).with_context(|| {
+            format!(
+                "Failed to connect to Hyprland event socket: {:?}",
+                self.event_socket_path
+            )
+        })?;
+        on_event(CompositorEvent::Connected);
+
+        for line in BufReader::new(stream).lines() {
+            let line = line.context("Failed to read from Hyprland event socket")?;
+            if let Some(event) = parse_event(&line) {
+                on_event(event);
+            }
+        }
+
+        Ok(())
+    }
+
     fn focus_window(&self, window_id: &str) -> Result<()> {
         let cmd = format!("dispatch focuswindow address:{}", window_id);
         self.send_command(&cmd)?;
         Ok(())
     }
 
+    fn list_workspaces(&self) -> Result<Vec<WorkspaceInfo>> {
+        let json = self.send_command("j/workspaces")?;
+        let workspaces: Vec<HyprlandWorkspaceDetails> =
+            serde_json::from_str(&json).context("Failed to parse Hyprland workspaces JSON")?;
+        let active = self.active_workspace().ok();
+
+        Ok(workspaces
+            .into_iter()
+            .map(|w| w.into_info(active))
+            .collect())
+    }
+
+    fn focus_workspace(&self, address: &str) -> Result<()> {
+        self.dispatch(&focus_workspace_args(address))
+    }
+
+    fn close_window(&self, window_id: &str) -> Result<()> {
+        self.dispatch(&format!("closewindow address:{}", window_id))
+    }
+
+    fn move_window_to_workspace(&self, window_id: &str, workspace: Option<i32>) -> Result<()> {
+        let workspace = match workspace {
+            Some(workspace) => workspace,
+            None => self.active_workspace()?,
+        };
+        self.dispatch(&format!(
+            "movetoworkspacesilent {},address:{}",
+            workspace, window_id
+        ))
+    }
+
+    fn toggle_floating(&self, window_id: &str) -> Result<()> {
+        self.dispatch(&format!("togglefloating address:{}", window_id))
+    }
+
+    fn toggle_fullscreen(&self, window_id: &str) -> Result<()> {
+        // The fullscreen dispatcher only acts on the active window
+        self.dispatch(&format!("focuswindow address:{}", window_id))?;
+        self.dispatch("fullscreen 0")
+    }
+
+    fn pin_window(&self, window_id: &str) -> Result<()> {
+        self.dispatch(&format!("pin address:{}", window_id))
+    }
+
+    fn focused_output(&self) -> Result<Option<String>> {
+        Ok(self.focused_monitor()?.map(|m| m.name))
+    }
+
+    fn cursor_position(&self) -> Result<Option<(f32, f32)>> {
+        let json = self.send_command("j/cursorpos")?;
+        let cursor: HyprlandCursor =
+            serde_json::from_str(&json).context("Failed to parse Hyprland cursor JSON")?;
+        // The cursor is in layout coordinates
+        Ok(self
+            .focused_monitor()?
+            .map(|m| ((cursor.x - m.x) as f32, (cursor.y - m.y) as f32)))
+    }
+
+    fn launch_on_workspace(&self, command: &str, workspace: WorkspaceTarget) -> Result<bool> {
+        self.dispatch(&exec_on_workspace_args(command, workspace))?;
+        Ok(true)
+    }
+
     fn name(&self) -> &'static str {
         "Hyprland"
     }
@@ -104,6 +232,17 @@ impl Compositor for HyprlandCompositor {
     }
 }
 
+/// Build the dispatcher arguments that run a command on a workspace.
+///
+/// `silent` keeps the current workspace focused.
+fn exec_on_workspace_args(command: &str, workspace: WorkspaceTarget) -> String {
+    let workspace = match workspace {
+        WorkspaceTarget::Number(number) => number.to_string(),
+        WorkspaceTarget::Empty => "empty".to_string(),
+    };
+    format!("exec [workspace {} silent] {}", workspace, command)
+}
+
 /// Hyprland client (window) information from IPC.
 #[derive(Debug, Deserialize)]
 struct HyprlandClient {
@@ -133,13 +272,109 @@ struct HyprlandWorkspace {
     id: i32,
 }
 
+/// Hyprland workspace information from `j/workspaces`.
+#[derive(Debug, Deserialize)]
+struct HyprlandWorkspaceDetails {
+    id: i32,
+    name: String,
+    #[serde(default)]
+    monitor: String,
+    #[serde(default)]
+    windows: usize,
+}
+
+impl HyprlandWorkspaceDetails {
+    fn into_info(self, active: Option<i32>) -> WorkspaceInfo {
+        let special = self.name.starts_with("special:");
+        // Special workspaces are toggled by name, and named workspaces have
+        // negative IDs so they are switched to by name too
+        let address = if special {
+            self.name.clone()
+        } else if self.id > 0 {
+            self.id.to_string()
+        } else {
+            format!("name:{}", self.name)
+        };
+
+        WorkspaceInfo {
+            address,
+            id: self.id,
+            name: self
+                .name
+                .strip_prefix("special:")
+                .unwrap_or(&self.name)
+                .to_string(),
+            output: (!self.monitor.is_empty()).then_some(self.monitor),
+            windows: self.windows,
+            special,
+            focused: active == Some(self.id),
+        }
+    }
+}
+
+/// Hyprland monitor information from `j/monitors`.
+#[derive(Debug, Deserialize)]
+struct HyprlandMonitor {
+    name: String,
+    #[serde(default)]
+    focused: bool,
+    /// Position in layout coordinates
+    #[serde(default)]
+    x: i32,
+    #[serde(default)]
+    y: i32,
+}
+
+/// Hyprland cursor position from `j/cursorpos`.
+#[derive(Debug, Deserialize)]
+struct HyprlandCursor {
+    x: i32,
+    y: i32,
+}
+
+/// Parse a line from the event socket (`EVENT>>DATA`).
+///
+/// Event addresses lack the `0x` prefix used by `j/clients`.
+fn parse_event(line: &str) -> Option<CompositorEvent> {
+    let (name, data) = line.split_once(">>")?;
+    match name {
+        "openwindow" | "closewindow" | "movewindowv2" => Some(CompositorEvent::WindowsChanged),
+        "windowtitlev2" => {
+            let (address, title) = data.split_once(',')?;
+            Some(CompositorEvent::WindowTitleChanged {
+                address: format!("0x{}", address),
+                title: title.to_string(),
+            })
+        }
+        // Sent with empty data (or ",") when no window is focused
+        "activewindowv2" if !data.is_empty() && data != "," => {
+            Some(CompositorEvent::WindowFocused(format!("0x{}", data)))
+        }
+        "urgent" => Some(CompositorEvent::WindowUrgencyChanged {
+            address: format!("0x{}", data),
+            urgent: true,
+        }),
+        _ => None,
+    }
+}
+
+/// Build the dispatcher arguments that switch to a workspace.
+///
+/// Special workspaces are toggled, as they are shown on top of the current one.
+fn focus_workspace_args(address: &str) -> String {
+    match address.strip_prefix("special:") {
+        Some(name) => format!("togglespecialworkspace {}", name),
+        None => format!("workspace {}", address),
+    }
+}
+
 /// Apply blur layer rules for zlaunch on Hyprland.
 ///
 /// This sets up transparency and blur effects via Hyprland IPC.
 /// Returns `Ok(true)` if rules were applied, `Ok(false)` if not on Hyprland.
-pub fn apply_blur_layer_rules() -> Result<bool> {
+pub fn apply_blur_layer_rules(instance_signature: Option<&str>) -> Result<bool> {
     // Check if we're on Hyprland
-    let Some(compositor) = HyprlandCompositor::new() else {
+    let Ok(compositor) = HyprlandCompositor::new(instance_signature) else {
         return Ok(false);
     };
 
@@ -157,3 +392,85 @@ pub fn apply_blur_layer_rules() -> Result<bool> {
 
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(id: i32, name: &str) -> HyprlandWorkspaceDetails {
+        HyprlandWorkspaceDetails {
+            id,
+            name: name.to_string(),
+            monitor: "DP-1".to_string(),
+            windows: 2,
+        }
+    }
+
+    #[test]
+    fn test_workspace_info() {
+        let info = workspace(3, "3").into_info(Some(3));
+        assert_eq!(info.address, "3");
+        assert_eq!(info.name, "3");
+        assert_eq!(info.output.as_deref(), Some("DP-1"));
+        assert_eq!(info.windows, 2);
+        assert!(!info.special);
+        assert!(info.focused);
+
+        let info = workspace(-98, "special:scratchpad").into_info(Some(3));
+        assert_eq!(info.address, "special:scratchpad");
+        assert_eq!(info.name, "scratchpad");
+        assert!(info.special);
+        assert!(!info.focused);
+
+        let info = workspace(-1337, "web").into_info(None);
+        assert_eq!(info.address, "name:web");
+        assert!(!info.special);
+    }
+
+    #[test]
+    fn test_parse_event() {
+        assert!(matches!(
+            parse_event("openwindow>>5678abcd,1,kitty,Terminal"),
+            Some(CompositorEvent::WindowsChanged)
+        ));
+        assert!(matches!(
+            parse_event("activewindowv2>>5678abcd"),
+            Some(CompositorEvent::WindowFocused(address)) if address == "0x5678abcd"
+        ));
+        assert!(parse_event("activewindowv2>>").is_none());
+        assert!(matches!(
+            parse_event("windowtitlev2>>5678abcd,vim: a, b"),
+            Some(CompositorEvent::WindowTitleChanged { address, title })
+                if address == "0x5678abcd" && title == "vim: a, b"
+        ));
+        assert!(matches!(
+            parse_event("urgent>>5678abcd"),
+            Some(CompositorEvent::WindowUrgencyChanged { address, urgent: true })
+                if address == "0x5678abcd"
+        ));
+        assert!(parse_event("workspace>>2").is_none());
+        assert!(parse_event("garbage").is_none());
+    }
+
+    #[test]
+    fn test_focus_workspace_args() {
+        assert_eq!(focus_workspace_args("3"), "workspace 3");
+        assert_eq!(focus_workspace_args("name:web"), "workspace name:web");
+        assert_eq!(
+            focus_workspace_args("special:scratchpad"),
+            "togglespecialworkspace scratchpad"
+        );
+    }
+
+    #[test]
+    fn test_exec_on_workspace_args() {
+        assert_eq!(
+            exec_on_workspace_args("kitty", WorkspaceTarget::Number(3)),
+            "exec [workspace 3 silent] kitty"
+        );
+        assert_eq!(
+            exec_on_workspace_args("'firefox' '--new-window'", WorkspaceTarget::Empty),
+            "exec [workspace empty silent] 'firefox' '--new-window'"
+        );
+    }
+}
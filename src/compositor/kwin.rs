@@ -1,17 +1,49 @@
-//! KDE KWin compositor implementation using D-Bus WindowsRunner API.
+//! KDE KWin compositor implementation using D-Bus.
 //!
-//! Uses KWin's krunner interface via D-Bus to enumerate and focus windows.
-//! This approach uses the /WindowsRunner D-Bus path which provides direct
-//! window listing without needing to capture script print() signals.
+//! Windows are listed by a small KWin script, loaded through
+//! `org.kde.kwin.Scripting`, which reports each window's virtual desktop,
+//! output, focus and state back to us over D-Bus. A report is reused for a
+//! moment, so listing windows and workspaces for one show runs the script
+//! once. If scripting is unavailable, the
+//! krunner interface at /WindowsRunner is used instead, which gives no desktop
+//! or focus state. Windows are focused and closed through the WindowsRunner,
+//! which uses the same window IDs as the script.
 
-use super::base::CompositorCapabilities;
-use super::{Compositor, WindowInfo};
-use anyhow::{Context, Result};
+use super::base::{CompositorCapabilities, get_display_title, is_launcher_window};
+use super::{Compositor, WindowInfo, WorkspaceInfo};
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
 use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 use zbus::blocking::{Connection, Proxy};
 use zbus::zvariant::OwnedValue;
 
+/// Script reporting the open windows.
+const WINDOWS_SCRIPT: &str = include_str!("kwin_windows.js");
+
+/// Plugin name the windows script is loaded under.
+const SCRIPT_PLUGIN: &str = "zlaunch-windows";
+
+/// Object path and interface the script reports to.
+const CALLBACK_PATH: &str = "/dev/zlaunch/KWinScript";
+const CALLBACK_INTERFACE: &str = "dev.zlaunch.KWinScript";
+
+/// How long to wait for the script to report back.
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long a script report is reused, covering the lookups of one show.
+const REPORT_MAX_AGE: Duration = Duration::from_secs(1);
+
+/// A script report being waited for: its token and where to send it.
+type PendingReport = Option<(String, mpsc::Sender<String>)>;
+
 /// Type alias for KRunner match results from WindowsRunner.Match D-Bus call.
 /// Tuple: (match_id, text, subtext, type, relevance, properties)
 type KRunnerMatch = (
@@ -23,30 +55,224 @@ type KRunnerMatch = (
     HashMap<String, OwnedValue>,
 );
 
-/// KWin compositor client using D-Bus WindowsRunner API.
+/// Window actions of the WindowsRunner, used as the match ID prefix.
+#[derive(Debug, Clone, Copy)]
+enum RunnerAction {
+    Activate = 0,
+    Close = 1,
+    Fullscreen = 4,
+}
+
+/// KWin compositor client using D-Bus.
 pub struct KwinCompositor {
     connection: Connection,
+    /// Whether windows can be listed with a KWin script
+    scripting: bool,
+    /// Serializes script runs
+    script_lock: Mutex<()>,
+    next_token: AtomicU64,
+    pending: Arc<Mutex<PendingReport>>,
+    /// Last script report and when it was received
+    report: Mutex<Option<(Instant, Vec<KwinScriptWindow>)>>,
+}
+
+/// Receives reports from the windows script.
+struct ScriptCallback {
+    pending: Arc<Mutex<PendingReport>>,
+}
+
+#[zbus::interface(name = "dev.zlaunch.KWinScript")]
+impl ScriptCallback {
+    /// Called by the script with the windows as JSON.
+    fn report(&self, token: String, json: String) {
+        if let Some((expected, tx)) = &*self.pending.lock().unwrap()
+            && *expected == token
+        {
+            let _ = tx.send(json);
+        }
+    }
 }
 
 impl KwinCompositor {
     /// Create a new KWin compositor client.
     ///
-    /// Returns None if KDE session is not detected or KWin is not available.
-    pub fn new() -> Option<Self> {
-        // Check if we're in a KDE session
-        if std::env::var("KDE_SESSION_VERSION").is_err() {
-            return None;
-        }
-
+    /// Fails if KWin is not available on the session bus.
+    pub fn new() -> Result<Self> {
         // Connect to session D-Bus
-        let connection = Connection::session().ok()?;
+        let connection = Connection::session().context("Failed to connect to session bus")?;
 
         // Verify KWin is available by calling supportInformation
-        let kwin_proxy = Proxy::new(&connection, "org.kde.KWin", "/KWin", "org.kde.KWin").ok()?;
+        let kwin_proxy = Proxy::new(&connection, "org.kde.KWin", "/KWin", "org.kde.KWin")
+            .context("Failed to create KWin proxy")?;
+
+        let _: String = kwin_proxy
+            .call("supportInformation", &())
+            .context("KWin is not running")?;
+
+        let pending = Arc::new(Mutex::new(None));
+        let callback = ScriptCallback {
+            pending: pending.clone(),
+        };
+        let callback_registered = connection
+            .object_server()
+            .at(CALLBACK_PATH, callback)
+            .is_ok();
+
+        let mut compositor = Self {
+            connection,
+            scripting: false,
+            script_lock: Mutex::new(()),
+            next_token: AtomicU64::new(0),
+            pending,
+            report: Mutex::new(None),
+        };
+
+        // Check that the scripting interface is there before relying on it
+        if callback_registered {
+            match compositor.check_scripting() {
+                Ok(()) => compositor.scripting = true,
+                Err(e) => debug!(%e, "KWin scripting unavailable, using WindowsRunner"),
+            }
+        }
+
+        Ok(compositor)
+    }
 
-        let _: String = kwin_proxy.call("supportInformation", &()).ok()?;
+    /// Check that scripts can be loaded, without running one.
+    fn check_scripting(&self) -> Result<()> {
+        let _: bool = self
+            .scripting()?
+            .call("isScriptLoaded", &(SCRIPT_PLUGIN,))
+            .context("Failed to call Scripting.isScriptLoaded")?;
+        Ok(())
+    }
+
+    /// Get the windows reported by the windows script.
+    ///
+    /// A recent report is reused instead of running the script again.
+    fn script_windows(&self) -> Result<Vec<KwinScriptWindow>> {
+        let _guard = self.script_lock.lock().unwrap();
+
+        if let Some((received, windows)) = &*self.report.lock().unwrap()
+            && received.elapsed() < REPORT_MAX_AGE
+        {
+            return Ok(windows.clone());
+        }
 
-        Some(Self { connection })
+        let windows = parse_script_report(&self.run_windows_script()?)?;
+        *self.report.lock().unwrap() = Some((Instant::now(), windows.clone()));
+        Ok(windows)
+    }
+
+    /// Forget the last script report, after changing windows ourselves.
+    fn invalidate_report(&self) {
+        *self.report.lock().unwrap() = None;
+    }
+
+    /// Run the windows script and wait for its report.
+    fn run_windows_script(&self) -> Result<String> {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed).to_string();
+        let service = self
+            .connection
+            .unique_name()
+            .context("Session bus connection has no unique name")?
+            .to_string();
+        let script = WINDOWS_SCRIPT
+            .replace("%SERVICE%", &service)
+            .replace("%PATH%", CALLBACK_PATH)
+            .replace("%INTERFACE%", CALLBACK_INTERFACE)
+            .replace("%TOKEN%", &token);
+
+        // The runtime directory is private to the user, unlike the temp dir
+        let path = dirs::runtime_dir()
+            .context("XDG_RUNTIME_DIR is not set")?
+            .join(format!("zlaunch-kwin-{}-{}.js", std::process::id(), token));
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)
+            .and_then(|mut file| {
+                file.write_all(script.as_bytes()).inspect_err(|_| {
+                    let _ = fs::remove_file(&path);
+                })
+            });
+        written.context("Failed to write KWin script")?;
+
+        let (tx, rx) = mpsc::channel();
+        *self.pending.lock().unwrap() = Some((token, tx));
+
+        let result = self.start_script(&path.to_string_lossy()).and_then(|()| {
+            rx.recv_timeout(SCRIPT_TIMEOUT)
+                .context("KWin script did not report back")
+        });
+
+        // The script may still be running if it did not report back
+        let _ = self.unload_script();
+        *self.pending.lock().unwrap() = None;
+        let _ = fs::remove_file(&path);
+
+        result
+    }
+
+    /// Create a proxy for the scripting interface.
+    fn scripting(&self) -> Result<Proxy<'_>> {
+        Proxy::new(
+            &self.connection,
+            "org.kde.KWin",
+            "/Scripting",
+            "org.kde.kwin.Scripting",
+        )
+        .context("Failed to create Scripting proxy")
+    }
+
+    /// Load and run a script file.
+    fn start_script(&self, path: &str) -> Result<()> {
+        // A previous run may have left the script loaded
+        self.unload_script()?;
+
+        let id: i32 = self
+            .scripting()?
+            .call("loadScript", &(path, SCRIPT_PLUGIN))
+            .context("Failed to call Scripting.loadScript")?;
+        if id < 0 {
+            bail!("KWin failed to load the windows script");
+        }
+
+        // KWin 6 and KWin 5 put loaded scripts at different paths
+        [format!("/Scripting/Script{}", id), format!("/{}", id)]
+            .iter()
+            .find_map(|script_path| {
+                let script = Proxy::new(
+                    &self.connection,
+                    "org.kde.KWin",
+                    script_path.as_str(),
+                    "org.kde.kwin.Script",
+                )
+                .ok()?;
+                script.call::<_, _, ()>("run", &()).ok()
+            })
+            .context("Failed to run the windows script")
+    }
+
+    /// Unload the windows script.
+    fn unload_script(&self) -> Result<()> {
+        let _: bool = self
+            .scripting()?
+            .call("unloadScript", &(SCRIPT_PLUGIN,))
+            .context("Failed to call Scripting.unloadScript")?;
+        Ok(())
+    }
+
+    /// Create a proxy for the virtual desktop manager.
+    fn desktop_manager(&self) -> Result<Proxy<'_>> {
+        Proxy::new(
+            &self.connection,
+            "org.kde.KWin",
+            "/VirtualDesktopManager",
+            "org.kde.KWin.VirtualDesktopManager",
+        )
+        .context("Failed to create VirtualDesktopManager proxy")
     }
 
     /// List windows using the WindowsRunner krunner interface.
@@ -88,6 +314,8 @@ impl KwinCompositor {
                         class,
                         workspace: 1,   // WindowsRunner doesn't expose workspace info
                         focused: false, // We can't easily determine this from krunner
+                        urgent: false,
+                        last_focused: None,
                     }
                 },
             )
@@ -96,8 +324,8 @@ impl KwinCompositor {
         Ok(windows)
     }
 
-    /// Focus a window using the WindowsRunner Run method.
-    fn focus_window_via_runner(&self, window_id: &str) -> Result<()> {
+    /// Run a window action using the WindowsRunner Run method.
+    fn run_window_action(&self, action: RunnerAction, window_id: &str) -> Result<()> {
         let runner_proxy = Proxy::new(
             &self.connection,
             "org.kde.KWin",
@@ -106,10 +334,10 @@ impl KwinCompositor {
         )
         .context("Failed to create WindowsRunner proxy")?;
 
-        // match_id needs the "0_" prefix for the activate action
-        let match_id = format!("0_{}", window_id);
+        // The match_id prefix selects the action
+        let match_id = format!("{}_{}", action as u8, window_id);
 
-        // Run with empty action_id (default action = activate)
+        // Run with empty action_id (the default action of the match)
         let _: () = runner_proxy
             .call("Run", &(&match_id, ""))
             .context("Failed to call WindowsRunner.Run")?;
@@ -118,14 +346,105 @@ impl KwinCompositor {
     }
 }
 
+/// A window reported by the windows script.
+#[derive(Debug, Clone, Deserialize)]
+struct KwinScriptWindow {
+    id: String,
+    title: String,
+    class: String,
+    /// Virtual desktop numbers, empty when on all desktops
+    desktops: Vec<i32>,
+    /// Name of the output the window is on, empty if unknown
+    output: String,
+    active: bool,
+    minimized: bool,
+    urgent: bool,
+}
+
+/// Parse the windows script report.
+fn parse_script_report(json: &str) -> Result<Vec<KwinScriptWindow>> {
+    serde_json::from_str(json).context("Failed to parse KWin script report")
+}
+
+/// Convert reported windows, topmost first and minimized windows last.
+fn to_window_infos(windows: Vec<KwinScriptWindow>) -> Vec<WindowInfo> {
+    let mut windows: Vec<KwinScriptWindow> = windows
+        .into_iter()
+        .filter(|w| !is_launcher_window(&w.class))
+        .collect();
+    windows.sort_by_key(|w| w.minimized);
+
+    windows
+        .into_iter()
+        .map(|w| WindowInfo {
+            address: w.id,
+            title: get_display_title(&w.title, &w.class),
+            class: w.class,
+            // Windows on all desktops are not counted on any of them
+            workspace: w.desktops.first().copied().unwrap_or(-1),
+            focused: w.active,
+            urgent: w.urgent,
+            last_focused: None,
+        })
+        .collect()
+}
+
 impl Compositor for KwinCompositor {
     fn list_windows(&self) -> Result<Vec<WindowInfo>> {
+        if self.scripting {
+            match self.script_windows() {
+                Ok(windows) => return Ok(to_window_infos(windows)),
+                Err(e) => warn!(%e, "KWin script failed, falling back to WindowsRunner"),
+            }
+        }
         self.list_windows_via_runner()
     }
 
+    fn list_workspaces(&self) -> Result<Vec<WorkspaceInfo>> {
+        if !self.scripting {
+            return Ok(Vec::new());
+        }
+
+        let manager = self.desktop_manager()?;
+        // (position, id, name) of each desktop
+        let desktops: Vec<(u32, String, String)> = manager
+            .get_property("desktops")
+            .context("Failed to get virtual desktops")?;
+        let current: String = manager
+            .get_property("current")
+            .context("Failed to get current virtual desktop")?;
+        let windows = self.list_windows()?;
+
+        Ok(desktops
+            .into_iter()
+            .map(|(position, id, name)| {
+                // Desktop numbers are 1-based positions
+                let number = position as i32 + 1;
+                WorkspaceInfo {
+                    focused: id == current,
+                    address: id,
+                    id: number,
+                    name,
+                    output: None,
+                    windows: windows.iter().filter(|w| w.workspace == number).count(),
+                    special: false,
+                }
+            })
+            .collect())
+    }
+
+    fn focus_workspace(&self, address: &str) -> Result<()> {
+        self.invalidate_report();
+        self.desktop_manager()?
+            .set_property("current", address)
+            .context("Failed to switch virtual desktop")
+    }
+
     fn focus_window(&self, window_id: &str) -> Result<()> {
+        self.invalidate_report();
+
         // First try the krunner approach
-        if let Ok(()) = self.focus_window_via_runner(window_id) {
+        if let Ok(()) = self.run_window_action(RunnerAction::Activate, window_id) {
             return Ok(());
         }
 
@@ -148,11 +467,90 @@ impl Compositor for KwinCompositor {
         }
     }
 
+    fn close_window(&self, window_id: &str) -> Result<()> {
+        self.invalidate_report();
+        self.run_window_action(RunnerAction::Close, window_id)
+    }
+
+    fn toggle_fullscreen(&self, window_id: &str) -> Result<()> {
+        self.invalidate_report();
+        self.run_window_action(RunnerAction::Fullscreen, window_id)
+    }
+
+    fn focused_output(&self) -> Result<Option<String>> {
+        let kwin_proxy = Proxy::new(&self.connection, "org.kde.KWin", "/KWin", "org.kde.KWin")
+            .context("Failed to create KWin proxy")?;
+        let name = match kwin_proxy.call::<_, _, String>("activeOutputName", &()) {
+            Ok(name) => name,
+            // Older KWin has no activeOutputName, use the active window's output
+            Err(e) if self.scripting => {
+                debug!(%e, "Failed to get the active KWin output, using the active window");
+                self.script_windows()?
+                    .into_iter()
+                    .find(|w| w.active)
+                    .map(|w| w.output)
+                    .unwrap_or_default()
+            }
+            Err(e) => return Err(e).context("Failed to get the active KWin output"),
+        };
+        Ok((!name.is_empty()).then_some(name))
+    }
+
     fn name(&self) -> &'static str {
         "KWin"
     }
 
     fn capabilities(&self) -> CompositorCapabilities {
-        CompositorCapabilities::limited()
+        CompositorCapabilities {
+            workspace_info: self.scripting,
+            focus_tracking: self.scripting,
+            ..CompositorCapabilities::limited()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script_windows() {
+        let json = r#"[
+            {"id": "{3c5a}", "title": "Settings", "class": "systemsettings",
+             "desktops": [1], "output": "DP-1", "active": false,
+             "minimized": true, "urgent": false},
+            {"id": "{6f1c}", "title": "Dolphin", "class": "org.kde.dolphin",
+             "desktops": [2], "output": "DP-1", "active": true,
+             "minimized": false, "urgent": false},
+            {"id": "{9a2e}", "title": "", "class": "konsole",
+             "desktops": [], "output": "", "active": false,
+             "minimized": false, "urgent": true},
+            {"id": "{0b7d}", "title": "zlaunch", "class": "zlaunch",
+             "desktops": [1], "output": "HDMI-A-1", "active": false,
+             "minimized": false, "urgent": false}
+        ]"#;
+        let report = parse_script_report(json).unwrap();
+        assert_eq!(report[1].output, "DP-1");
+        let windows = to_window_infos(report);
+
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].address, "{6f1c}");
+        assert_eq!(windows[0].workspace, 2);
+        assert!(windows[0].focused);
+
+        // Untitled window on all desktops
+        assert_eq!(windows[1].title, "konsole");
+        assert_eq!(windows[1].workspace, -1);
+        assert!(windows[1].urgent);
+
+        // Minimized windows are listed last
+        assert_eq!(windows[2].address, "{3c5a}");
+    }
+
+    #[test]
+    fn test_windows_script_placeholders() {
+        for placeholder in ["%SERVICE%", "%PATH%", "%INTERFACE%", "%TOKEN%"] {
+            assert!(WINDOWS_SCRIPT.contains(placeholder), "{}", placeholder);
+        }
     }
 }
@@ -0,0 +1,33 @@
+// Reports the open windows to zlaunch over D-Bus.
+//
+// Loaded through org.kde.kwin.Scripting. zlaunch fills in the %PLACEHOLDERS%
+// before loading it. Works with both the KWin 6 and KWin 5 scripting APIs.
+
+const windows = workspace.windowList ? workspace.windowList() : workspace.clientList();
+const active = workspace.activeWindow !== undefined ? workspace.activeWindow : workspace.activeClient;
+
+function desktopNumbers(window) {
+    // KWin 6: list of virtual desktops, empty when on all desktops
+    if (window.desktops !== undefined) {
+        return window.desktops.map(desktop => desktop.x11DesktopNumber);
+    }
+    // KWin 5: desktop number, -1 when on all desktops
+    return window.desktop > 0 ? [window.desktop] : [];
+}
+
+const report = windows
+    .filter(window => window.normalWindow && !window.skipTaskbar)
+    // Stacking order is bottom to top; list the topmost window first
+    .reverse()
+    .map(window => ({
+        id: window.internalId.toString(),
+        title: String(window.caption),
+        class: String(window.resourceClass),
+        desktops: desktopNumbers(window),
+        output: window.output ? String(window.output.name) : "",
+        active: window === active,
+        minimized: Boolean(window.minimized),
+        urgent: Boolean(window.demandsAttention),
+    }));
+
+callDBus("%SERVICE%", "%PATH%", "%INTERFACE%", "Report", "%TOKEN%", JSON.stringify(report));
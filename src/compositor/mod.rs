@@ -1,22 +1,29 @@
 //! Compositor abstraction for window management.
 //!
 //! This module provides a trait-based abstraction for interacting with
-//! Wayland compositors to list windows and switch focus. Implementations
-//! are provided for Hyprland (IPC socket), Niri (IPC socket), and KDE/KWin (DBus).
+//! Wayland compositors to list, focus and manage windows. Implementations
+//! are provided for Hyprland (IPC socket), Niri (IPC socket), Sway (i3-ipc
+//! socket), KDE/KWin (DBus), and other wlroots-based compositors
+//! (foreign-toplevel protocols).
 
 pub mod base;
 mod detect;
 pub mod error;
+mod foreign_toplevel;
 pub mod hyprland;
 mod kwin;
 mod niri;
 mod noop;
+mod sway;
+mod tracker;
 
 pub use base::CompositorCapabilities;
 pub use detect::detect_compositor;
 pub use error::CompositorError;
+pub use tracker::WindowTracker;
 
 use std::fmt;
+use std::time::Instant;
 
 /// Information about an open window from the compositor.
 #[derive(Debug, Clone)]
@@ -31,6 +38,53 @@ pub struct WindowInfo {
     pub workspace: i32,
     /// Whether this window is currently focused
     pub focused: bool,
+    /// Whether this window demands attention
+    pub urgent: bool,
+    /// When this window was last focused, if seen by the window tracker
+    pub last_focused: Option<Instant>,
+}
+
+/// Information about a workspace from the compositor.
+#[derive(Debug, Clone)]
+pub struct WorkspaceInfo {
+    /// Workspace identifier used to focus it (compositor-specific)
+    pub address: String,
+    /// Workspace ID, matching `WindowInfo::workspace`
+    pub id: i32,
+    /// Workspace name (e.g., "3", "web", or "scratchpad" for special workspaces)
+    pub name: String,
+    /// Output (monitor) the workspace is on
+    pub output: Option<String>,
+    /// Number of windows on the workspace
+    pub windows: usize,
+    /// Whether this is a special (scratchpad) workspace
+    pub special: bool,
+    /// Whether this workspace is currently focused
+    pub focused: bool,
+}
+
+/// An event from the compositor's event stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompositorEvent {
+    /// The event stream is connected, so later changes will be reported
+    Connected,
+    /// Windows were opened, closed or moved
+    WindowsChanged,
+    /// A window's title changed
+    WindowTitleChanged { address: String, title: String },
+    /// A window was focused
+    WindowFocused(String),
+    /// A window started or stopped demanding attention
+    WindowUrgencyChanged { address: String, urgent: bool },
+}
+
+/// Workspace to launch an application on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceTarget {
+    /// Workspace with the given number
+    Number(i32),
+    /// A new empty workspace
+    Empty,
 }
 
 /// Trait for compositor window management operations.
@@ -49,6 +103,85 @@ pub trait Compositor: Send + Sync {
     /// The address format is compositor-specific.
     fn focus_window(&self, window_id: &str) -> anyhow::Result<()>;
 
+    /// Follow the compositor's event stream, calling `on_event` for each event.
+    ///
+    /// Sends [`CompositorEvent::Connected`] first, once events are being
+    /// received. Blocks until the stream ends. Only supported when the
+    /// `event_stream` capability is set.
+    fn subscribe(&self, _on_event: &mut dyn FnMut(CompositorEvent)) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not provide an event stream", self.name())
+    }
+
+    /// List all workspaces, including special (scratchpad) workspaces.
+    ///
+    /// Default implementation returns no workspaces.
+    fn list_workspaces(&self) -> anyhow::Result<Vec<WorkspaceInfo>> {
+        Ok(Vec::new())
+    }
+
+    /// Switch to a workspace by its address.
+    ///
+    /// Special workspaces are toggled instead.
+    fn focus_workspace(&self, _address: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support switching workspaces", self.name())
+    }
+
+    /// Close a window by its address.
+    fn close_window(&self, _window_id: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support closing windows", self.name())
+    }
+
+    /// Move a window to a workspace, or to the current workspace if `None`.
+    fn move_window_to_workspace(
+        &self,
+        _window_id: &str,
+        _workspace: Option<i32>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support moving windows", self.name())
+    }
+
+    /// Toggle whether a window is floating.
+    fn toggle_floating(&self, _window_id: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support floating windows", self.name())
+    }
+
+    /// Toggle whether a window is fullscreen.
+    fn toggle_fullscreen(&self, _window_id: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support fullscreen windows", self.name())
+    }
+
+    /// Toggle whether a window is pinned (shown on all workspaces).
+    fn pin_window(&self, _window_id: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support pinning windows", self.name())
+    }
+
+    /// Get the name of the focused output (e.g. `DP-1`).
+    ///
+    /// Default implementation returns `None`, letting the compositor place
+    /// the launcher.
+    fn focused_output(&self) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Get the pointer position relative to the focused output.
+    ///
+    /// Default implementation returns `None`.
+    fn cursor_position(&self) -> anyhow::Result<Option<(f32, f32)>> {
+        Ok(None)
+    }
+
+    /// Launch a shell command line on a workspace.
+    ///
+    /// Returns `Ok(false)` if the compositor cannot launch onto a workspace,
+    /// in which case the caller launches the command normally.
+    fn launch_on_workspace(
+        &self,
+        _command: &str,
+        _workspace: WorkspaceTarget,
+    ) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
     /// Get the compositor name for logging/debugging.
     fn name(&self) -> &'static str;
 
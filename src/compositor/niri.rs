@@ -1,19 +1,24 @@
-use super::base::{CompositorCapabilities, get_display_title, is_launcher_window};
-use super::{Compositor, WindowInfo};
+use super::base::{CompositorCapabilities, get_display_title, is_launcher_window, socket_path};
+use super::{Compositor, CompositorEvent, WindowInfo, WorkspaceInfo};
 use anyhow::{Context, Result, anyhow, bail};
 use serde::Deserialize;
+use serde_json::json;
 use std::io::{BufRead, Write};
 use std::os::unix::net::UnixStream;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct NiriCompositor {
     socket_path: PathBuf,
 }
 
 impl NiriCompositor {
-    pub fn new() -> Option<Self> {
-        Some(Self {
-            socket_path: std::env::var("NIRI_SOCKET").ok()?.into(),
+    /// Create a new Niri compositor client.
+    ///
+    /// Uses the given socket path, or NIRI_SOCKET. Fails if neither is set or
+    /// the socket does not exist.
+    pub fn new(socket: Option<&Path>) -> Result<Self> {
+        Ok(Self {
+            socket_path: socket_path(socket, "NIRI_SOCKET")?,
         })
     }
 
@@ -34,6 +39,83 @@ impl NiriCompositor {
 
         Ok(response)
     }
+
+    /// Run an action, failing if Niri replies with an error.
+    fn run_action(&self, action: serde_json::Value) -> Result<()> {
+        let request = json!({ "Action": action });
+        let response = self.send_command(&format!("{}\n", request))?;
+
+        let reply: std::result::Result<serde_json::Value, serde_json::Value> =
+            serde_json::from_str(&response).context("Failed to parse Niri action reply")?;
+        if let Err(e) = reply {
+            bail!("Niri action failed: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Get all workspaces.
+    fn workspaces(&self) -> Result<Vec<NiriWorkspace>> {
+        let json_string = self.send_command("\"Workspaces\"\n")?;
+
+        let niri_result: std::result::Result<NiriWorkspacesReply, serde_json::Value> =
+            serde_json::from_str(&json_string).context("Failed to parse Niri workspaces JSON")?;
+
+        let Ok(niri_reply) = niri_result else {
+            bail!("Niri returned an error to Workspaces request");
+        };
+
+        Ok(niri_reply.workspaces)
+    }
+
+    /// Get the ID of the focused workspace.
+    fn focused_workspace_id(&self) -> Result<u64> {
+        self.workspaces()?
+            .into_iter()
+            .find(|workspace| workspace.is_focused)
+            .map(|workspace| workspace.id)
+            .ok_or(anyhow!("Niri reported no focused workspace"))
+    }
+}
+
+/// Parse an event from the event stream.
+///
+/// Each event is an object with a single key naming the event.
+fn parse_event(line: &str) -> Option<CompositorEvent> {
+    let event: serde_json::Value = serde_json::from_str(line).ok()?;
+    let (name, body) = event.as_object()?.iter().next()?;
+    match name.as_str() {
+        "WindowsChanged" | "WindowOpenedOrChanged" | "WindowClosed" => {
+            Some(CompositorEvent::WindowsChanged)
+        }
+        // The ID is null when no window is focused
+        "WindowFocusChanged" => Some(CompositorEvent::WindowFocused(
+            body.get("id")?.as_u64()?.to_string(),
+        )),
+        "WindowUrgencyChanged" => Some(CompositorEvent::WindowUrgencyChanged {
+            address: body.get("id")?.as_u64()?.to_string(),
+            urgent: body.get("urgent")?.as_bool()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Parse the reply to a `FocusedOutput` request.
+fn parse_focused_output(json_string: &str) -> Result<Option<String>> {
+    let niri_result: std::result::Result<NiriFocusedOutputReply, serde_json::Value> =
+        serde_json::from_str(json_string).context("Failed to parse Niri output JSON")?;
+
+    let Ok(niri_reply) = niri_result else {
+        bail!("Niri returned an error to FocusedOutput request");
+    };
+
+    Ok(niri_reply.output.map(|output| output.name))
+}
+
+/// Parse a window address into a Niri window ID.
+fn parse_window_id(address: &str) -> Result<u64> {
+    address
+        .parse()
+        .with_context(|| format!("Invalid Niri window ID: {}", address))
 }
 
 impl Compositor for NiriCompositor {
@@ -48,6 +130,66 @@ impl Compositor for NiriCompositor {
         Ok(())
     }
 
+    fn subscribe(&self, on_event: &mut dyn FnMut(CompositorEvent)) -> Result<()> {
+        let mut stream = UnixStream::connect(&self.socket_path)
+            .with_context(|| format!("Failed to connect to Niri socket: {:?}", self.socket_path))?;
+
+        stream
+            .write_all(b"\"EventStream\"\n")
+            .context("Failed to request Niri event stream")?;
+
+        // The first line is the reply to the request, followed by events
+        let mut lines = std::io::BufReader::new(stream).lines();
+        lines
+            .next()
+            .transpose()
+            .context("Failed to read from Niri event stream")?
+            .context("Niri closed the event stream")?;
+        on_event(CompositorEvent::Connected);
+
+        for line in lines {
+            let line = line.context("Failed to read from Niri event stream")?;
+            if let Some(event) = parse_event(&line) {
+                on_event(event);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn close_window(&self, window_id: &str) -> Result<()> {
+        let id = parse_window_id(window_id)?;
+        self.run_action(json!({ "CloseWindow": { "id": id } }))
+    }
+
+    fn move_window_to_workspace(&self, window_id: &str, workspace: Option<i32>) -> Result<()> {
+        let id = parse_window_id(window_id)?;
+        let reference = match workspace {
+            Some(index) => {
+                let index = u8::try_from(index).context("Invalid Niri workspace index")?;
+                json!({ "Index": index })
+            }
+            None => json!({ "Id": self.focused_workspace_id()? }),
+        };
+        self.run_action(json!({
+            "MoveWindowToWorkspace": {
+                "window_id": id,
+                "reference": reference,
+                "focus": false,
+            }
+        }))
+    }
+
+    fn toggle_floating(&self, window_id: &str) -> Result<()> {
+        let id = parse_window_id(window_id)?;
+        self.run_action(json!({ "ToggleWindowFloating": { "id": id } }))
+    }
+
+    fn toggle_fullscreen(&self, window_id: &str) -> Result<()> {
+        let id = parse_window_id(window_id)?;
+        self.run_action(json!({ "FullscreenWindow": { "id": id } }))
+    }
+
     fn list_windows(&self) -> Result<Vec<WindowInfo>> {
         let json_string = self.send_command("\"Windows\"\n")?;
 
@@ -70,14 +212,53 @@ impl Compositor for NiriCompositor {
                 class: window.app_id,
                 workspace: window.workspace_id as i32,
                 focused: window.is_focused,
+                urgent: window.is_urgent,
+                last_focused: None,
             });
         }
 
         Ok(window_info)
     }
 
+    fn list_workspaces(&self) -> Result<Vec<WorkspaceInfo>> {
+        let windows = self.list_windows()?;
+
+        Ok(self
+            .workspaces()?
+            .into_iter()
+            .map(|workspace| {
+                let id = workspace.id as i32;
+                WorkspaceInfo {
+                    address: workspace.id.to_string(),
+                    id,
+                    name: workspace.name.unwrap_or_else(|| workspace.idx.to_string()),
+                    output: workspace.output,
+                    windows: windows.iter().filter(|w| w.workspace == id).count(),
+                    special: false,
+                    focused: workspace.is_focused,
+                }
+            })
+            .collect())
+    }
+
+    fn focus_workspace(&self, address: &str) -> Result<()> {
+        let id: u64 = address
+            .parse()
+            .with_context(|| format!("Invalid Niri workspace ID: {}", address))?;
+        self.run_action(json!({ "FocusWorkspace": { "reference": { "Id": id } } }))
+    }
+
+    fn focused_output(&self) -> Result<Option<String>> {
+        let json_string = self.send_command("\"FocusedOutput\"\n")?;
+        parse_focused_output(&json_string)
+    }
+
     fn capabilities(&self) -> CompositorCapabilities {
-        CompositorCapabilities::full()
+        // Niri has no sticky windows
+        CompositorCapabilities {
+            pin_window: false,
+            ..CompositorCapabilities::full()
+        }
     }
 }
 
@@ -94,4 +275,71 @@ struct NiriWindow {
     app_id: String,
     workspace_id: i64,
     is_focused: bool,
+    #[serde(default)]
+    is_urgent: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriWorkspacesReply {
+    #[serde(rename = "Workspaces")]
+    workspaces: Vec<NiriWorkspace>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriWorkspace {
+    id: u64,
+    idx: u8,
+    name: Option<String>,
+    output: Option<String>,
+    is_focused: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriFocusedOutputReply {
+    #[serde(rename = "FocusedOutput")]
+    output: Option<NiriOutput>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriOutput {
+    name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event() {
+        assert!(parse_event(r#"{"Ok":"Handled"}"#).is_none());
+        assert!(matches!(
+            parse_event(r#"{"WindowClosed":{"id":7}}"#),
+            Some(CompositorEvent::WindowsChanged)
+        ));
+        assert!(matches!(
+            parse_event(r#"{"WindowFocusChanged":{"id":7}}"#),
+            Some(CompositorEvent::WindowFocused(address)) if address == "7"
+        ));
+        assert!(parse_event(r#"{"WindowFocusChanged":{"id":null}}"#).is_none());
+        assert!(matches!(
+            parse_event(r#"{"WindowUrgencyChanged":{"id":7,"urgent":false}}"#),
+            Some(CompositorEvent::WindowUrgencyChanged { address, urgent: false })
+                if address == "7"
+        ));
+        assert!(parse_event(r#"{"WorkspaceActivated":{"id":1,"focused":true}}"#).is_none());
+    }
+
+    #[test]
+    fn test_parse_focused_output() {
+        let reply = r#"{"Ok":{"FocusedOutput":{"name":"DP-1","make":"Dell","model":"U2720Q"}}}"#;
+        assert_eq!(
+            parse_focused_output(reply).unwrap().as_deref(),
+            Some("DP-1")
+        );
+        assert_eq!(
+            parse_focused_output(r#"{"Ok":{"FocusedOutput":null}}"#).unwrap(),
+            None
+        );
+        assert!(parse_focused_output(r#"{"Err":"no outputs"}"#).is_err());
+    }
 }
@@ -0,0 +1,599 @@
+//! Sway compositor implementation using the i3-ipc protocol.
+
+use super::base::{CompositorCapabilities, get_display_title, is_launcher_window, socket_path};
+use super::{Compositor, CompositorEvent, WindowInfo, WorkspaceInfo};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+/// Magic string starting every i3-ipc message.
+const MAGIC: &[u8] = b"i3-ipc";
+
+/// i3-ipc message types.
+const RUN_COMMAND: u32 = 0;
+const GET_WORKSPACES: u32 = 1;
+const SUBSCRIBE: u32 = 2;
+const GET_OUTPUTS: u32 = 3;
+const GET_TREE: u32 = 4;
+
+/// Event type for window events (events have the high bit set).
+const WINDOW_EVENT: u32 = 0x8000_0003;
+
+/// Name of the workspace holding scratchpad windows.
+const SCRATCHPAD: &str = "__i3_scratch";
+
+/// Sway compositor client using IPC socket communication.
+pub struct SwayCompositor {
+    socket_path: PathBuf,
+}
+
+impl SwayCompositor {
+    /// Create a new Sway compositor client.
+    ///
+    /// Uses the given socket path, or SWAYSOCK. Fails if neither is set or
+    /// the socket does not exist.
+    pub fn new(socket: Option<&Path>) -> Result<Self> {
+        Ok(Self {
+            socket_path: socket_path(socket, "SWAYSOCK")?,
+        })
+    }
+
+    fn connect(&self) -> Result<UnixStream> {
+        UnixStream::connect(&self.socket_path)
+            .with_context(|| format!("Failed to connect to Sway socket: {:?}", self.socket_path))
+    }
+
+    /// Send a message to Sway and receive the reply payload.
+    fn send_message(&self, kind: u32, payload: &str) -> Result<Vec<u8>> {
+        let mut stream = self.connect()?;
+        write_message(&mut stream, kind, payload.as_bytes())
+            .context("Failed to write message to Sway socket")?;
+
+        let (reply_kind, reply) =
+            read_message(&mut stream).context("Failed to read reply from Sway socket")?;
+        if reply_kind != kind {
+            bail!(
+                "Unexpected Sway reply type {} to message {}",
+                reply_kind,
+                kind
+            );
+        }
+        Ok(reply)
+    }
+
+    /// Run a command, failing if Sway reports an error.
+    fn run_command(&self, command: &str) -> Result<()> {
+        let reply = self.send_message(RUN_COMMAND, command)?;
+        let results: Vec<SwayCommandResult> =
+            serde_json::from_slice(&reply).context("Failed to parse Sway command reply")?;
+
+        if let Some(failed) = results.into_iter().find(|r| !r.success) {
+            bail!(
+                "Sway command '{}' failed: {}",
+                command,
+                failed.error.unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+
+    /// Run a command on a window.
+    fn run_window_command(&self, window_id: &str, command: &str) -> Result<()> {
+        let id = parse_window_id(window_id)?;
+        self.run_command(&format!("[con_id={}] {}", id, command))
+    }
+
+    /// Get the layout tree.
+    fn tree(&self) -> Result<SwayNode> {
+        let reply = self.send_message(GET_TREE, "")?;
+        serde_json::from_slice(&reply).context("Failed to parse Sway tree JSON")
+    }
+
+    /// Get all workspaces (Sway leaves out the scratchpad).
+    fn workspaces(&self) -> Result<Vec<SwayWorkspace>> {
+        let reply = self.send_message(GET_WORKSPACES, "")?;
+        serde_json::from_slice(&reply).context("Failed to parse Sway workspaces JSON")
+    }
+}
+
+/// Write an i3-ipc message.
+fn write_message(writer: &mut impl Write, kind: u32, payload: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "i3-ipc payload too large"))?;
+
+    let mut message = Vec::with_capacity(MAGIC.len() + 8 + payload.len());
+    message.extend_from_slice(MAGIC);
+    message.extend_from_slice(&len.to_ne_bytes());
+    message.extend_from_slice(&kind.to_ne_bytes());
+    message.extend_from_slice(payload);
+    writer.write_all(&message)
+}
+
+/// Read an i3-ipc message, returning its type and payload.
+fn read_message(reader: &mut impl Read) -> io::Result<(u32, Vec<u8>)> {
+    let mut header = [0u8; 14];
+    reader.read_exact(&mut header)?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Invalid i3-ipc magic string",
+        ));
+    }
+
+    let len = u32::from_ne_bytes(header[6..10].try_into().unwrap());
+    let kind = u32::from_ne_bytes(header[10..14].try_into().unwrap());
+
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    Ok((kind, payload))
+}
+
+/// Parse an event from the event stream.
+fn parse_event(kind: u32, payload: &[u8]) -> Option<CompositorEvent> {
+    if kind != WINDOW_EVENT {
+        return None;
+    }
+
+    let event: SwayWindowEvent = serde_json::from_slice(payload).ok()?;
+    let address = event.container.id.to_string();
+    match event.change.as_str() {
+        "new" | "close" | "move" => Some(CompositorEvent::WindowsChanged),
+        "title" => Some(CompositorEvent::WindowTitleChanged {
+            address,
+            title: event.container.name.unwrap_or_default(),
+        }),
+        "focus" => Some(CompositorEvent::WindowFocused(address)),
+        "urgent" => Some(CompositorEvent::WindowUrgencyChanged {
+            address,
+            urgent: event.container.urgent,
+        }),
+        _ => None,
+    }
+}
+
+/// Parse a window address into a Sway container ID.
+fn parse_window_id(address: &str) -> Result<i64> {
+    address
+        .parse()
+        .with_context(|| format!("Invalid Sway container ID: {}", address))
+}
+
+/// Quote a workspace name for use in a command.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Get a workspace ID from a node ID.
+///
+/// Workspace numbers are not unique, as named workspaces and the scratchpad
+/// have none, so workspaces are identified by their node ID instead.
+fn workspace_id(node_id: i64) -> i32 {
+    i32::try_from(node_id).unwrap_or(-1)
+}
+
+/// Collect the windows in a layout tree.
+fn tree_windows(tree: &SwayNode) -> Vec<WindowInfo> {
+    fn walk(node: &SwayNode, workspace: Option<&SwayNode>, out: &mut Vec<WindowInfo>) {
+        let workspace = if node.kind == "workspace" {
+            Some(node)
+        } else {
+            workspace
+        };
+
+        if node.is_view()
+            && let Some(workspace) = workspace
+        {
+            let class = node.class();
+            if !is_launcher_window(&class) {
+                let title = node.name.as_deref().unwrap_or_default();
+                out.push(WindowInfo {
+                    address: node.id.to_string(),
+                    title: get_display_title(title, &class),
+                    class,
+                    workspace: workspace_id(workspace.id),
+                    focused: node.focused,
+                    urgent: node.urgent,
+                    last_focused: None,
+                });
+            }
+        }
+
+        for child in node.nodes.iter().chain(&node.floating_nodes) {
+            walk(child, workspace, out);
+        }
+    }
+
+    let mut windows = Vec::new();
+    walk(tree, None, &mut windows);
+    windows
+}
+
+/// Find the scratchpad workspace in a layout tree.
+fn find_scratchpad(node: &SwayNode) -> Option<&SwayNode> {
+    if node.kind == "workspace" {
+        return (node.name.as_deref() == Some(SCRATCHPAD)).then_some(node);
+    }
+    node.nodes.iter().find_map(find_scratchpad)
+}
+
+impl Compositor for SwayCompositor {
+    fn list_windows(&self) -> Result<Vec<WindowInfo>> {
+        Ok(tree_windows(&self.tree()?))
+    }
+
+    fn focus_window(&self, window_id: &str) -> Result<()> {
+        // Focusing a hidden scratchpad window also shows it
+        self.run_window_command(window_id, "focus")
+    }
+
+    fn subscribe(&self, on_event: &mut dyn FnMut(CompositorEvent)) -> Result<()> {
+        let mut stream = self.connect()?;
+        write_message(&mut stream, SUBSCRIBE, br#"["window"]"#)
+            .context("Failed to subscribe to Sway events")?;
+
+        loop {
+            let (kind, payload) = match read_message(&mut stream) {
+                Ok(message) => message,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e).context("Failed to read from Sway event stream"),
+            };
+
+            if kind == SUBSCRIBE {
+                let reply: SwayCommandResult = serde_json::from_slice(&payload)
+                    .context("Failed to parse Sway subscribe reply")?;
+                if !reply.success {
+                    bail!("Sway rejected the event subscription");
+                }
+                on_event(CompositorEvent::Connected);
+            } else if let Some(event) = parse_event(kind, &payload) {
+                on_event(event);
+            }
+        }
+    }
+
+    fn list_workspaces(&self) -> Result<Vec<WorkspaceInfo>> {
+        let tree = self.tree()?;
+        let windows = tree_windows(&tree);
+        let count = |id: i32| windows.iter().filter(|w| w.workspace == id).count();
+
+        let mut workspaces: Vec<WorkspaceInfo> = self
+            .workspaces()?
+            .into_iter()
+            .map(|workspace| {
+                let id = workspace_id(workspace.id);
+                WorkspaceInfo {
+                    address: workspace.name.clone(),
+                    id,
+                    name: workspace.name,
+                    output: Some(workspace.output),
+                    windows: count(id),
+                    special: false,
+                    focused: workspace.focused,
+                }
+            })
+            .collect();
+
+        if let Some(scratchpad) = find_scratchpad(&tree) {
+            let id = workspace_id(scratchpad.id);
+            workspaces.push(WorkspaceInfo {
+                address: SCRATCHPAD.to_string(),
+                id,
+                name: "scratchpad".to_string(),
+                output: None,
+                windows: count(id),
+                special: true,
+                focused: false,
+            });
+        }
+        Ok(workspaces)
+    }
+
+    fn focus_workspace(&self, address: &str) -> Result<()> {
+        if address == SCRATCHPAD {
+            return self.run_command("scratchpad show");
+        }
+        self.run_command(&format!("workspace {}", quote(address)))
+    }
+
+    fn close_window(&self, window_id: &str) -> Result<()> {
+        self.run_window_command(window_id, "kill")
+    }
+
+    fn move_window_to_workspace(&self, window_id: &str, workspace: Option<i32>) -> Result<()> {
+        let target = match workspace {
+            Some(number) => format!("number {}", number),
+            None => {
+                let focused = self
+                    .workspaces()?
+                    .into_iter()
+                    .find(|workspace| workspace.focused)
+                    .ok_or(anyhow!("Sway reported no focused workspace"))?;
+                quote(&focused.name)
+            }
+        };
+        self.run_window_command(
+            window_id,
+            &format!("move container to workspace {}", target),
+        )
+    }
+
+    fn toggle_floating(&self, window_id: &str) -> Result<()> {
+        self.run_window_command(window_id, "floating toggle")
+    }
+
+    fn toggle_fullscreen(&self, window_id: &str) -> Result<()> {
+        self.run_window_command(window_id, "fullscreen toggle")
+    }
+
+    fn pin_window(&self, window_id: &str) -> Result<()> {
+        // Sway only keeps floating windows sticky
+        self.run_window_command(window_id, "sticky toggle")
+    }
+
+    fn focused_output(&self) -> Result<Option<String>> {
+        let reply = self.send_message(GET_OUTPUTS, "")?;
+        let outputs: Vec<SwayOutput> =
+            serde_json::from_slice(&reply).context("Failed to parse Sway outputs JSON")?;
+        Ok(outputs.into_iter().find(|o| o.focused).map(|o| o.name))
+    }
+
+    fn name(&self) -> &'static str {
+        "Sway"
+    }
+
+    fn capabilities(&self) -> CompositorCapabilities {
+        // Sway has no blur
+        CompositorCapabilities {
+            blur_support: false,
+            ..CompositorCapabilities::full()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayCommandResult {
+    success: bool,
+    error: Option<String>,
+}
+
+/// A node of the layout tree (root, output, workspace, container or view).
+#[derive(Debug, Deserialize)]
+struct SwayNode {
+    id: i64,
+    #[serde(rename = "type")]
+    kind: String,
+    name: Option<String>,
+    #[serde(default)]
+    focused: bool,
+    #[serde(default)]
+    urgent: bool,
+    /// Set for views only ("xdg_shell" or "xwayland")
+    shell: Option<String>,
+    app_id: Option<String>,
+    window_properties: Option<SwayWindowProperties>,
+    #[serde(default)]
+    nodes: Vec<SwayNode>,
+    #[serde(default)]
+    floating_nodes: Vec<SwayNode>,
+}
+
+impl SwayNode {
+    fn is_view(&self) -> bool {
+        self.shell.is_some()
+    }
+
+    /// Get the app ID, or the X11 class for Xwayland windows.
+    fn class(&self) -> String {
+        self.app_id
+            .clone()
+            .or_else(|| {
+                self.window_properties
+                    .as_ref()
+                    .and_then(|p| p.class.clone())
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayWindowProperties {
+    class: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayWorkspace {
+    id: i64,
+    name: String,
+    output: String,
+    focused: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayOutput {
+    name: String,
+    #[serde(default)]
+    focused: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayWindowEvent {
+    change: String,
+    container: SwayEventContainer,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayEventContainer {
+    id: i64,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    urgent: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// GET_TREE reply recorded from Sway, trimmed to the fields we read.
+    const TREE: &str = r#"{
+        "id": 1, "type": "root", "name": "root", "focused": false, "urgent": false,
+        "nodes": [
+            {
+                "id": 2147483646, "type": "output", "name": "__i3", "nodes": [
+                    {
+                        "id": 2147483645, "type": "workspace", "name": "__i3_scratch",
+                        "num": -1, "nodes": [], "floating_nodes": [
+                            {
+                                "id": 9, "type": "floating_con", "name": "notes.md - Neovim",
+                                "focused": false, "urgent": false, "shell": "xdg_shell",
+                                "app_id": "foot", "nodes": [], "floating_nodes": []
+                            }
+                        ]
+                    }
+                ]
+            },
+            {
+                "id": 3, "type": "output", "name": "DP-1", "focused": false, "nodes": [
+                    {
+                        "id": 4, "type": "workspace", "name": "1", "num": 1,
+                        "nodes": [
+                            {
+                                "id": 5, "type": "con", "name": null, "layout": "splith",
+                                "nodes": [
+                                    {
+                                        "id": 6, "type": "con", "name": "~",
+                                        "focused": true, "urgent": false,
+                                        "shell": "xdg_shell", "app_id": "foot",
+                                        "nodes": [], "floating_nodes": []
+                                    },
+                                    {
+                                        "id": 7, "type": "con", "name": "Mozilla Firefox",
+                                        "focused": false, "urgent": false,
+                                        "shell": "xdg_shell", "app_id": "firefox",
+                                        "nodes": [], "floating_nodes": []
+                                    }
+                                ],
+                                "floating_nodes": []
+                            }
+                        ],
+                        "floating_nodes": []
+                    },
+                    {
+                        "id": 8, "type": "workspace", "name": "games", "num": -1,
+                        "nodes": [], "floating_nodes": [
+                            {
+                                "id": 10, "type": "floating_con", "name": "",
+                                "focused": false, "urgent": true, "shell": "xwayland",
+                                "app_id": null, "window_properties": { "class": "Steam" },
+                                "nodes": [], "floating_nodes": []
+                            }
+                        ]
+                    },
+                    {
+                        "id": 11, "type": "workspace", "name": "chat", "num": -1,
+                        "nodes": [
+                            {
+                                "id": 12, "type": "con", "name": "Signal",
+                                "focused": false, "urgent": false,
+                                "shell": "xwayland", "app_id": null,
+                                "window_properties": { "class": "Signal" },
+                                "nodes": [], "floating_nodes": []
+                            }
+                        ],
+                        "floating_nodes": []
+                    }
+                ]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_tree_windows() {
+        let tree: SwayNode = serde_json::from_str(TREE).unwrap();
+        let windows = tree_windows(&tree);
+
+        let summary: Vec<_> = windows
+            .iter()
+            .map(|w| (w.workspace, w.address.as_str(), w.class.as_str()))
+            .collect();
+        // Named workspaces and the scratchpad have distinct IDs
+        assert_eq!(
+            summary,
+            [
+                (2147483645, "9", "foot"),
+                (4, "6", "foot"),
+                (4, "7", "firefox"),
+                (8, "10", "Steam"),
+                (11, "12", "Signal"),
+            ]
+        );
+
+        let focused = &windows[1];
+        assert!(focused.focused);
+        assert_eq!(focused.title, "~");
+
+        // Xwayland window without a title
+        let steam = &windows[3];
+        assert_eq!(steam.title, "Steam");
+        assert!(steam.urgent);
+        assert!(!steam.focused);
+
+        assert_eq!(find_scratchpad(&tree).unwrap().id, 2147483645);
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, GET_TREE, b"").unwrap();
+        write_message(&mut buffer, RUN_COMMAND, b"[con_id=6] focus").unwrap();
+        assert_eq!(&buffer[..6], MAGIC);
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(read_message(&mut reader).unwrap(), (GET_TREE, Vec::new()));
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            (RUN_COMMAND, b"[con_id=6] focus".to_vec())
+        );
+        assert!(read_message(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let event = |change: &str, urgent: bool| {
+            format!(r#"{{"change":"{change}","container":{{"id":7,"urgent":{urgent}}}}}"#)
+        };
+
+        assert!(matches!(
+            parse_event(WINDOW_EVENT, event("close", false).as_bytes()),
+            Some(CompositorEvent::WindowsChanged)
+        ));
+        assert!(matches!(
+            parse_event(WINDOW_EVENT, event("focus", false).as_bytes()),
+            Some(CompositorEvent::WindowFocused(address)) if address == "7"
+        ));
+        assert!(matches!(
+            parse_event(WINDOW_EVENT, event("urgent", true).as_bytes()),
+            Some(CompositorEvent::WindowUrgencyChanged { address, urgent: true })
+                if address == "7"
+        ));
+        assert!(matches!(
+            parse_event(
+                WINDOW_EVENT,
+                br#"{"change":"title","container":{"id":7,"name":"~/src"}}"#
+            ),
+            Some(CompositorEvent::WindowTitleChanged { address, title })
+                if address == "7" && title == "~/src"
+        ));
+        assert!(parse_event(WINDOW_EVENT, event("mark", false).as_bytes()).is_none());
+        assert!(parse_event(0x8000_0000, event("focus", false).as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("1"), r#""1""#);
+        assert_eq!(quote(r#"my "ws""#), r#""my \"ws\"""#);
+    }
+}
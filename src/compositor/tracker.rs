@@ -0,0 +1,245 @@
+//! Live window list kept current from the compositor's event stream.
+//!
+//! A background thread follows the event stream and refetches the window
+//! list when windows change, so showing the launcher needs no compositor
+//! round trip. It also records when each window was last focused, giving a
+//! most recently used order, and which windows demand attention.
+
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use super::base::get_display_title;
+use super::{Compositor, CompositorEvent, WindowInfo};
+
+/// Delay before reconnecting after the event stream ends.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Keeps an always-current window list.
+#[derive(Clone)]
+pub struct WindowTracker {
+    compositor: Arc<dyn Compositor>,
+    state: Arc<Mutex<TrackerState>>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    /// Open windows, most recently focused first
+    windows: Vec<WindowInfo>,
+    /// Whether the event stream is connected, so `windows` is current
+    live: bool,
+}
+
+impl WindowTracker {
+    /// Create a tracker, following the event stream if the compositor has one.
+    pub fn start(compositor: Arc<dyn Compositor>) -> Self {
+        let tracker = Self {
+            compositor,
+            state: Arc::new(Mutex::new(TrackerState::default())),
+        };
+
+        if tracker.compositor.capabilities().event_stream {
+            let tracker = tracker.clone();
+            thread::spawn(move || tracker.run());
+        }
+
+        tracker
+    }
+
+    /// Get the open windows, most recently focused first.
+    ///
+    /// Queries the compositor if the event stream is not connected.
+    pub fn windows(&self) -> anyhow::Result<Vec<WindowInfo>> {
+        {
+            let state = self.state.lock().unwrap();
+            if state.live {
+                return Ok(state.windows.clone());
+            }
+        }
+        self.compositor.list_windows()
+    }
+
+    /// Follow the event stream, reconnecting when it ends.
+    ///
+    /// The window list is fetched once the stream is connected, so no change
+    /// is missed between the two.
+    fn run(&self) {
+        loop {
+            let result = self
+                .compositor
+                .subscribe(&mut |event| self.handle_event(event));
+            self.state.lock().unwrap().live = false;
+
+            match result {
+                Ok(()) => warn!("Compositor event stream ended"),
+                Err(e) => warn!(%e, "Compositor event stream failed"),
+            }
+            thread::sleep(RECONNECT_DELAY);
+        }
+    }
+
+    /// Refetch the window list.
+    fn refresh(&self) {
+        match self.compositor.list_windows() {
+            Ok(windows) => {
+                let mut state = self.state.lock().unwrap();
+                state.merge(windows, Instant::now());
+                state.live = true;
+            }
+            Err(e) => {
+                warn!(%e, "Failed to list windows");
+                self.state.lock().unwrap().live = false;
+            }
+        }
+    }
+
+    fn handle_event(&self, event: CompositorEvent) {
+        debug!(?event, "Compositor event");
+        match event {
+            CompositorEvent::Connected | CompositorEvent::WindowsChanged => self.refresh(),
+            CompositorEvent::WindowTitleChanged { address, title } => {
+                self.state.lock().unwrap().set_title(&address, &title);
+            }
+            CompositorEvent::WindowFocused(address) => {
+                self.state.lock().unwrap().focus(&address, Instant::now());
+            }
+            CompositorEvent::WindowUrgencyChanged { address, urgent } => {
+                self.state.lock().unwrap().set_urgent(&address, urgent);
+            }
+        }
+    }
+}
+
+impl TrackerState {
+    /// Replace the window list, keeping focus times and urgency.
+    fn merge(&mut self, mut windows: Vec<WindowInfo>, now: Instant) {
+        for window in &mut windows {
+            if let Some(old) = self.windows.iter().find(|w| w.address == window.address) {
+                window.last_focused = old.last_focused;
+                window.urgent |= old.urgent;
+            }
+            if window.focused && window.last_focused.is_none() {
+                window.last_focused = Some(now);
+            }
+        }
+        self.windows = windows;
+        self.sort();
+    }
+
+    /// Mark a window as focused, clearing its urgency.
+    fn focus(&mut self, address: &str, now: Instant) {
+        for window in &mut self.windows {
+            window.focused = window.address == address;
+            if window.focused {
+                window.last_focused = Some(now);
+                window.urgent = false;
+            }
+        }
+        self.sort();
+    }
+
+    fn set_title(&mut self, address: &str, title: &str) {
+        if let Some(window) = self.windows.iter_mut().find(|w| w.address == address) {
+            window.title = get_display_title(title, &window.class);
+        }
+    }
+
+    fn set_urgent(&mut self, address: &str, urgent: bool) {
+        if let Some(window) = self.windows.iter_mut().find(|w| w.address == address) {
+            window.urgent = urgent;
+        }
+    }
+
+    /// Sort most recently focused first, keeping the compositor's order for
+    /// windows not focused since tracking started.
+    fn sort(&mut self) {
+        self.windows.sort_by_key(|w| Reverse(w.last_focused));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(address: &str, focused: bool) -> WindowInfo {
+        WindowInfo {
+            address: address.to_string(),
+            title: address.to_string(),
+            class: address.to_string(),
+            workspace: 1,
+            focused,
+            urgent: false,
+            last_focused: None,
+        }
+    }
+
+    fn addresses(state: &TrackerState) -> Vec<&str> {
+        state.windows.iter().map(|w| w.address.as_str()).collect()
+    }
+
+    #[test]
+    fn test_focus_orders_most_recent_first() {
+        let start = Instant::now();
+        let mut state = TrackerState::default();
+        state.merge(
+            vec![window("a", true), window("b", false), window("c", false)],
+            start,
+        );
+        assert_eq!(addresses(&state), ["a", "b", "c"]);
+
+        state.focus("c", start + Duration::from_secs(1));
+        state.focus("b", start + Duration::from_secs(2));
+        assert_eq!(addresses(&state), ["b", "c", "a"]);
+        assert!(state.windows[0].focused);
+        assert!(!state.windows[1].focused);
+    }
+
+    #[test]
+    fn test_merge_keeps_focus_times_and_urgency() {
+        let start = Instant::now();
+        let mut state = TrackerState::default();
+        state.merge(vec![window("a", true), window("b", false)], start);
+        state.focus("b", start + Duration::from_secs(1));
+        state.set_urgent("a", true);
+
+        // "b" closed, "c" opened
+        state.merge(
+            vec![window("a", false), window("c", false)],
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(addresses(&state), ["a", "c"]);
+        assert!(state.windows[0].urgent);
+        assert_eq!(state.windows[0].last_focused, Some(start));
+        assert_eq!(state.windows[1].last_focused, None);
+    }
+
+    #[test]
+    fn test_focus_clears_urgency() {
+        let start = Instant::now();
+        let mut state = TrackerState::default();
+        state.merge(vec![window("a", true), window("b", false)], start);
+        state.set_urgent("b", true);
+        assert!(state.windows[1].urgent);
+
+        state.focus("b", start + Duration::from_secs(1));
+        assert_eq!(addresses(&state), ["b", "a"]);
+        assert!(!state.windows[0].urgent);
+    }
+
+    #[test]
+    fn test_set_title() {
+        let mut state = TrackerState::default();
+        state.merge(vec![window("a", true), window("b", false)], Instant::now());
+
+        state.set_title("b", "Renamed");
+        assert_eq!(state.windows[1].title, "Renamed");
+        // Untitled windows show their class
+        state.set_title("a", "");
+        assert_eq!(state.windows[0].title, "a");
+        state.set_title("gone", "Ignored");
+        assert_eq!(addresses(&state), ["a", "b"]);
+    }
+}
@@ -0,0 +1,233 @@
+//! Targeted edits of the config file.
+//!
+//! Settings changed from the launcher are written by editing the affected
+//! lines only, so the rest of the file keeps its formatting and comments.
+
+use anyhow::{Result, bail};
+use toml::{Table, Value};
+
+/// Set `key = value` in the table at `path` of a TOML document.
+///
+/// An existing key is replaced in place and a missing key is added below the
+/// table header. A missing table is appended to the document. The result is
+/// checked against the parsed document, so an edit that would change anything
+/// else is refused.
+pub fn set_value(content: &str, path: &[&str], key: &str, value: Value) -> Result<String> {
+    let mut expected: Table = toml::from_str(content)?;
+    set_in_table(&mut expected, path, key, value.clone())?;
+
+    let assignment = format!("{} = {}", format_key(key), value);
+    let mut lines: Vec<&str> = content.lines().collect();
+    let edited = match find_table(&lines, path) {
+        Some(header) => {
+            let end = lines[header + 1..]
+                .iter()
+                .position(|line| line.trim_start().starts_with('['))
+                .map_or(lines.len(), |offset| header + 1 + offset);
+            let existing = (header + 1..end).find(|&i| line_key(lines[i]).as_deref() == Some(key));
+            match existing {
+                Some(i) => lines[i] = &assignment,
+                None => lines.insert(header + 1, &assignment),
+            }
+            lines.join("\n") + "\n"
+        }
+        None => {
+            let header = path.iter().map(|part| format_key(part)).collect::<Vec<_>>();
+            let mut edited = content.trim_end().to_string();
+            if !edited.is_empty() {
+                edited.push_str("\n\n");
+            }
+            edited.push_str(&format!("[{}]\n{}\n", header.join("."), assignment));
+            edited
+        }
+    };
+
+    match toml::from_str::<Table>(&edited) {
+        Ok(table) if table == expected => Ok(edited),
+        _ => bail!("The config file layout is not supported for editing"),
+    }
+}
+
+fn set_in_table(table: &mut Table, path: &[&str], key: &str, value: Value) -> Result<()> {
+    let mut table = table;
+    for part in path {
+        let entry = table
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        let Value::Table(inner) = entry else {
+            bail!("'{}' is not a table", part);
+        };
+        table = inner;
+    }
+    table.insert(key.to_string(), value);
+    Ok(())
+}
+
+/// Index of the header line of the table at `path`.
+fn find_table(lines: &[&str], path: &[&str]) -> Option<usize> {
+    lines.iter().position(|line| {
+        let line = line.trim();
+        if line.starts_with("[[") {
+            return false;
+        }
+        line.strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .and_then(|(header, _)| parse_dotted_key(header))
+            .is_some_and(|parts| parts.iter().map(String::as_str).eq(path.iter().copied()))
+    })
+}
+
+/// Key assigned on a `key = value` line.
+fn line_key(line: &str) -> Option<String> {
+    let (key, _) = line.split_once('=')?;
+    match parse_dotted_key(key)?.as_slice() {
+        [key] => Some(key.clone()),
+        _ => None,
+    }
+}
+
+/// Parse a dotted key of bare, basic-quoted and literal-quoted parts.
+fn parse_dotted_key(text: &str) -> Option<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut chars = text.trim().chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let part = match chars.peek()? {
+            '"' => {
+                chars.next();
+                let mut part = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => part.push(chars.next()?),
+                        c => part.push(c),
+                    }
+                }
+                part
+            }
+            '\'' => {
+                chars.next();
+                let mut part = String::new();
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => part.push(c),
+                    }
+                }
+                part
+            }
+            _ => {
+                let mut part = String::new();
+                while let Some(c) = chars.next_if(|c| is_bare_key_char(*c)) {
+                    part.push(c);
+                }
+                if part.is_empty() {
+                    return None;
+                }
+                part
+            }
+        };
+        parts.push(part);
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => return Some(parts),
+            Some('.') => {}
+            Some(_) => return None,
+        }
+    }
+}
+
+fn is_bare_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Format a key, quoting it unless it is a bare key.
+fn format_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(is_bare_key_char) {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hide(content: &str, id: &str) -> Result<String> {
+        set_value(
+            content,
+            &["applications", id],
+            "hidden",
+            Value::Boolean(true),
+        )
+    }
+
+    #[test]
+    fn test_set_value_in_empty_file() {
+        assert_eq!(
+            hide("", "org.mozilla.firefox").unwrap(),
+            "[applications.\"org.mozilla.firefox\"]\nhidden = true\n"
+        );
+    }
+
+    #[test]
+    fn test_set_value_appends_table() {
+        let content = "# My launcher\ntheme = \"nord\" # favourite\n";
+        assert_eq!(
+            hide(content, "avahi-discover").unwrap(),
+            "# My launcher\ntheme = \"nord\" # favourite\n\n[applications.avahi-discover]\nhidden = true\n"
+        );
+    }
+
+    #[test]
+    fn test_set_value_in_existing_table() {
+        let content = "\
+[applications.\"org.mozilla.firefox\"]
+# Wayland please
+env = { MOZ_ENABLE_WAYLAND = \"1\" }
+hidden = false
+
+[fuzzy_match]
+enabled = true
+";
+        assert_eq!(
+            hide(content, "org.mozilla.firefox").unwrap(),
+            "\
+[applications.\"org.mozilla.firefox\"]
+# Wayland please
+env = { MOZ_ENABLE_WAYLAND = \"1\" }
+hidden = true
+
+[fuzzy_match]
+enabled = true
+"
+        );
+
+        let content = "[applications.'htop']\nname = \"Top\"\n";
+        assert_eq!(
+            hide(content, "htop").unwrap(),
+            "[applications.'htop']\nhidden = true\nname = \"Top\"\n"
+        );
+    }
+
+    #[test]
+    fn test_set_value_refuses_unsupported_layout() {
+        let content = "applications = { htop = { name = \"Top\" } }\n";
+        assert!(hide(content, "htop").is_err());
+        assert!(hide("not toml [", "htop").is_err());
+    }
+
+    #[test]
+    fn test_parse_dotted_key() {
+        assert_eq!(
+            parse_dotted_key(" applications . \"a.b\\\"c\" ").unwrap(),
+            ["applications", "a.b\"c"]
+        );
+        assert_eq!(parse_dotted_key("a.'b c'").unwrap(), ["a", "b c"]);
+        assert_eq!(parse_dotted_key("a b"), None);
+        assert_eq!(parse_dotted_key("a."), None);
+    }
+}
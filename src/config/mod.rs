@@ -6,23 +6,30 @@
 //!
 //! # Modules
 //!
+//! - `edit` - Targeted edits of the config file
 //! - `service` - Configuration loading, caching, and persistence
 //! - `theme_loader` - Theme discovery and loading
 //! - `types` - Configuration type definitions
 //! - `validation` - Configuration validation utilities
 
+mod edit;
 mod service;
 mod theme_loader;
 mod types;
 pub mod validation;
 
 // Re-export types
-pub use types::{AppConfig, ConfigModule, ConfigSearchProvider, FuzzyMatchConfig, LauncherMode};
+pub use types::{
+    AppConfig, ApplicationOverride, ClipboardConfig, CompositorBackend, ConfigModule,
+    ConfigSearchProvider, FuzzyMatchConfig, HyprlandConfig, IpcSocketConfig, LauncherAnchor,
+    LauncherMode, OutputConfig, PlacementConfig, TerminalConfig, WindowGrouping, WindowsConfig,
+};
 
 // Re-export service functions
 pub use service::{
     ConfigProvider, ConfigService, config, config_file_exists, get_combined_modules,
-    get_default_modes, init_config, launcher_size, load_configured_theme, update_config,
+    get_default_modes, hide_application, init_config, launcher_size, load_configured_theme,
+    set_active_output, update_config,
 };
 
 // Re-export theme functions
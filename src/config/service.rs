@@ -10,6 +10,9 @@ use super::types::{AppConfig, ConfigModule, LauncherMode};
 /// Global config instance (mutable via RwLock).
 static CONFIG: RwLock<AppConfig> = RwLock::new(AppConfig::default_const());
 
+/// Output the launcher is shown on, selecting per-output sizes.
+static ACTIVE_OUTPUT: RwLock<Option<String>> = RwLock::new(None);
+
 /// One-time warning for deprecated disabled_modules option.
 static DISABLED_MODULES_WARNING: Once = Once::new();
 
@@ -144,6 +147,40 @@ pub fn update_config(f: impl FnOnce(&mut AppConfig)) {
     }
 }
 
+/// Hide an application and persist it to the config file.
+///
+/// Only the application's section is edited, keeping the rest of the file
+/// as written. A missing config file is created. On error the application
+/// stays hidden until the daemon restarts.
+pub fn hide_application(id: &str) -> anyhow::Result<()> {
+    CONFIG
+        .write()
+        .unwrap()
+        .applications
+        .entry(id.to_string())
+        .or_default()
+        .hidden = true;
+
+    let dir = config_dir().ok_or_else(|| anyhow::anyhow!("No config dir"))?;
+    let config_path = dir.join("config.toml");
+    let content = match std::fs::read_to_string(&config_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let edited = super::edit::set_value(
+        &content,
+        &["applications", id],
+        "hidden",
+        toml::Value::Boolean(true),
+    )?;
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&config_path, edited)?;
+    tracing::debug!("Saved hidden application to {:?}", config_path);
+    Ok(())
+}
+
 /// Save config to file.
 fn save_config_to_file(config: &AppConfig) -> anyhow::Result<()> {
     let config_path = config_dir()
@@ -155,9 +192,15 @@ fn save_config_to_file(config: &AppConfig) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Get the configured launcher panel size (width, height).
+/// Set the output the launcher is shown on, or `None` if unknown.
+pub fn set_active_output(output: Option<String>) {
+    *ACTIVE_OUTPUT.write().unwrap() = output;
+}
+
+/// Get the configured launcher panel size (width, height) on the active output.
 pub fn launcher_size() -> (f32, f32) {
-    config().get_launcher_size()
+    let output = ACTIVE_OUTPUT.read().unwrap();
+    config().get_launcher_size_on(output.as_deref())
 }
 
 /// Load the configured theme, falling back to default if anything fails.
@@ -1,7 +1,8 @@
 //! Configuration type definitions.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
 
 /// Configuration for fuzzy matching algorithm.
 ///
@@ -38,6 +39,18 @@ pub struct FuzzyMatchConfig {
     /// it will be promoted to the top of the list.
     /// Default: true
     pub show_best_match: bool,
+    /// Maximum bonus added for frequently and recently launched items.
+    /// The bonus scales with the item's frecency (0.0-1.0). Set to 0 to disable.
+    /// Default: 20000
+    pub frecency_weight: i64,
+    /// Number of recently launched items shown in the "Recent" section
+    /// when the query is empty. Set to 0 to hide the section.
+    /// Default: 5
+    pub recent_items: usize,
+    /// Bonus added to the item previously chosen for the typed query (or a
+    /// shorter prefix of it). Set to 0 to disable selection learning.
+    /// Default: 150000
+    pub selection_bonus: i64,
 }
 
 impl FuzzyMatchConfig {
@@ -52,6 +65,9 @@ impl FuzzyMatchConfig {
             action_score_multiplier: 0.8,
             submenu_score_multiplier: 0.9,
             show_best_match: true,
+            frecency_weight: 20_000,
+            recent_items: 5,
+            selection_bonus: 150_000,
         }
     }
 }
@@ -62,6 +78,267 @@ impl Default for FuzzyMatchConfig {
     }
 }
 
+/// Terminal emulator used for applications with `Terminal=true`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    /// Terminal program (e.g. "foot"). When unset, `$TERMINAL`,
+    /// `xdg-terminal-exec` and common terminals are tried in turn.
+    pub command: Option<String>,
+    /// Arguments passed to the terminal. `{cmd}` is replaced by the command
+    /// and its arguments; without it they are appended.
+    /// Default: the known convention for the terminal, otherwise `["-e"]`
+    pub args: Option<Vec<String>>,
+}
+
+impl TerminalConfig {
+    /// Const default for static initialization.
+    pub const fn default_const() -> Self {
+        Self {
+            command: None,
+            args: None,
+        }
+    }
+}
+
+/// How windows are grouped in the Windows section.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowGrouping {
+    /// One flat list.
+    #[default]
+    None,
+    /// Sub-headers per workspace, current workspace first.
+    Workspace,
+    /// Windows of the same application collapsed into one row, expanded with Tab.
+    App,
+}
+
+/// Windows section settings, configured as `[windows]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowsConfig {
+    /// How windows are grouped.
+    /// Default: none
+    pub group_by: WindowGrouping,
+}
+
+impl WindowsConfig {
+    /// Const default for static initialization.
+    pub const fn default_const() -> Self {
+        Self {
+            group_by: WindowGrouping::None,
+        }
+    }
+}
+
+/// Clipboard history settings, configured as `[clipboard]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// Keep the history on disk across daemon restarts.
+    /// Default: false
+    pub persist: bool,
+    /// Maximum number of items kept.
+    /// Default: 500
+    pub max_entries: usize,
+    /// Maximum total size of the history in bytes.
+    /// Default: 128 MiB
+    pub max_total_bytes: usize,
+    /// Items larger than this many bytes are not recorded.
+    /// Default: 32 MiB
+    pub max_item_bytes: usize,
+    /// Items older than this many days are removed.
+    /// Default: none
+    pub max_age_days: Option<u32>,
+    /// Ignore text in well-known secret formats (API keys, private keys, card numbers).
+    /// Default: true
+    pub ignore_secrets: bool,
+    /// Regular expressions for text that is not recorded.
+    pub ignore_patterns: Vec<String>,
+    /// Regular expressions for text that is removed after `sensitive_expiry_secs`.
+    pub sensitive_patterns: Vec<String>,
+    /// Seconds before items matching `sensitive_patterns` are removed.
+    /// Default: 60
+    pub sensitive_expiry_secs: u64,
+    /// Application IDs whose copies are not recorded.
+    pub ignore_apps: Vec<String>,
+}
+
+impl ClipboardConfig {
+    /// Const default for static initialization.
+    pub const fn default_const() -> Self {
+        Self {
+            persist: false,
+            max_entries: 500,
+            max_total_bytes: 128 * 1024 * 1024,
+            max_item_bytes: 32 * 1024 * 1024,
+            max_age_days: None,
+            ignore_secrets: true,
+            ignore_patterns: Vec::new(),
+            sensitive_patterns: Vec::new(),
+            sensitive_expiry_secs: 60,
+            ignore_apps: Vec::new(),
+        }
+    }
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self::default_const()
+    }
+}
+
+/// Compositor backend used for window management.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompositorBackend {
+    /// Detect the compositor from the environment.
+    #[default]
+    Auto,
+    Hyprland,
+    Kwin,
+    Niri,
+    Sway,
+    /// Foreign-toplevel protocols of wlroots-based compositors.
+    Wlr,
+    /// No compositor integration (applications only).
+    None,
+}
+
+/// Hyprland backend settings, configured as `[hyprland]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HyprlandConfig {
+    /// Instance signature locating the IPC sockets.
+    /// Default: `$HYPRLAND_INSTANCE_SIGNATURE`
+    pub instance_signature: Option<String>,
+}
+
+impl HyprlandConfig {
+    /// Const default for static initialization.
+    pub const fn default_const() -> Self {
+        Self {
+            instance_signature: None,
+        }
+    }
+}
+
+/// Settings for a backend using an IPC socket, configured as `[niri]` or `[sway]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpcSocketConfig {
+    /// Path of the IPC socket.
+    /// Default: `$NIRI_SOCKET` or `$SWAYSOCK`
+    pub socket: Option<PathBuf>,
+}
+
+impl IpcSocketConfig {
+    /// Const default for static initialization.
+    pub const fn default_const() -> Self {
+        Self { socket: None }
+    }
+}
+
+/// Point of the output the launcher panel is placed at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LauncherAnchor {
+    /// Centre of the output.
+    #[default]
+    Center,
+    /// Top edge, centred horizontally (like Spotlight).
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Pointer position, falling back to the centre when the compositor
+    /// cannot report it.
+    Cursor,
+}
+
+impl LauncherAnchor {
+    /// Check if this anchor is a single edge, along which the panel can be stretched.
+    pub fn is_edge(&self) -> bool {
+        matches!(self, Self::Top | Self::Bottom | Self::Left | Self::Right)
+    }
+}
+
+/// Placement of the launcher panel, configured as `[placement]` or
+/// `[mode_placement.<mode>]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlacementConfig {
+    /// Point of the output the panel is placed at.
+    /// Default: center
+    pub anchor: LauncherAnchor,
+    /// Distance (x, y) in pixels from the anchored edges, or from the pointer.
+    /// Default: (0.0, 0.0)
+    pub offset: (f32, f32),
+    /// Stretch the panel along the anchored edge: a full-height sidebar for
+    /// `left`/`right`, a full-width bar for `top`/`bottom`.
+    pub fill: bool,
+}
+
+impl PlacementConfig {
+    /// Const default for static initialization.
+    pub const fn default_const() -> Self {
+        Self {
+            anchor: LauncherAnchor::Center,
+            offset: (0.0, 0.0),
+            fill: false,
+        }
+    }
+}
+
+impl Default for PlacementConfig {
+    fn default() -> Self {
+        Self::default_const()
+    }
+}
+
+/// Launcher sizes for a single output, configured as `[outputs.<name>]`.
+///
+/// The output name is the one reported by the compositor (e.g. `DP-1`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Size of the launcher panel on this output, overriding `launcher_size`.
+    pub launcher_size: Option<(f32, f32)>,
+    /// Window buffer size on this output, overriding `window_size`.
+    pub window_size: Option<(f32, f32)>,
+}
+
+/// Overrides for a single application, configured as `[applications.<desktop-id>]`.
+///
+/// The desktop ID may be given with or without the `.desktop` suffix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApplicationOverride {
+    /// Hide the application from the launcher.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    /// Display name replacing the entry's `Name=`.
+    pub name: Option<String>,
+    /// Extra search terms matched like the name (e.g. "ff" for Firefox).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Icon name or absolute path replacing the entry's `Icon=`.
+    pub icon: Option<String>,
+    /// Extra environment variables for the launched process.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Extra arguments appended to the command line.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Focus an open window instead of launching, overriding the global setting.
+    pub focus_or_launch: Option<bool>,
+}
+
 /// Application configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -94,6 +371,33 @@ pub struct AppConfig {
     pub combined_modules: Option<Vec<ConfigModule>>,
     /// Fuzzy matching configuration for search scoring.
     pub fuzzy_match: FuzzyMatchConfig,
+    /// Terminal emulator configuration.
+    pub terminal: TerminalConfig,
+    /// Per-application overrides, keyed by desktop ID.
+    pub applications: BTreeMap<String, ApplicationOverride>,
+    /// Environment variables set for all launched applications.
+    pub env: BTreeMap<String, String>,
+    /// Focus an open window of an application instead of launching a new instance.
+    pub focus_or_launch: bool,
+    /// Compositor backend for window management.
+    /// Default: auto
+    pub compositor: CompositorBackend,
+    /// Hyprland backend settings.
+    pub hyprland: HyprlandConfig,
+    /// Niri backend settings.
+    pub niri: IpcSocketConfig,
+    /// Sway backend settings.
+    pub sway: IpcSocketConfig,
+    /// Per-output launcher sizes, keyed by output name.
+    pub outputs: BTreeMap<String, OutputConfig>,
+    /// Placement of the launcher panel on the output.
+    pub placement: PlacementConfig,
+    /// Per-mode placements, overriding `placement`.
+    pub mode_placement: BTreeMap<LauncherMode, PlacementConfig>,
+    /// Windows section settings.
+    pub windows: WindowsConfig,
+    /// Clipboard history settings.
+    pub clipboard: ClipboardConfig,
 }
 
 impl AppConfig {
@@ -111,6 +415,19 @@ impl AppConfig {
             default_modes: None,
             combined_modules: None,
             fuzzy_match: FuzzyMatchConfig::default_const(),
+            terminal: TerminalConfig::default_const(),
+            applications: BTreeMap::new(),
+            env: BTreeMap::new(),
+            focus_or_launch: false,
+            compositor: CompositorBackend::Auto,
+            hyprland: HyprlandConfig::default_const(),
+            niri: IpcSocketConfig::default_const(),
+            sway: IpcSocketConfig::default_const(),
+            outputs: BTreeMap::new(),
+            placement: PlacementConfig::default_const(),
+            mode_placement: BTreeMap::new(),
+            windows: WindowsConfig::default_const(),
+            clipboard: ClipboardConfig::default_const(),
         }
     }
 
@@ -118,6 +435,29 @@ impl AppConfig {
     pub fn get_launcher_size(&self) -> (f32, f32) {
         self.launcher_size.unwrap_or((600.0, 400.0))
     }
+
+    /// Get the launcher panel size on an output, falling back to `launcher_size`.
+    pub fn get_launcher_size_on(&self, output: Option<&str>) -> (f32, f32) {
+        self.output_config(output)
+            .and_then(|o| o.launcher_size)
+            .unwrap_or_else(|| self.get_launcher_size())
+    }
+
+    /// Get the window buffer size on an output, falling back to `window_size`.
+    pub fn get_window_size_on(&self, output: Option<&str>) -> Option<(f32, f32)> {
+        self.output_config(output)
+            .and_then(|o| o.window_size)
+            .or(self.window_size)
+    }
+
+    fn output_config(&self, output: Option<&str>) -> Option<&OutputConfig> {
+        self.outputs.get(output?)
+    }
+
+    /// Get the placement of the launcher panel when opened in a mode.
+    pub fn placement_for(&self, mode: &LauncherMode) -> &PlacementConfig {
+        self.mode_placement.get(mode).unwrap_or(&self.placement)
+    }
 }
 
 impl Default for AppConfig {
@@ -159,6 +499,19 @@ impl Default for AppConfig {
             default_modes: None,
             combined_modules: None,
             fuzzy_match: FuzzyMatchConfig::default(),
+            terminal: TerminalConfig::default(),
+            applications: BTreeMap::new(),
+            env: BTreeMap::new(),
+            focus_or_launch: false,
+            compositor: CompositorBackend::Auto,
+            hyprland: HyprlandConfig::default(),
+            niri: IpcSocketConfig::default(),
+            sway: IpcSocketConfig::default(),
+            outputs: BTreeMap::new(),
+            placement: PlacementConfig::default_const(),
+            mode_placement: BTreeMap::new(),
+            windows: WindowsConfig::default_const(),
+            clipboard: ClipboardConfig::default_const(),
         }
     }
 }
@@ -196,7 +549,9 @@ impl ConfigModule {
 }
 
 /// Launcher modes - determines what view is shown.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, clap::ValueEnum,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum LauncherMode {
     /// Combined mode: shows all enabled modules together.
@@ -216,6 +571,10 @@ pub enum LauncherMode {
     Themes,
     #[value(alias = "window")]
     Windows,
+    /// Alt-Tab style window switcher in most recently focused order.
+    #[serde(rename = "windows-mru")]
+    #[value(alias = "mru")]
+    WindowsMru,
 }
 
 impl LauncherMode {
@@ -232,6 +591,7 @@ impl LauncherMode {
             "search" => Some(Self::Search),
             "themes" | "theme" => Some(Self::Themes),
             "windows" | "window" => Some(Self::Windows),
+            "windows-mru" | "mru" => Some(Self::WindowsMru),
             _ => None,
         }
     }
@@ -249,6 +609,7 @@ impl LauncherMode {
             Self::Search => "Search",
             Self::Themes => "Themes",
             Self::Windows => "Windows",
+            Self::WindowsMru => "Window Switcher",
         }
     }
 
@@ -279,7 +640,7 @@ impl LauncherMode {
             Self::Actions => Some(ConfigModule::Actions),
             Self::Search => Some(ConfigModule::Search),
             Self::Themes => Some(ConfigModule::Themes),
-            Self::Windows => Some(ConfigModule::Windows),
+            Self::Windows | Self::WindowsMru => Some(ConfigModule::Windows),
         }
     }
 }
@@ -358,6 +719,14 @@ mod tests {
             LauncherMode::parse_str("calc"),
             Some(LauncherMode::Calculator)
         );
+        assert_eq!(
+            LauncherMode::parse_str("windows-mru"),
+            Some(LauncherMode::WindowsMru)
+        );
+        assert_eq!(
+            LauncherMode::parse_str("mru"),
+            Some(LauncherMode::WindowsMru)
+        );
     }
 
     #[test]
@@ -404,6 +773,10 @@ mod tests {
         assert!(config.hyprland_auto_blur);
         assert!(config.enable_transparency);
         assert!(config.search_providers.is_some());
+        assert!(config.terminal.command.is_none());
+        assert!(config.terminal.args.is_none());
+        assert!(config.applications.is_empty());
+        assert!(config.env.is_empty());
     }
 
     #[test]
@@ -441,6 +814,211 @@ mod tests {
         assert_eq!(config.get_launcher_size(), (700.0, 500.0));
     }
 
+    #[test]
+    fn test_output_sizes() {
+        let toml_str = r#"
+            launcher_size = [700.0, 500.0]
+            window_size = [1920.0, 1080.0]
+
+            [outputs.HDMI-A-1]
+            launcher_size = [1000.0, 700.0]
+
+            [outputs.DP-1]
+            window_size = [3840.0, 2160.0]
+        "#;
+
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        assert_eq!(
+            config.get_launcher_size_on(Some("HDMI-A-1")),
+            (1000.0, 700.0)
+        );
+        assert_eq!(config.get_launcher_size_on(Some("DP-1")), (700.0, 500.0));
+        assert_eq!(config.get_launcher_size_on(None), (700.0, 500.0));
+        assert_eq!(
+            config.get_window_size_on(Some("HDMI-A-1")),
+            Some((1920.0, 1080.0))
+        );
+        assert_eq!(
+            config.get_window_size_on(Some("DP-1")),
+            Some((3840.0, 2160.0))
+        );
+        assert_eq!(
+            config.get_window_size_on(Some("eDP-1")),
+            Some((1920.0, 1080.0))
+        );
+    }
+
+    #[test]
+    fn test_placement_deserialization() {
+        let toml_str = r#"
+            [placement]
+            anchor = "top"
+            offset = [0.0, 120.0]
+
+            [mode_placement.emojis]
+            anchor = "cursor"
+
+            [mode_placement.windows-mru]
+            anchor = "left"
+            fill = true
+        "#;
+
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        let placement = config.placement_for(&LauncherMode::Combined);
+        assert_eq!(placement.anchor, LauncherAnchor::Top);
+        assert_eq!(placement.offset, (0.0, 120.0));
+        assert!(!placement.fill);
+        let placement = config.placement_for(&LauncherMode::Emojis);
+        assert_eq!(placement.anchor, LauncherAnchor::Cursor);
+        assert_eq!(placement.offset, (0.0, 0.0));
+        let placement = config.placement_for(&LauncherMode::WindowsMru);
+        assert_eq!(placement.anchor, LauncherAnchor::Left);
+        assert!(placement.fill);
+    }
+
+    #[test]
+    fn test_placement_default() {
+        let config = AppConfig::default();
+        assert_eq!(
+            config.placement_for(&LauncherMode::Emojis),
+            &PlacementConfig::default()
+        );
+        assert_eq!(config.placement.anchor, LauncherAnchor::Center);
+    }
+
+    #[test]
+    fn test_windows_config_deserialization() {
+        let config: AppConfig = toml::from_str("").expect("Failed to deserialize");
+        assert_eq!(config.windows.group_by, WindowGrouping::None);
+
+        let toml_str = r#"
+            [windows]
+            group_by = "workspace"
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        assert_eq!(config.windows.group_by, WindowGrouping::Workspace);
+    }
+
+    #[test]
+    fn test_clipboard_config_deserialization() {
+        let config: AppConfig = toml::from_str("").expect("Failed to deserialize");
+        assert!(!config.clipboard.persist);
+        assert_eq!(config.clipboard.max_entries, 500);
+        assert_eq!(config.clipboard.max_age_days, None);
+
+        let toml_str = r#"
+            [clipboard]
+            persist = true
+            max_entries = 50
+            max_age_days = 7
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        assert!(config.clipboard.persist);
+        assert_eq!(config.clipboard.max_entries, 50);
+        assert_eq!(config.clipboard.max_total_bytes, 128 * 1024 * 1024);
+        assert_eq!(config.clipboard.max_age_days, Some(7));
+
+        let toml_str = r#"
+            [clipboard]
+            ignore_patterns = ["^hunter2$"]
+            ignore_apps = ["org.keepassxc.KeePassXC"]
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        assert!(config.clipboard.ignore_secrets);
+        assert_eq!(config.clipboard.ignore_patterns, ["^hunter2$"]);
+        assert_eq!(config.clipboard.ignore_apps, ["org.keepassxc.KeePassXC"]);
+        assert_eq!(config.clipboard.sensitive_expiry_secs, 60);
+    }
+
+    #[test]
+    fn test_terminal_config_deserialization() {
+        let toml_str = r#"
+            [terminal]
+            command = "wezterm"
+            args = ["start", "--cwd", ".", "--", "{cmd}"]
+        "#;
+
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        assert_eq!(config.terminal.command.as_deref(), Some("wezterm"));
+        assert_eq!(
+            config.terminal.args,
+            Some(vec![
+                "start".to_string(),
+                "--cwd".to_string(),
+                ".".to_string(),
+                "--".to_string(),
+                "{cmd}".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_compositor_config_deserialization() {
+        let config = AppConfig::default();
+        assert_eq!(config.compositor, CompositorBackend::Auto);
+        assert!(config.niri.socket.is_none());
+
+        let toml_str = r#"
+            compositor = "niri"
+
+            [niri]
+            socket = "/run/user/1000/niri.sock"
+
+            [hyprland]
+            instance_signature = "abc_123"
+        "#;
+
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        assert_eq!(config.compositor, CompositorBackend::Niri);
+        assert_eq!(
+            config.niri.socket,
+            Some(PathBuf::from("/run/user/1000/niri.sock"))
+        );
+        assert_eq!(
+            config.hyprland.instance_signature.as_deref(),
+            Some("abc_123")
+        );
+        assert!(config.sway.socket.is_none());
+
+        let config: AppConfig = toml::from_str(r#"compositor = "none""#).unwrap();
+        assert_eq!(config.compositor, CompositorBackend::None);
+    }
+
+    #[test]
+    fn test_application_overrides_deserialization() {
+        let toml_str = r#"
+            [applications."org.mozilla.firefox"]
+            aliases = ["ff"]
+            env = { MOZ_ENABLE_WAYLAND = "1" }
+
+            [applications.avahi-discover]
+            hidden = true
+        "#;
+
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        let firefox = &config.applications["org.mozilla.firefox"];
+        assert_eq!(firefox.aliases, vec!["ff".to_string()]);
+        assert_eq!(firefox.env["MOZ_ENABLE_WAYLAND"], "1");
+        assert!(!firefox.hidden);
+        assert!(config.applications["avahi-discover"].hidden);
+    }
+
+    #[test]
+    fn test_application_override_serialization_skips_empty() {
+        let mut config = AppConfig::default();
+        config.applications.insert(
+            "avahi-discover".to_string(),
+            ApplicationOverride {
+                hidden: true,
+                ..Default::default()
+            },
+        );
+
+        let toml_str = toml::to_string(&config).expect("Failed to serialize");
+        assert!(toml_str.contains("[applications.avahi-discover]\nhidden = true\n"));
+        assert!(!toml_str.contains("aliases"));
+    }
+
     #[test]
     fn test_config_module_serde() {
         let toml_str = r#"
@@ -551,6 +1129,9 @@ mod tests {
         assert!((config.description_penalty - 0.3).abs() < f64::EPSILON);
         assert!((config.action_score_multiplier - 0.8).abs() < f64::EPSILON);
         assert!((config.submenu_score_multiplier - 0.9).abs() < f64::EPSILON);
+        assert_eq!(config.frecency_weight, 20_000);
+        assert_eq!(config.recent_items, 5);
+        assert_eq!(config.selection_bonus, 150_000);
     }
 
     #[test]
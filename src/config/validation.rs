@@ -4,7 +4,9 @@
 //! non-fatal issues that should be logged but don't prevent startup.
 
 use super::theme_loader::list_themes;
-use super::types::{AppConfig, ConfigSearchProvider};
+use super::types::{AppConfig, ConfigSearchProvider, PlacementConfig, TerminalConfig};
+use crate::desktop::env::is_valid_variable_name;
+use crate::process::{COMMAND_PLACEHOLDER, find_program};
 
 /// Non-fatal validation warning.
 #[derive(Debug)]
@@ -18,18 +20,137 @@ pub struct ValidationWarning {
 /// Validate the entire config, returning warnings for non-fatal issues.
 ///
 /// This function checks for:
-/// - Launcher dimensions outside recommended ranges
+/// - Launcher dimensions outside recommended ranges, globally and per output
+/// - Stretched panels that are not anchored to an edge
 /// - Search provider URLs missing the `{query}` placeholder
 /// - Invalid trigger formats for search providers
+/// - A configured terminal that is not installed
+/// - Invalid environment variable names
+/// - Invalid clipboard patterns
 pub fn validate_config(config: &AppConfig) -> Vec<ValidationWarning> {
     let mut warnings = vec![];
 
     // Validate launcher_size dimensions if set
-    let (launcher_w, launcher_h) = config.get_launcher_size();
+    let launcher_size = config.get_launcher_size();
+    warnings.extend(validate_launcher_size("launcher_size", launcher_size));
+
+    // Validate search providers
+    if let Some(providers) = &config.search_providers {
+        for provider in providers {
+            warnings.extend(validate_search_provider(provider));
+        }
+    }
+
+    // Validate theme exists (only if non-default)
+    if !config.theme.is_empty() && config.theme != "default" && !validate_theme_name(&config.theme)
+    {
+        warnings.push(ValidationWarning {
+            field: "theme".to_string(),
+            message: format!(
+                "Theme '{}' not found. Will fall back to default theme.",
+                config.theme
+            ),
+        });
+    }
+
+    // Validate window_size if set (only relevant when enable_backdrop is true)
+    if config.enable_backdrop
+        && let Some(window_size) = config.window_size
+    {
+        warnings.extend(validate_window_size(
+            "window_size",
+            window_size,
+            launcher_size,
+        ));
+    }
+
+    // Validate per-output sizes
+    for (name, output) in &config.outputs {
+        if let Some(size) = output.launcher_size {
+            warnings.extend(validate_launcher_size(
+                &format!("outputs.{}.launcher_size", name),
+                size,
+            ));
+        }
+        if config.enable_backdrop
+            && let Some(window_size) = config.get_window_size_on(Some(name))
+            && (output.launcher_size.is_some() || output.window_size.is_some())
+        {
+            warnings.extend(validate_window_size(
+                &format!("outputs.{}.window_size", name),
+                window_size,
+                config.get_launcher_size_on(Some(name)),
+            ));
+        }
+    }
+
+    // Validate placements
+    if let Some(warning) = validate_placement("placement", &config.placement) {
+        warnings.push(warning);
+    }
+    for (mode, placement) in &config.mode_placement {
+        if let Some(mut warning) = validate_placement("mode_placement", placement) {
+            warning.message = format!("{} mode: {}", mode.display_name(), warning.message);
+            warnings.push(warning);
+        }
+    }
+
+    warnings.extend(validate_terminal(&config.terminal));
+
+    // Validate environment variable names
+    let env_sections = std::iter::once(("env".to_string(), &config.env)).chain(
+        config
+            .applications
+            .iter()
+            .map(|(id, app)| (format!("applications.{}.env", id), &app.env)),
+    );
+    for (field, env) in env_sections {
+        for key in env.keys().filter(|key| !is_valid_variable_name(key)) {
+            warnings.push(ValidationWarning {
+                field: field.clone(),
+                message: format!(
+                    "Invalid environment variable name '{}'. It will be ignored.",
+                    key
+                ),
+            });
+        }
+    }
+
+    // Validate clipboard patterns
+    let pattern_fields = [
+        (
+            "clipboard.ignore_patterns",
+            &config.clipboard.ignore_patterns,
+        ),
+        (
+            "clipboard.sensitive_patterns",
+            &config.clipboard.sensitive_patterns,
+        ),
+    ];
+    for (field, patterns) in pattern_fields {
+        for pattern in patterns {
+            if let Err(e) = regex::Regex::new(pattern) {
+                warnings.push(ValidationWarning {
+                    field: field.to_string(),
+                    message: format!("Invalid pattern '{}': {}. It will be ignored.", pattern, e),
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Validate launcher panel dimensions.
+fn validate_launcher_size(
+    field: &str,
+    (launcher_w, launcher_h): (f32, f32),
+) -> Vec<ValidationWarning> {
+    let mut warnings = vec![];
 
     if launcher_w < 300.0 {
         warnings.push(ValidationWarning {
-            field: "launcher_size".to_string(),
+            field: field.to_string(),
             message: format!(
                 "Width {} is below minimum (300). Consider increasing for usability.",
                 launcher_w
@@ -37,7 +158,7 @@ pub fn validate_config(config: &AppConfig) -> Vec<ValidationWarning> {
         });
     } else if launcher_w > 2000.0 {
         warnings.push(ValidationWarning {
-            field: "launcher_size".to_string(),
+            field: field.to_string(),
             message: format!(
                 "Width {} exceeds maximum (2000). This may cause display issues.",
                 launcher_w
@@ -47,7 +168,7 @@ pub fn validate_config(config: &AppConfig) -> Vec<ValidationWarning> {
 
     if launcher_h < 200.0 {
         warnings.push(ValidationWarning {
-            field: "launcher_size".to_string(),
+            field: field.to_string(),
             message: format!(
                 "Height {} is below minimum (200). Consider increasing for usability.",
                 launcher_h
@@ -55,7 +176,7 @@ pub fn validate_config(config: &AppConfig) -> Vec<ValidationWarning> {
         });
     } else if launcher_h > 1500.0 {
         warnings.push(ValidationWarning {
-            field: "launcher_size".to_string(),
+            field: field.to_string(),
             message: format!(
                 "Height {} exceeds maximum (1500). This may cause display issues.",
                 launcher_h
@@ -63,47 +184,86 @@ pub fn validate_config(config: &AppConfig) -> Vec<ValidationWarning> {
         });
     }
 
-    // Validate search providers
-    if let Some(providers) = &config.search_providers {
-        for provider in providers {
-            warnings.extend(validate_search_provider(provider));
-        }
-    }
+    warnings
+}
 
-    // Validate theme exists (only if non-default)
-    if !config.theme.is_empty() && config.theme != "default" && !validate_theme_name(&config.theme)
-    {
+/// Validate that the window buffer can hold the launcher panel.
+fn validate_window_size(
+    field: &str,
+    (w, h): (f32, f32),
+    (launcher_w, launcher_h): (f32, f32),
+) -> Vec<ValidationWarning> {
+    let mut warnings = vec![];
+
+    if w < launcher_w {
         warnings.push(ValidationWarning {
-            field: "theme".to_string(),
+            field: field.to_string(),
             message: format!(
-                "Theme '{}' not found. Will fall back to default theme.",
-                config.theme
+                "window_size width ({}) is smaller than launcher_size width ({}). Will use {}.",
+                w, launcher_w, launcher_w
+            ),
+        });
+    }
+    if h < launcher_h {
+        warnings.push(ValidationWarning {
+            field: field.to_string(),
+            message: format!(
+                "window_size height ({}) is smaller than launcher_size height ({}). Will use {}.",
+                h, launcher_h, launcher_h
             ),
         });
     }
 
-    // Validate window_size if set (only relevant when enable_backdrop is true)
-    if config.enable_backdrop {
-        if let Some((w, h)) = config.window_size {
-            if w < launcher_w {
-                warnings.push(ValidationWarning {
-                    field: "window_size".to_string(),
-                    message: format!(
-                        "window_size width ({}) is smaller than launcher_size width ({}). Will use {}.",
-                        w, launcher_w, launcher_w
-                    ),
-                });
-            }
-            if h < launcher_h {
-                warnings.push(ValidationWarning {
-                    field: "window_size".to_string(),
-                    message: format!(
-                        "window_size height ({}) is smaller than launcher_size height ({}). Will use {}.",
-                        h, launcher_h, launcher_h
-                    ),
-                });
-            }
+    warnings
+}
+
+/// Validate that a stretched panel is anchored to an edge.
+fn validate_placement(field: &str, placement: &PlacementConfig) -> Option<ValidationWarning> {
+    (placement.fill && !placement.anchor.is_edge()).then(|| ValidationWarning {
+        field: field.to_string(),
+        message:
+            "fill only applies to the top, bottom, left and right anchors. It will be ignored."
+                .to_string(),
+    })
+}
+
+/// Validate the terminal configuration.
+fn validate_terminal(terminal: &TerminalConfig) -> Vec<ValidationWarning> {
+    let mut warnings = vec![];
+
+    match &terminal.command {
+        Some(command) if find_program(command).is_none() => {
+            warnings.push(ValidationWarning {
+                field: "terminal.command".to_string(),
+                message: format!(
+                    "Terminal '{}' not found in PATH. Terminal applications will fail to launch.",
+                    command
+                ),
+            });
+        }
+        None if terminal.args.is_some() => {
+            warnings.push(ValidationWarning {
+                field: "terminal.args".to_string(),
+                message: "terminal.args is ignored without terminal.command.".to_string(),
+            });
         }
+        _ => {}
+    }
+
+    let placeholders = terminal
+        .args
+        .iter()
+        .flatten()
+        .filter(|arg| *arg == COMMAND_PLACEHOLDER)
+        .count();
+    if placeholders > 1 {
+        warnings.push(ValidationWarning {
+            field: "terminal.args".to_string(),
+            message: format!(
+                "{} appears more than once. The command will run with repeated arguments.",
+                COMMAND_PLACEHOLDER
+            ),
+        });
     }
 
     warnings
@@ -383,4 +543,117 @@ mod tests {
         // Should have no window_size warnings since backdrop is disabled
         assert!(!warnings.iter().any(|w| w.field == "window_size"));
     }
+
+    #[test]
+    fn test_validate_output_sizes() {
+        let toml_str = r#"
+            [outputs.DP-1]
+            launcher_size = [100.0, 400.0]
+
+            [outputs.HDMI-A-1]
+            launcher_size = [1000.0, 700.0]
+            window_size = [800.0, 1080.0]
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        let warnings = validate_config(&config);
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.field == "outputs.DP-1.launcher_size" && w.message.contains("Width"))
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.field == "outputs.HDMI-A-1.window_size" && w.message.contains("width"))
+        );
+        assert!(!warnings.iter().any(|w| w.field == "launcher_size"));
+    }
+
+    #[test]
+    fn test_validate_placement_fill() {
+        let toml_str = r#"
+            [placement]
+            anchor = "left"
+            fill = true
+
+            [mode_placement.emojis]
+            anchor = "cursor"
+            fill = true
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).expect("Failed to deserialize");
+        let warnings = validate_config(&config);
+        assert!(!warnings.iter().any(|w| w.field == "placement"));
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.field == "mode_placement" && w.message.starts_with("Emojis mode"))
+        );
+    }
+
+    #[test]
+    fn test_validate_terminal_not_found() {
+        let config = AppConfig {
+            terminal: TerminalConfig {
+                command: Some("zlaunch-nonexistent-terminal".to_string()),
+                args: None,
+            },
+            ..AppConfig::default()
+        };
+        let warnings = validate_config(&config);
+        assert!(warnings.iter().any(|w| w.field == "terminal.command"));
+    }
+
+    #[test]
+    fn test_validate_terminal_args_without_command() {
+        let config = AppConfig {
+            terminal: TerminalConfig {
+                command: None,
+                args: Some(vec!["-e".to_string()]),
+            },
+            ..AppConfig::default()
+        };
+        let warnings = validate_config(&config);
+        assert!(warnings.iter().any(|w| w.field == "terminal.args"));
+    }
+
+    #[test]
+    fn test_validate_application_env_name() {
+        let mut config = AppConfig::default();
+        let app = config
+            .applications
+            .entry("firefox".to_string())
+            .or_default();
+        app.env.insert("A=B".to_string(), "1".to_string());
+        app.env
+            .insert("MOZ_ENABLE_WAYLAND".to_string(), "1".to_string());
+
+        let warnings = validate_config(&config);
+        let env_warnings: Vec<_> = warnings
+            .iter()
+            .filter(|w| w.field == "applications.firefox.env")
+            .collect();
+        assert_eq!(env_warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_global_env_name() {
+        let mut config = AppConfig::default();
+        config.env.insert(String::new(), "1".to_string());
+
+        let warnings = validate_config(&config);
+        assert!(warnings.iter().any(|w| w.field == "env"));
+    }
+
+    #[test]
+    fn test_validate_clipboard_patterns() {
+        let mut config = AppConfig::default();
+        config.clipboard.ignore_patterns = vec!["^token-".to_string(), "(".to_string()];
+
+        let warnings = validate_config(&config);
+        let pattern_warnings: Vec<_> = warnings
+            .iter()
+            .filter(|w| w.field == "clipboard.ignore_patterns")
+            .collect();
+        assert_eq!(pattern_warnings.len(), 1);
+    }
 }
@@ -7,8 +7,9 @@ use tracing::debug;
 
 use crate::app::window::LauncherWindow;
 use crate::app::{DaemonEvent, WindowEvent, window};
-use crate::compositor::Compositor;
+use crate::compositor::{Compositor, WindowTracker};
 use crate::config::get_default_modes;
+use crate::desktop::env::refresh_session_environment;
 use crate::error::IpcError;
 use crate::items::ApplicationItem;
 
@@ -56,6 +57,7 @@ pub async fn run_event_loop(
     event_tx: flume::Sender<DaemonEvent>,
     initial_applications: Vec<ApplicationItem>,
     compositor: Arc<dyn Compositor>,
+    window_tracker: WindowTracker,
     cx: &mut gpui::AsyncApp,
 ) {
     let mut window_state = WindowState::new();
@@ -69,12 +71,18 @@ pub async fn run_event_loop(
                 });
             }
 
-            DaemonEvent::Show { modes, response_tx } => {
+            DaemonEvent::Show {
+                modes,
+                output,
+                response_tx,
+            } => {
                 let result = handle_show(
                     &mut window_state,
                     modes,
+                    output,
                     &applications,
                     &compositor,
+                    &window_tracker,
                     &event_tx,
                     cx,
                 );
@@ -94,19 +102,28 @@ pub async fn run_event_loop(
                 }
             }
 
-            DaemonEvent::Toggle { modes, response_tx } => {
+            DaemonEvent::Toggle {
+                modes,
+                output,
+                response_tx,
+            } => {
                 debug!("Processing Toggle event, visible={}", window_state.visible);
                 let result = if window_state.visible {
-                    let _ = cx.update(|cx| {
-                        window_state.close(cx);
-                    });
+                    // Pressing the switcher binding again advances the selection
+                    if !advance_window_switcher(&window_state, cx) {
+                        let _ = cx.update(|cx| {
+                            window_state.close(cx);
+                        });
+                    }
                     Ok(())
                 } else {
                     handle_show(
                         &mut window_state,
                         modes,
+                        output,
                         &applications,
                         &compositor,
+                        &window_tracker,
                         &event_tx,
                         cx,
                     )
@@ -192,12 +209,37 @@ pub async fn run_event_loop(
     }
 }
 
+/// Advance the window switcher if it is shown.
+///
+/// Returns false if the launcher is not showing the window switcher.
+fn advance_window_switcher(window_state: &WindowState, cx: &mut gpui::AsyncApp) -> bool {
+    let Some(ref lw) = window_state.launcher_window else {
+        return false;
+    };
+    let view = lw.launcher_view.clone();
+    cx.update(|cx| {
+        lw.handle
+            .update(cx, |_, window, cx| {
+                view.update(cx, |launcher, cx| {
+                    launcher.advance_window_switcher(window, cx)
+                })
+            })
+            .unwrap_or(false)
+    })
+    .unwrap_or(false)
+}
+
 /// Handle the Show event - create and show the launcher window.
+///
+/// The window is shown on the given output, or the compositor's focused output.
+#[allow(clippy::too_many_arguments)]
 fn handle_show(
     window_state: &mut WindowState,
     modes: Option<Vec<crate::config::LauncherMode>>,
+    output: Option<String>,
     applications: &[ApplicationItem],
     compositor: &Arc<dyn Compositor>,
+    window_tracker: &WindowTracker,
     event_tx: &flume::Sender<DaemonEvent>,
     cx: &mut gpui::AsyncApp,
 ) -> Result<(), IpcError> {
@@ -205,14 +247,30 @@ fn handle_show(
         return Ok(()); // Already visible
     }
 
+    // Pick up variables imported into the session since the last show
+    std::thread::spawn(|| {
+        if let Err(e) = refresh_session_environment() {
+            debug!(%e, "Session environment not refreshed");
+        }
+    });
+
     // Use provided modes or fall back to configured defaults
     let effective_modes = modes.unwrap_or_else(get_default_modes);
 
+    let output = output.or_else(|| {
+        compositor.focused_output().unwrap_or_else(|e| {
+            debug!(%e, "Focused output unknown");
+            None
+        })
+    });
+
     cx.update(|cx| {
         match window::create_and_show_window(
             applications.to_vec(),
             compositor.clone(),
+            window_tracker,
             effective_modes,
+            output,
             event_tx.clone(),
             cx,
         ) {
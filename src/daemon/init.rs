@@ -6,8 +6,8 @@ use anyhow::Result;
 use std::sync::Arc;
 use tracing::{error, info};
 
-use crate::compositor::{Compositor, detect_compositor};
-use crate::config::{ConfigModule, get_combined_modules};
+use crate::compositor::{Compositor, WindowTracker, detect_compositor};
+use crate::config::{CompositorBackend, ConfigModule, get_combined_modules};
 use crate::desktop::cache::load_applications;
 use crate::ipc::{IpcServerHandle, client, prepare_socket, start_server};
 use crate::items::ApplicationItem;
@@ -56,7 +56,7 @@ pub fn start_ipc_server(
 }
 
 /// Initialize clipboard monitoring if enabled in config.
-pub fn init_clipboard_if_enabled() {
+pub fn init_clipboard_if_enabled(window_tracker: WindowTracker) {
     let combined_modules = get_combined_modules();
 
     if combined_modules.contains(&ConfigModule::Clipboard) {
@@ -64,19 +64,31 @@ pub fn init_clipboard_if_enabled() {
         crate::clipboard::data::init();
         info!("Initialized clipboard history");
 
-        let _clipboard_monitor_handle = crate::clipboard::monitor::start_monitor();
+        let _clipboard_monitor_handle = crate::clipboard::monitor::start_monitor(window_tracker);
     }
 }
 
-/// Detect and return the compositor.
+/// Detect (or create the configured) compositor and return it.
 pub fn init_compositor() -> Arc<dyn Compositor> {
-    Arc::from(detect_compositor())
+    Arc::from(detect_compositor(&crate::config::config()))
+}
+
+/// Start tracking open windows in the background.
+pub fn init_window_tracker(compositor: Arc<dyn Compositor>) -> WindowTracker {
+    WindowTracker::start(compositor)
 }
 
 /// Apply compositor-specific configuration (e.g., Hyprland blur rules).
 pub fn apply_compositor_config() {
-    if crate::config::config().hyprland_auto_blur {
-        match crate::compositor::hyprland::apply_blur_layer_rules() {
+    let config = crate::config::config();
+    let hyprland_allowed = matches!(
+        config.compositor,
+        CompositorBackend::Auto | CompositorBackend::Hyprland
+    );
+
+    if config.hyprland_auto_blur && hyprland_allowed {
+        let signature = config.hyprland.instance_signature.as_deref();
+        match crate::compositor::hyprland::apply_blur_layer_rules(signature) {
             Ok(true) => info!("Applied Hyprland blur layer rules"),
             Ok(false) => {} // Not on Hyprland, silently skip
             Err(e) => error!("Failed to apply Hyprland blur rules: {}", e),
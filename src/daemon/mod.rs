@@ -40,14 +40,18 @@ pub fn run() -> Result<()> {
     // Initialize config from file (single source of truth)
     crate::config::init_config();
 
+    // Load launch usage statistics for frecency ranking
+    crate::usage::init();
+
     // Capture the full session environment early
     crate::desktop::capture_session_environment();
 
-    // Start clipboard monitor if enabled
-    init::init_clipboard_if_enabled();
-
     // Detect compositor for window switching support
     let compositor = init::init_compositor();
+    let window_tracker = init::init_window_tracker(compositor.clone());
+
+    // Start clipboard monitor if enabled
+    init::init_clipboard_if_enabled(window_tracker.clone());
 
     // Apply compositor-specific configuration
     init::apply_compositor_config();
@@ -83,6 +87,7 @@ pub fn run() -> Result<()> {
             // Clone for move into async block
             let applications = applications.clone();
             let compositor = compositor.clone();
+            let window_tracker = window_tracker.clone();
             let event_tx_clone = event_tx.clone();
 
             // Spawn file watcher on shared tokio runtime
@@ -97,6 +102,7 @@ pub fn run() -> Result<()> {
                     event_tx_clone,
                     applications,
                     compositor,
+                    window_tracker,
                     cx,
                 )
                 .await;
@@ -107,6 +113,9 @@ pub fn run() -> Result<()> {
             .detach();
         });
 
+    // Write usage changes still waiting for their delayed save
+    crate::usage::flush();
+
     // After GPUI exits, check if we should reload
     if reload::is_reload_requested() {
         reload::exec_reload()?;
@@ -19,7 +19,7 @@ use tracing::{debug, info, warn};
 pub use validation::get_directory_mtimes;
 
 /// Current cache format version.
-const CACHE_VERSION: u32 = 1;
+const CACHE_VERSION: u32 = 4;
 
 /// Cached representation of a desktop entry.
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -33,6 +33,12 @@ pub struct CachedEntry {
     pub categories: Vec<String>,
     pub terminal: bool,
     pub source_path: PathBuf,
+    #[serde(default)]
+    pub mime_types: Vec<String>,
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub startup_wm_class: Option<String>,
     #[serde(with = "system_time_serde")]
     pub mtime: SystemTime,
 }
@@ -49,6 +55,9 @@ impl From<CachedEntry> for DesktopEntry {
             cached.categories,
             cached.terminal,
             cached.source_path,
+            cached.mime_types,
+            cached.working_dir,
+            cached.startup_wm_class,
         )
     }
 }
@@ -69,6 +78,9 @@ impl From<&DesktopEntry> for CachedEntry {
             categories: entry.categories.clone(),
             terminal: entry.terminal,
             source_path: entry.path.clone(),
+            mime_types: entry.mime_types.clone(),
+            working_dir: entry.working_dir.clone(),
+            startup_wm_class: entry.startup_wm_class.clone(),
             mtime,
         }
     }
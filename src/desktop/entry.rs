@@ -12,6 +12,14 @@ pub struct DesktopEntry {
     pub categories: Vec<String>,
     pub terminal: bool,
     pub path: PathBuf,
+    /// MIME types the application can open (from `MimeType=`)
+    pub mime_types: Vec<String>,
+    /// Working directory for the launched process (from `Path=`)
+    pub working_dir: Option<PathBuf>,
+    /// Window class of the application's windows (from `StartupWMClass=`)
+    pub startup_wm_class: Option<String>,
+    /// Extra environment variables for the launched process
+    pub env: Vec<(String, String)>,
 }
 
 impl DesktopEntry {
@@ -26,6 +34,9 @@ impl DesktopEntry {
         categories: Vec<String>,
         terminal: bool,
         path: PathBuf,
+        mime_types: Vec<String>,
+        working_dir: Option<PathBuf>,
+        startup_wm_class: Option<String>,
     ) -> Self {
         Self {
             id,
@@ -37,6 +48,10 @@ impl DesktopEntry {
             categories,
             terminal,
             path,
+            mime_types,
+            working_dir,
+            startup_wm_class,
+            env: Vec::new(),
         }
     }
 }
@@ -1,39 +1,166 @@
-use std::collections::HashMap;
+//! Session environment passed to launched applications.
+//!
+//! The environment is composed from, in increasing priority:
+//! 1. The daemon's process environment at startup
+//! 2. The systemd user session environment. Variables whose session value
+//!    changed since startup override the process environment.
+//! 3. The `[env]` config section
+//! 4. Runtime changes made with `zlaunch env set/unset`
+//!
+//! The systemd environment is re-read by [`refresh_session_environment`], so
+//! variables imported later (e.g. via `systemctl --user import-environment`)
+//! reach newly launched applications.
+
+use std::collections::{BTreeMap, HashMap};
 use std::process::Command;
-use std::sync::OnceLock;
+use std::sync::{Arc, RwLock};
+
+static SESSION_ENV: RwLock<Option<SessionEnvironment>> = RwLock::new(None);
 
-static SESSION_ENV: OnceLock<HashMap<String, String>> = OnceLock::new();
+/// Sources of the session environment and their merged result.
+struct SessionEnvironment {
+    /// Process environment at startup
+    process: HashMap<String, String>,
+    /// systemd user environment at startup
+    initial_systemd: HashMap<String, String>,
+    /// Most recently read systemd user environment
+    systemd: HashMap<String, String>,
+    /// Runtime changes (`None` unsets the variable)
+    overrides: BTreeMap<String, Option<String>>,
+    /// Merged environment handed to child processes
+    merged: Arc<HashMap<String, String>>,
+}
+
+impl SessionEnvironment {
+    fn new(systemd: HashMap<String, String>) -> Self {
+        let mut env = Self {
+            process: std::env::vars().collect(),
+            initial_systemd: systemd.clone(),
+            systemd,
+            overrides: BTreeMap::new(),
+            merged: Arc::default(),
+        };
+        env.merge();
+        env
+    }
+
+    /// Recompute the merged environment, returning the number of changed variables.
+    fn merge(&mut self) -> usize {
+        let merged = merge_environment(
+            &self.process,
+            &self.initial_systemd,
+            &self.systemd,
+            &crate::config::config().env,
+            &self.overrides,
+        );
+        let changed = count_changes(&self.merged, &merged);
+        self.merged = Arc::new(merged);
+        changed
+    }
+}
 
 /// Capture the user session environment at startup.
 /// This reads from systemd user session to get the full desktop environment,
 /// including theming variables like QT_QPA_PLATFORMTHEME, XDG_CURRENT_DESKTOP, etc.
 pub fn capture_session_environment() {
-    SESSION_ENV.get_or_init(|| {
-        let mut env = HashMap::new();
+    let systemd = read_systemd_user_environment().unwrap_or_default();
+    *SESSION_ENV.write().unwrap() = Some(SessionEnvironment::new(systemd));
+}
 
-        // Start with current process environment
-        for (key, value) in std::env::vars() {
-            env.insert(key, value);
-        }
+/// Get the session environment for passing to child processes.
+pub fn get_session_environment() -> Arc<HashMap<String, String>> {
+    if let Some(env) = SESSION_ENV.read().unwrap().as_ref() {
+        return env.merged.clone();
+    }
 
-        // Try to get additional variables from systemd user session
-        if let Some(systemd_env) = read_systemd_user_environment() {
-            for (key, value) in systemd_env {
-                // Only add if not already set (prefer current process env)
-                env.entry(key).or_insert(value);
-            }
-        }
+    // Fallback if not explicitly initialized
+    SESSION_ENV
+        .write()
+        .unwrap()
+        .get_or_insert_with(|| SessionEnvironment::new(HashMap::new()))
+        .merged
+        .clone()
+}
 
-        env
+/// Re-read the systemd user environment.
+///
+/// Returns the number of variables that changed in the session environment.
+pub fn refresh_session_environment() -> anyhow::Result<usize> {
+    let systemd = read_systemd_user_environment()
+        .ok_or_else(|| anyhow::anyhow!("Failed to read the systemd user environment"))?;
+    Ok(update_session_environment(|env| env.systemd = systemd))
+}
+
+/// Set a variable in the session environment until the daemon restarts.
+pub fn set_session_variable(name: &str, value: &str) {
+    update_session_environment(|env| {
+        env.overrides
+            .insert(name.to_string(), Some(value.to_string()));
+    });
+}
+
+/// Remove a variable from the session environment until the daemon restarts.
+pub fn unset_session_variable(name: &str) {
+    update_session_environment(|env| {
+        env.overrides.insert(name.to_string(), None);
     });
 }
 
-/// Get the captured session environment for passing to child processes.
-pub fn get_session_environment() -> &'static HashMap<String, String> {
-    SESSION_ENV.get_or_init(|| {
-        // Fallback if not explicitly initialized
-        std::env::vars().collect()
-    })
+/// Check whether a string can be used as an environment variable name.
+pub fn is_valid_variable_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['=', '\0'])
+}
+
+/// Apply a change to the session environment and re-merge it.
+fn update_session_environment(f: impl FnOnce(&mut SessionEnvironment)) -> usize {
+    let mut guard = SESSION_ENV.write().unwrap();
+    let env = guard.get_or_insert_with(|| SessionEnvironment::new(HashMap::new()));
+    f(env);
+    env.merge()
+}
+
+/// Merge the environment sources, later sources taking priority.
+fn merge_environment(
+    process: &HashMap<String, String>,
+    initial_systemd: &HashMap<String, String>,
+    systemd: &HashMap<String, String>,
+    config: &BTreeMap<String, String>,
+    overrides: &BTreeMap<String, Option<String>>,
+) -> HashMap<String, String> {
+    let mut env = process.clone();
+
+    for (key, value) in systemd {
+        // Prefer the process environment unless the session value changed since startup
+        if !env.contains_key(key) || initial_systemd.get(key) != Some(value) {
+            env.insert(key.clone(), value.clone());
+        }
+    }
+
+    env.extend(
+        config
+            .iter()
+            .filter(|(key, _)| is_valid_variable_name(key))
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+
+    for (key, value) in overrides {
+        match value {
+            Some(value) => env.insert(key.clone(), value.clone()),
+            None => env.remove(key),
+        };
+    }
+
+    env
+}
+
+/// Count the variables that were added, removed or changed.
+fn count_changes(old: &HashMap<String, String>, new: &HashMap<String, String>) -> usize {
+    let changed_or_added = new
+        .iter()
+        .filter(|(key, value)| old.get(*key) != Some(value))
+        .count();
+    let removed = old.keys().filter(|key| !new.contains_key(*key)).count();
+    changed_or_added + removed
 }
 
 /// Read environment variables from systemd user session.
@@ -47,10 +174,16 @@ fn read_systemd_user_environment() -> Option<HashMap<String, String>> {
         return None;
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    Some(parse_systemd_environment(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse the output of `systemctl --user show-environment`.
+fn parse_systemd_environment(output: &str) -> HashMap<String, String> {
     let mut env = HashMap::new();
 
-    for line in stdout.lines() {
+    for line in output.lines() {
         if let Some((key, value)) = line.split_once('=') {
             // systemd may quote values, remove quotes if present
             let value = value.trim_matches('"').trim_matches('\'');
@@ -58,5 +191,83 @@ fn read_systemd_user_environment() -> Option<HashMap<String, String>> {
         }
     }
 
-    Some(env)
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_process_env_preferred_until_session_changes() {
+        let process = vars(&[("WAYLAND_DISPLAY", "wayland-1"), ("HOME", "/home/u")]);
+        let initial = vars(&[("WAYLAND_DISPLAY", "wayland-0"), ("QT_STYLE", "kvantum")]);
+
+        let env = merge_environment(
+            &process,
+            &initial,
+            &initial,
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+        );
+        assert_eq!(env["WAYLAND_DISPLAY"], "wayland-1");
+        assert_eq!(env["QT_STYLE"], "kvantum");
+
+        let refreshed = vars(&[("WAYLAND_DISPLAY", "wayland-2"), ("QT_STYLE", "kvantum")]);
+        let env = merge_environment(
+            &process,
+            &initial,
+            &refreshed,
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+        );
+        assert_eq!(env["WAYLAND_DISPLAY"], "wayland-2");
+    }
+
+    #[test]
+    fn test_config_and_overrides_take_priority() {
+        let process = vars(&[("EDITOR", "vi"), ("SSH_AUTH_SOCK", "/tmp/old")]);
+        let config = BTreeMap::from([
+            ("EDITOR".to_string(), "nvim".to_string()),
+            ("A=B".to_string(), "ignored".to_string()),
+        ]);
+        let overrides = BTreeMap::from([
+            ("EDITOR".to_string(), Some("hx".to_string())),
+            ("SSH_AUTH_SOCK".to_string(), None),
+        ]);
+
+        let env = merge_environment(
+            &process,
+            &HashMap::new(),
+            &HashMap::new(),
+            &config,
+            &overrides,
+        );
+        assert_eq!(env["EDITOR"], "hx");
+        assert!(!env.contains_key("SSH_AUTH_SOCK"));
+        assert!(!env.contains_key("A=B"));
+    }
+
+    #[test]
+    fn test_count_changes() {
+        let old = vars(&[("A", "1"), ("B", "2"), ("C", "3")]);
+        let new = vars(&[("A", "1"), ("B", "20"), ("D", "4")]);
+        assert_eq!(count_changes(&old, &new), 3);
+    }
+
+    #[test]
+    fn test_parse_systemd_environment() {
+        let env = parse_systemd_environment("HOME=/home/u\nXCURSOR_THEME='Adwaita'\nbogus\n");
+        assert_eq!(
+            env,
+            vars(&[("HOME", "/home/u"), ("XCURSOR_THEME", "Adwaita")])
+        );
+    }
 }
@@ -1,26 +1,490 @@
+//! Launching desktop entries.
+//!
+//! Exec strings are split following the desktop entry quoting rules and
+//! field codes (`%f`, `%F`, `%u`, `%U`, `%i`, `%c`, `%k`) are expanded into
+//! separate arguments, so file paths containing spaces survive intact.
+
+use crate::compositor::{Compositor, WorkspaceTarget};
 use crate::desktop::entry::DesktopEntry;
-use crate::process;
+use crate::desktop::env::get_session_environment;
+use crate::error::ProcessError;
+use crate::process::{self, DetachedProcess};
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, warn};
+
+/// Counter making environment file names unique within the daemon.
+static NEXT_ENV_FILE: AtomicU64 = AtomicU64::new(0);
 
+/// Launch an application without any files.
 pub fn launch_application(entry: &DesktopEntry) -> anyhow::Result<()> {
-    let exec = clean_exec_string(&entry.exec);
+    launch_application_with_files(entry, &[])
+}
+
+/// Launch an application with the given files.
+///
+/// Entries that only accept a single file (`%f`/`%u`) are launched once
+/// per file. Entries without a file field code ignore the files.
+pub fn launch_application_with_files(
+    entry: &DesktopEntry,
+    files: &[PathBuf],
+) -> anyhow::Result<()> {
+    for args in build_commands(entry, files)? {
+        spawn_command(entry, &terminal_command(entry, args)?)?;
+    }
+
+    Ok(())
+}
+
+/// Launch an application with the given files on a workspace.
+///
+/// Falls back to launching normally if the compositor cannot launch onto
+/// workspaces, or fails to. Processes started by the compositor are not
+/// watched for early failures, as the launcher is not their parent.
+///
+/// The environment is handed over in a private file instead of the command
+/// line, which the compositor may log and which other users can read.
+pub fn launch_application_on_workspace(
+    entry: &DesktopEntry,
+    files: &[PathBuf],
+    compositor: &dyn Compositor,
+    workspace: WorkspaceTarget,
+) -> anyhow::Result<()> {
+    let session_env = get_session_environment();
+    for args in build_commands(entry, files)? {
+        let args = terminal_command(entry, args)?;
+        let env_file = match write_env_file(&env_file_contents(entry, &session_env)) {
+            Ok(path) => path,
+            Err(e) => {
+                warn!(%e, "Failed to write launch environment, launching normally");
+                spawn_command(entry, &args)?;
+                continue;
+            }
+        };
 
+        let line = shell_command_line(entry, &args, &env_file);
+        let launched = compositor.launch_on_workspace(&line, workspace);
+        if !matches!(launched, Ok(true)) {
+            let _ = fs::remove_file(&env_file);
+        }
+        match launched {
+            Ok(true) => {}
+            Ok(false) => spawn_command(entry, &args)?,
+            Err(e) => {
+                warn!(%e, "Failed to launch on workspace, launching normally");
+                spawn_command(entry, &args)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Wrap a command in a terminal emulator for terminal applications.
+fn terminal_command(entry: &DesktopEntry, args: Vec<String>) -> Result<Vec<String>, ProcessError> {
     if entry.terminal {
-        process::launch_in_terminal(&exec)?;
+        Ok(process::resolve_terminal()?.command(&args))
     } else {
-        process::launch_exec(&exec)?;
+        Ok(args)
     }
+}
 
-    Ok(())
+/// Spawn a command with the entry's environment and working directory.
+fn spawn_command(entry: &DesktopEntry, args: &[String]) -> Result<(), ProcessError> {
+    let (program, args) = args.split_first().ok_or(ProcessError::EmptyCommand)?;
+
+    let mut process = DetachedProcess::new(program)
+        .args(args)
+        .with_session_env()
+        .envs(entry.env.iter().map(|(k, v)| (k, v)));
+    if let Some(dir) = &entry.working_dir {
+        process = process.current_dir(dir);
+    }
+    process.spawn()
+}
+
+/// Build a shell command line for a command spawned by the compositor.
+///
+/// The compositor starts the process with its own environment, so the line
+/// clears it and has a shell load the environment from `env_file`, as
+/// written by [`write_env_file`]. The shell removes the file before running
+/// the command.
+fn shell_command_line(entry: &DesktopEntry, args: &[String], env_file: &Path) -> String {
+    let mut line = String::new();
+    if let Some(dir) = &entry.working_dir {
+        line.push_str(&format!("cd {}; ", shell_quote(&dir.to_string_lossy())));
+    }
+    line.push_str(r#"exec env -i /bin/sh -c '. "$0" && rm -f -- "$0" && exec "$@"' "#);
+    line.push_str(&shell_quote(&env_file.to_string_lossy()));
+
+    for arg in args {
+        line.push(' ');
+        line.push_str(&shell_quote(arg));
+    }
+    line
+}
+
+/// Build a shell script exporting the session environment and the entry's
+/// extra variables, as [`spawn_command`] sets them.
+///
+/// Variables whose names a shell cannot export are skipped.
+fn env_file_contents(entry: &DesktopEntry, session_env: &HashMap<String, String>) -> String {
+    let mut session_env: Vec<_> = session_env.iter().collect();
+    session_env.sort();
+    let vars = session_env
+        .into_iter()
+        .chain(entry.env.iter().map(|(key, value)| (key, value)));
+
+    let mut contents = String::new();
+    for (key, value) in vars {
+        let is_name = !key.starts_with(|c: char| c.is_ascii_digit())
+            && !key.is_empty()
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_name {
+            debug!(key, "Skipping variable that cannot be exported by a shell");
+            continue;
+        }
+        contents.push_str(&format!("export {}={}\n", key, shell_quote(value)));
+    }
+    contents
+}
+
+/// Write an environment file to the runtime directory, readable only by the
+/// user.
+fn write_env_file(contents: &str) -> anyhow::Result<PathBuf> {
+    let path = dirs::runtime_dir()
+        .context("XDG_RUNTIME_DIR is not set")?
+        .join(format!(
+            "zlaunch-env-{}-{}",
+            std::process::id(),
+            NEXT_ENV_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)?;
+    if let Err(e) = file.write_all(contents.as_bytes()) {
+        let _ = fs::remove_file(&path);
+        return Err(e.into());
+    }
+    Ok(path)
+}
+
+/// Quote an argument for a POSIX shell.
+fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Check whether an exec string accepts files or URLs.
+pub fn accepts_files(exec: &str) -> bool {
+    ["%f", "%F", "%u", "%U"]
+        .iter()
+        .any(|code| exec.contains(code))
+}
+
+/// Check whether an exec string accepts several files in one invocation.
+pub fn accepts_multiple_files(exec: &str) -> bool {
+    exec.contains("%F") || exec.contains("%U")
+}
+
+/// Quote an argument for use in an exec string.
+///
+/// Arguments containing whitespace or reserved characters are enclosed in
+/// double quotes with `"`, `` ` ``, `$` and `\` escaped. A literal `%` is
+/// written as `%%` so it is not taken for a field code.
+pub fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    let needs_quotes = arg.is_empty()
+        || arg.chars().any(|c| {
+            c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '`' | '$' | ';' | '&' | '|')
+        });
+    if !needs_quotes {
+        return arg;
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Build the argument vectors to run for an entry and a set of files.
+fn build_commands(
+    entry: &DesktopEntry,
+    files: &[PathBuf],
+) -> Result<Vec<Vec<String>>, ProcessError> {
+    let args =
+        split_exec(&entry.exec).ok_or_else(|| ProcessError::InvalidExec(entry.exec.clone()))?;
+
+    if args.is_empty() {
+        return Err(ProcessError::EmptyCommand);
+    }
+
+    if files.len() > 1 && !accepts_multiple_files(&entry.exec) && accepts_files(&entry.exec) {
+        return Ok(files
+            .iter()
+            .map(|file| expand_args(&args, entry, std::slice::from_ref(file)))
+            .collect());
+    }
+
+    Ok(vec![expand_args(&args, entry, files)])
+}
+
+/// Split an exec string into arguments.
+///
+/// Arguments may be enclosed in double quotes, inside which `\"`, `` \` ``,
+/// `\$` and `\\` are unescaped. Returns `None` for an unterminated quote.
+pub(crate) fn split_exec(exec: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                in_arg = true;
+            }
+            '\\' if in_quotes => current.push(chars.next()?),
+            c if c.is_whitespace() && !in_quotes => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return None;
+    }
+    if in_arg {
+        args.push(current);
+    }
+
+    Some(args)
+}
+
+/// Expand field codes in split arguments.
+fn expand_args(args: &[String], entry: &DesktopEntry, files: &[PathBuf]) -> Vec<String> {
+    let mut result = Vec::with_capacity(args.len() + files.len());
+
+    for arg in args {
+        match arg.as_str() {
+            "%F" | "%U" => result.extend(files.iter().map(|f| f.to_string_lossy().to_string())),
+            "%i" => {
+                if let Some(icon) = &entry.icon {
+                    result.push("--icon".to_string());
+                    result.push(icon.clone());
+                }
+            }
+            _ => {
+                if let Some(expanded) = expand_field_codes(arg, entry, files.first()) {
+                    result.push(expanded);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Expand field codes embedded in a single argument.
+///
+/// Returns `None` if the argument consisted only of field codes that
+/// expanded to nothing, so it can be dropped instead of passed as "".
+fn expand_field_codes(arg: &str, entry: &DesktopEntry, file: Option<&PathBuf>) -> Option<String> {
+    if !arg.contains('%') {
+        return Some(arg.to_string());
+    }
+
+    let mut result = String::new();
+    let mut chars = arg.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('%') => result.push('%'),
+            Some('f') | Some('u') => {
+                if let Some(file) = file {
+                    result.push_str(&file.to_string_lossy());
+                }
+            }
+            Some('c') => result.push_str(&entry.name),
+            Some('k') => result.push_str(&entry.path.to_string_lossy()),
+            // List codes inside a larger argument and deprecated codes expand to nothing
+            _ => {}
+        }
+    }
+
+    (!result.is_empty()).then_some(result)
 }
 
-fn clean_exec_string(exec: &str) -> String {
-    let mut result = exec.to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(exec: &str) -> DesktopEntry {
+        DesktopEntry::new(
+            "viewer".to_string(),
+            "Image Viewer".to_string(),
+            exec.to_string(),
+            Some("viewer-icon".to_string()),
+            None,
+            None,
+            vec![],
+            false,
+            PathBuf::from("/usr/share/applications/viewer.desktop"),
+            vec![],
+            None,
+            None,
+        )
+    }
+
+    fn files(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_split_exec_quotes() {
+        assert_eq!(
+            split_exec(r#"sh -c "echo \"hi there\"" %f"#).unwrap(),
+            vec!["sh", "-c", "echo \"hi there\"", "%f"]
+        );
+        assert_eq!(split_exec(r#"app """#).unwrap(), vec!["app", ""]);
+        assert!(split_exec(r#"app "unterminated"#).is_none());
+    }
 
-    for placeholder in [
-        "%f", "%F", "%u", "%U", "%d", "%D", "%n", "%N", "%i", "%c", "%k",
-    ] {
-        result = result.replace(placeholder, "");
+    #[test]
+    fn test_no_files_drops_field_codes() {
+        let commands = build_commands(&entry("viewer --new %U"), &[]).unwrap();
+        assert_eq!(commands, vec![vec!["viewer", "--new"]]);
     }
 
-    result.trim().to_string()
+    #[test]
+    fn test_multiple_files_single_invocation() {
+        let targets = files(&["/tmp/a b.png", "/tmp/c.png"]);
+        let commands = build_commands(&entry("viewer %F"), &targets).unwrap();
+        assert_eq!(commands, vec![vec!["viewer", "/tmp/a b.png", "/tmp/c.png"]]);
+    }
+
+    #[test]
+    fn test_single_file_code_launches_per_file() {
+        let targets = files(&["/tmp/a.png", "/tmp/b.png"]);
+        let commands = build_commands(&entry("viewer --file=%f"), &targets).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                vec!["viewer", "--file=/tmp/a.png"],
+                vec!["viewer", "--file=/tmp/b.png"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_icon_name_and_location_codes() {
+        let commands = build_commands(&entry("viewer %i --title %c %k 100%%"), &[]).unwrap();
+        assert_eq!(
+            commands,
+            vec![vec![
+                "viewer",
+                "--icon",
+                "viewer-icon",
+                "--title",
+                "Image Viewer",
+                "/usr/share/applications/viewer.desktop",
+                "100%",
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_quote_exec_arg_round_trip() {
+        let args = ["--flag", "two words", r#"say "hi" $HOME \ `x`"#, ""];
+        let exec = args
+            .iter()
+            .map(|arg| quote_exec_arg(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(split_exec(&exec).unwrap(), args);
+        assert_eq!(quote_exec_arg("--flag"), "--flag");
+        assert_eq!(quote_exec_arg("--zoom=100%"), "--zoom=100%%");
+    }
+
+    #[test]
+    fn test_accepts_files() {
+        assert!(accepts_files("viewer %u"));
+        assert!(!accepts_files("viewer"));
+        assert!(accepts_multiple_files("viewer %F"));
+        assert!(!accepts_multiple_files("viewer %f"));
+    }
+
+    #[test]
+    fn test_shell_command_line() {
+        let mut entry = entry("viewer %f");
+        let env_file = Path::new("/run/user/1000/zlaunch-env-1-0");
+        assert_eq!(
+            shell_command_line(
+                &entry,
+                &["viewer", "/tmp/it's here.png"].map(String::from),
+                env_file
+            ),
+            r#"exec env -i /bin/sh -c '. "$0" && rm -f -- "$0" && exec "$@"' "#.to_string()
+                + r#"/run/user/1000/zlaunch-env-1-0 viewer '/tmp/it'\''s here.png'"#
+        );
+
+        // The environment stays out of the command line
+        entry.working_dir = Some(PathBuf::from("/opt/viewer"));
+        entry.env = vec![("GDK_BACKEND".to_string(), "wayland".to_string())];
+        let line = shell_command_line(&entry, &["viewer".to_string()], env_file);
+        assert!(line.starts_with("cd /opt/viewer; exec env -i "));
+        assert!(!line.contains("GDK_BACKEND") && !line.contains("wayland"));
+    }
+
+    #[test]
+    fn test_env_file_contents() {
+        let mut entry = entry("viewer");
+        entry.env = vec![("GDK_BACKEND".to_string(), "wayland".to_string())];
+        let session_env = HashMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("GDK_BACKEND".to_string(), "x11".to_string()),
+            ("API_TOKEN".to_string(), "it's secret".to_string()),
+            ("BASH_FUNC_f%%".to_string(), "() { :; }".to_string()),
+        ]);
+        assert_eq!(
+            env_file_contents(&entry, &session_env),
+            "export API_TOKEN='it'\\''s secret'\n\
+             export GDK_BACKEND=x11\n\
+             export PATH=/usr/bin\n\
+             export GDK_BACKEND=wayland\n"
+        );
+    }
 }
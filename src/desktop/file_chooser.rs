@@ -0,0 +1,103 @@
+//! File selection through the XDG desktop portal.
+//!
+//! Uses `org.freedesktop.portal.FileChooser` so the user's native file
+//! dialog is shown regardless of toolkit.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+const PORTAL_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+
+/// Show the portal file dialog and wait for the user's choice.
+///
+/// Blocks until the dialog is closed. Returns an empty list if it was cancelled.
+pub fn pick_files(title: &str, multiple: bool) -> Result<Vec<PathBuf>> {
+    let connection = Connection::session().context("Failed to connect to session bus")?;
+
+    // Subscribe to the request's Response signal before calling, so a fast
+    // response cannot be missed. The request path is derived from our token.
+    let token = format!("zlaunch_{}", std::process::id());
+    let sender = connection
+        .unique_name()
+        .context("Session bus connection has no unique name")?
+        .trim_start_matches(':')
+        .replace('.', "_");
+    let request_path = format!("{}/request/{}/{}", PORTAL_PATH, sender, token);
+
+    let request = Proxy::new(
+        &connection,
+        PORTAL_DESTINATION,
+        request_path.as_str(),
+        "org.freedesktop.portal.Request",
+    )
+    .context("Failed to create portal request proxy")?;
+    let mut responses = request
+        .receive_signal("Response")
+        .context("Failed to subscribe to portal responses")?;
+
+    let chooser = Proxy::new(
+        &connection,
+        PORTAL_DESTINATION,
+        PORTAL_PATH,
+        "org.freedesktop.portal.FileChooser",
+    )
+    .context("Failed to create FileChooser proxy")?;
+
+    let mut options: HashMap<&str, Value> = HashMap::new();
+    options.insert("handle_token", Value::from(token.as_str()));
+    options.insert("multiple", Value::from(multiple));
+
+    let _: OwnedObjectPath = chooser
+        .call("OpenFile", &("", title, options))
+        .context("Failed to call FileChooser.OpenFile")?;
+
+    let message = responses
+        .next()
+        .context("Portal closed without a response")?;
+    let (response, mut results): (u32, HashMap<String, OwnedValue>) = message
+        .body()
+        .deserialize()
+        .context("Invalid portal response")?;
+
+    // Non-zero responses mean the dialog was cancelled or failed
+    if response != 0 {
+        return Ok(Vec::new());
+    }
+
+    let uris: Vec<String> = results
+        .remove("uris")
+        .map(Vec::<String>::try_from)
+        .transpose()
+        .context("Invalid uris in portal response")?
+        .unwrap_or_default();
+
+    Ok(uris
+        .iter()
+        .filter_map(|uri| file_uri_to_path(uri))
+        .collect())
+}
+
+/// Convert a `file://` URI to a local path.
+fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let decoded = urlencoding::decode(path).ok()?;
+    Some(PathBuf::from(decoded.into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_uri_to_path() {
+        assert_eq!(
+            file_uri_to_path("file:///home/user/My%20Photo.png"),
+            Some(PathBuf::from("/home/user/My Photo.png"))
+        );
+        assert_eq!(file_uri_to_path("https://example.com/a.png"), None);
+    }
+}
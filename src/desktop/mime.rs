@@ -0,0 +1,528 @@
+//! MIME type detection and file handler lookup.
+//!
+//! File types are resolved from the shared-mime-info glob database
+//! (`mime/globs2`, `mime/subclasses`, `mime/aliases`) and default handlers
+//! from `mimeapps.list`, following the XDG specifications.
+
+use crate::desktop::scanner::get_xdg_data_dirs;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Fallback type for files that are neither matched nor look like text.
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Global MIME database, loaded on first use.
+static MIME_DATABASE: OnceLock<MimeDatabase> = OnceLock::new();
+
+/// A single filename pattern from `globs2`.
+#[derive(Debug)]
+struct Glob {
+    weight: u32,
+    mime: String,
+    pattern: String,
+    case_sensitive: bool,
+}
+
+/// Filename patterns and type hierarchy from shared-mime-info.
+#[derive(Debug, Default)]
+pub struct MimeDatabase {
+    globs: Vec<Glob>,
+    /// Child type to parent types.
+    subclasses: HashMap<String, Vec<String>>,
+    /// Alias to canonical type.
+    aliases: HashMap<String, String>,
+}
+
+impl MimeDatabase {
+    /// Load the database from all XDG data directories.
+    pub fn load() -> Self {
+        let mut db = Self::default();
+
+        for dir in get_xdg_data_dirs() {
+            let mime_dir = dir.join("mime");
+            if let Ok(content) = fs::read_to_string(mime_dir.join("globs2")) {
+                db.add_globs(&content);
+            }
+            if let Ok(content) = fs::read_to_string(mime_dir.join("subclasses")) {
+                db.add_subclasses(&content);
+            }
+            if let Ok(content) = fs::read_to_string(mime_dir.join("aliases")) {
+                db.add_aliases(&content);
+            }
+        }
+
+        // Highest weight first, then the longest (most specific) pattern
+        db.globs.sort_by(|a, b| {
+            b.weight
+                .cmp(&a.weight)
+                .then_with(|| b.pattern.len().cmp(&a.pattern.len()))
+        });
+
+        db
+    }
+
+    /// Parse `globs2` lines (`weight:type:pattern[:flags]`).
+    fn add_globs(&mut self, content: &str) {
+        for line in content.lines().filter(|l| !l.starts_with('#')) {
+            let mut parts = line.splitn(4, ':');
+            let (Some(weight), Some(mime), Some(pattern)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let Ok(weight) = weight.parse() else {
+                continue;
+            };
+            let case_sensitive = parts.next().is_some_and(|flags| flags.contains("cs"));
+
+            self.globs.push(Glob {
+                weight,
+                mime: mime.to_string(),
+                pattern: if case_sensitive {
+                    pattern.to_string()
+                } else {
+                    pattern.to_lowercase()
+                },
+                case_sensitive,
+            });
+        }
+    }
+
+    /// Parse `subclasses` lines (`child parent`).
+    fn add_subclasses(&mut self, content: &str) {
+        for (child, parent) in content.lines().filter_map(|l| l.split_once(' ')) {
+            self.subclasses
+                .entry(child.to_string())
+                .or_default()
+                .push(parent.to_string());
+        }
+    }
+
+    /// Parse `aliases` lines (`alias canonical`).
+    fn add_aliases(&mut self, content: &str) {
+        for (alias, canonical) in content.lines().filter_map(|l| l.split_once(' ')) {
+            self.aliases
+                .entry(alias.to_string())
+                .or_insert_with(|| canonical.to_string());
+        }
+    }
+
+    /// Guess the MIME type of a file from its name, falling back to its content.
+    pub fn mime_type_for_path(&self, path: &Path) -> String {
+        if path.is_dir() {
+            return "inode/directory".to_string();
+        }
+
+        if let Some(name) = path.file_name().and_then(|n| n.to_str())
+            && let Some(mime) = self.mime_type_for_name(name)
+        {
+            return mime.to_string();
+        }
+
+        if looks_like_text(path) {
+            "text/plain".to_string()
+        } else {
+            OCTET_STREAM.to_string()
+        }
+    }
+
+    /// Match a file name against the glob patterns.
+    fn mime_type_for_name(&self, name: &str) -> Option<&str> {
+        let lower = name.to_lowercase();
+        self.globs
+            .iter()
+            .find(|glob| {
+                let name = if glob.case_sensitive { name } else { &lower };
+                glob_matches(&glob.pattern, name)
+            })
+            .map(|glob| glob.mime.as_str())
+    }
+
+    /// Get a type followed by all of its ancestors, most specific first.
+    ///
+    /// All `text/*` types implicitly inherit from `text/plain`.
+    pub fn with_parents(&self, mime: &str) -> Vec<String> {
+        let canonical = self.aliases.get(mime).map_or(mime, String::as_str);
+        let mut result = vec![canonical.to_string()];
+        let mut i = 0;
+
+        while i < result.len() {
+            let mut parents = self.subclasses.get(&result[i]).cloned().unwrap_or_default();
+            if result[i].starts_with("text/") && result[i] != "text/plain" {
+                parents.push("text/plain".to_string());
+            }
+            for parent in parents {
+                if !result.contains(&parent) {
+                    result.push(parent);
+                }
+            }
+            i += 1;
+        }
+
+        result
+    }
+}
+
+/// Get the global MIME database, loading it on first use.
+pub fn mime_database() -> &'static MimeDatabase {
+    MIME_DATABASE.get_or_init(MimeDatabase::load)
+}
+
+/// Application associations from `mimeapps.list`.
+#[derive(Debug, Default)]
+pub struct MimeApps {
+    /// Preferred handlers per type, in precedence order.
+    defaults: HashMap<String, Vec<String>>,
+    /// Extra handlers per type not declared in the applications' `MimeType=`.
+    added: HashMap<String, Vec<String>>,
+    /// Handlers that must not be offered for a type.
+    removed: HashMap<String, Vec<String>>,
+}
+
+impl MimeApps {
+    /// Load and merge all `mimeapps.list` files, most important first.
+    pub fn load() -> Self {
+        let mut apps = Self::default();
+
+        for path in mimeapps_paths() {
+            if let Ok(content) = fs::read_to_string(&path) {
+                apps.merge(&content);
+            }
+        }
+
+        apps
+    }
+
+    /// Merge a `mimeapps.list` file with lower precedence than those already merged.
+    fn merge(&mut self, content: &str) {
+        let mut section = "";
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = match name {
+                    "Default Applications" => "default",
+                    "Added Associations" => "added",
+                    "Removed Associations" => "removed",
+                    _ => "",
+                };
+                continue;
+            }
+
+            let target = match section {
+                "default" => &mut self.defaults,
+                "added" => &mut self.added,
+                "removed" => &mut self.removed,
+                _ => continue,
+            };
+            let Some((mime, ids)) = line.split_once('=') else {
+                continue;
+            };
+
+            let list = target.entry(mime.trim().to_string()).or_default();
+            for id in ids.split(';').map(str::trim).filter(|id| !id.is_empty()) {
+                let id = id.strip_suffix(".desktop").unwrap_or(id).to_string();
+                if !list.contains(&id) {
+                    list.push(id);
+                }
+            }
+        }
+    }
+
+    /// Rank the applications that can open all files of the given types.
+    ///
+    /// `file_types` holds, per file, its MIME type followed by its parents.
+    /// `apps` holds each application's id and declared MIME types. Returns
+    /// indices into `apps`; the default handler for the first file (if any
+    /// qualifies) comes first and is reported in `Handlers::has_default`.
+    pub fn handlers(&self, file_types: &[Vec<String>], apps: &[(&str, &[String])]) -> Handlers {
+        let can_open = |id: &str, declared: &[String], types: &[String]| {
+            types.iter().any(|mime| {
+                let removed = self
+                    .removed
+                    .get(mime)
+                    .is_some_and(|ids| ids.iter().any(|r| r == id));
+                let added = self
+                    .added
+                    .get(mime)
+                    .is_some_and(|ids| ids.iter().any(|a| a == id));
+                let default = self
+                    .defaults
+                    .get(mime)
+                    .is_some_and(|ids| ids.iter().any(|d| d == id));
+                !removed && (declared.contains(mime) || added || default)
+            })
+        };
+
+        let mut indices: Vec<usize> = apps
+            .iter()
+            .enumerate()
+            .filter(|(_, (id, declared))| {
+                !file_types.is_empty()
+                    && file_types.iter().all(|types| can_open(id, declared, types))
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        // The most specific type with a usable default decides
+        let default = file_types.first().and_then(|types| {
+            types.iter().find_map(|mime| {
+                self.defaults
+                    .get(mime)?
+                    .iter()
+                    .find_map(|id| indices.iter().position(|&index| apps[index].0 == id))
+            })
+        });
+
+        if let Some(pos) = default {
+            let index = indices.remove(pos);
+            indices.insert(0, index);
+        }
+
+        Handlers {
+            indices,
+            has_default: default.is_some(),
+        }
+    }
+}
+
+/// Applications able to open a set of files.
+#[derive(Debug, Default, PartialEq)]
+pub struct Handlers {
+    /// Indices of the applications, default handler first.
+    pub indices: Vec<usize>,
+    /// Whether the first index is the configured default handler.
+    pub has_default: bool,
+}
+
+/// Find the applications able to open all given files.
+///
+/// See [`MimeApps::handlers`] for the meaning of `apps` and the result.
+pub fn handlers_for_files(
+    files: &[PathBuf],
+    apps: &[(&str, &[String])],
+    mime_apps: &MimeApps,
+) -> Handlers {
+    let db = mime_database();
+    let file_types: Vec<Vec<String>> = files
+        .iter()
+        .map(|file| db.with_parents(&db.mime_type_for_path(file)))
+        .collect();
+
+    mime_apps.handlers(&file_types, apps)
+}
+
+/// Get the `mimeapps.list` locations, most important first.
+fn mimeapps_paths() -> Vec<PathBuf> {
+    let desktops: Vec<String> = std::env::var("XDG_CURRENT_DESKTOP")
+        .map(|d| d.split(':').map(|s| s.to_lowercase()).collect())
+        .unwrap_or_default();
+
+    let mut dirs = Vec::new();
+    if let Some(config_home) = dirs::config_dir() {
+        dirs.push(config_home);
+    }
+    match std::env::var("XDG_CONFIG_DIRS") {
+        Ok(config_dirs) => dirs.extend(config_dirs.split(':').map(PathBuf::from)),
+        Err(_) => dirs.push(PathBuf::from("/etc/xdg")),
+    }
+    dirs.extend(
+        get_xdg_data_dirs()
+            .into_iter()
+            .map(|dir| dir.join("applications")),
+    );
+
+    let mut paths = Vec::new();
+    for dir in dirs {
+        for desktop in &desktops {
+            paths.push(dir.join(format!("{}-mimeapps.list", desktop)));
+        }
+        paths.push(dir.join("mimeapps.list"));
+    }
+    paths
+}
+
+/// Check whether the start of a file looks like text (valid UTF-8, no NUL bytes).
+fn looks_like_text(path: &Path) -> bool {
+    let Ok(mut file) = fs::File::open(path) else {
+        return false;
+    };
+    let mut buf = [0u8; 512];
+    let Ok(len) = file.read(&mut buf) else {
+        return false;
+    };
+
+    let head = &buf[..len];
+    if head.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // A multi-byte character may have been cut off at the end of the buffer
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Match a file name against a glob pattern supporting `*` and `?`.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the last star swallow one more character
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn database() -> MimeDatabase {
+        let mut db = MimeDatabase::default();
+        db.add_globs(
+            "# comment\n\
+             50:text/x-rust:*.rs\n\
+             50:image/png:*.png\n\
+             50:application/gzip:*.gz\n\
+             55:application/x-compressed-tar:*.tar.gz\n\
+             50:text/x-makefile:Makefile:cs\n",
+        );
+        db.add_subclasses(
+            "text/x-rust text/plain\napplication/x-compressed-tar application/gzip\n",
+        );
+        db.add_aliases("image/x-png image/png\n");
+        db.globs.sort_by(|a, b| {
+            b.weight
+                .cmp(&a.weight)
+                .then_with(|| b.pattern.len().cmp(&a.pattern.len()))
+        });
+        db
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*.png", "photo.png"));
+        assert!(glob_matches("*.tar.gz", "src.tar.gz"));
+        assert!(glob_matches("file?.txt", "file1.txt"));
+        assert!(glob_matches("Makefile", "Makefile"));
+        assert!(!glob_matches("*.png", "photo.jpg"));
+        assert!(!glob_matches("Makefile", "Makefile.am"));
+    }
+
+    #[test]
+    fn test_mime_type_for_name() {
+        let db = database();
+        assert_eq!(db.mime_type_for_name("main.rs"), Some("text/x-rust"));
+        assert_eq!(db.mime_type_for_name("PHOTO.PNG"), Some("image/png"));
+        assert_eq!(
+            db.mime_type_for_name("src.tar.gz"),
+            Some("application/x-compressed-tar")
+        );
+        assert_eq!(db.mime_type_for_name("Makefile"), Some("text/x-makefile"));
+        assert_eq!(db.mime_type_for_name("makefile"), None);
+    }
+
+    #[test]
+    fn test_with_parents() {
+        let db = database();
+        assert_eq!(
+            db.with_parents("text/x-rust"),
+            types(&["text/x-rust", "text/plain"])
+        );
+        assert_eq!(
+            db.with_parents("text/x-makefile"),
+            types(&["text/x-makefile", "text/plain"])
+        );
+        assert_eq!(
+            db.with_parents("application/x-compressed-tar"),
+            types(&["application/x-compressed-tar", "application/gzip"])
+        );
+        assert_eq!(db.with_parents("image/x-png"), types(&["image/png"]));
+    }
+
+    #[test]
+    fn test_mimeapps_merge_precedence() {
+        let mut apps = MimeApps::default();
+        apps.merge("[Default Applications]\nimage/png=gimp.desktop;\n");
+        apps.merge("[Default Applications]\nimage/png=eog.desktop;gimp.desktop\n[Added Associations]\nimage/png=krita.desktop;\n");
+
+        assert_eq!(apps.defaults["image/png"], types(&["gimp", "eog"]));
+        assert_eq!(apps.added["image/png"], types(&["krita"]));
+    }
+
+    #[test]
+    fn test_handlers_default_first() {
+        let mut mimeapps = MimeApps::default();
+        mimeapps.merge(
+            "[Default Applications]\n\
+             text/plain=gedit.desktop\n\
+             [Added Associations]\n\
+             text/x-rust=code.desktop;\n\
+             [Removed Associations]\n\
+             text/plain=libreoffice.desktop\n",
+        );
+
+        let none = types(&[]);
+        let editor = types(&["text/plain"]);
+        let office = types(&["text/plain", "application/msword"]);
+        let viewer = types(&["image/png"]);
+        let apps: Vec<(&str, &[String])> = vec![
+            ("code", none.as_slice()),
+            ("libreoffice", office.as_slice()),
+            ("eog", viewer.as_slice()),
+            ("gedit", editor.as_slice()),
+        ];
+
+        let handlers = mimeapps.handlers(&[types(&["text/x-rust", "text/plain"])], &apps);
+        assert_eq!(handlers.indices, vec![3, 0]);
+        assert!(handlers.has_default);
+    }
+
+    #[test]
+    fn test_handlers_require_all_files() {
+        let mimeapps = MimeApps::default();
+        let images = types(&["image/png", "image/jpeg"]);
+        let png_only = types(&["image/png"]);
+        let apps: Vec<(&str, &[String])> = vec![
+            ("gimp", images.as_slice()),
+            ("optipng", png_only.as_slice()),
+        ];
+
+        let handlers = mimeapps.handlers(&[types(&["image/png"]), types(&["image/jpeg"])], &apps);
+        assert_eq!(handlers.indices, vec![0]);
+        assert!(!handlers.has_default);
+
+        assert_eq!(mimeapps.handlers(&[], &apps), Handlers::default());
+    }
+}
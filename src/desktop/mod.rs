@@ -2,6 +2,8 @@ pub mod cache;
 pub mod entry;
 pub mod env;
 pub mod exec;
+pub mod file_chooser;
+pub mod mime;
 pub mod parser;
 pub mod scanner;
 pub mod watcher;
@@ -9,5 +11,7 @@ pub mod watcher;
 pub use cache::load_applications;
 pub use entry::DesktopEntry;
 pub use env::{capture_session_environment, get_session_environment};
-pub use exec::launch_application;
+pub use exec::{
+    launch_application, launch_application_on_workspace, launch_application_with_files,
+};
 pub use scanner::scan_applications;
@@ -1,6 +1,6 @@
 use crate::desktop::entry::DesktopEntry;
 use freedesktop_desktop_entry::DesktopEntry as FdEntry;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub fn parse_desktop_file(path: &Path) -> Option<DesktopEntry> {
     let content = std::fs::read_to_string(path).ok()?;
@@ -30,6 +30,21 @@ pub fn parse_desktop_file(path: &Path) -> Option<DesktopEntry> {
 
     let terminal = fd_entry.terminal();
 
+    let mime_types: Vec<String> = fd_entry
+        .mime_type()
+        .map(|types| types.into_iter().map(|t| t.to_string()).collect())
+        .unwrap_or_default();
+
+    let working_dir = fd_entry
+        .desktop_entry("Path")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+
+    let startup_wm_class = fd_entry
+        .desktop_entry("StartupWMClass")
+        .filter(|class| !class.is_empty())
+        .map(|class| class.to_string());
+
     // icon_path is resolved later in cache.rs after all entries are loaded
     Some(DesktopEntry::new(
         id,
@@ -41,5 +56,41 @@ pub fn parse_desktop_file(path: &Path) -> Option<DesktopEntry> {
         categories,
         terminal,
         path.to_path_buf(),
+        mime_types,
+        working_dir,
+        startup_wm_class,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str, content: &str) -> Option<DesktopEntry> {
+        let dir = std::env::temp_dir().join(format!("zlaunch-parser-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        let entry = parse_desktop_file(&path);
+        std::fs::remove_file(&path).ok();
+        entry
+    }
+
+    #[test]
+    fn test_parse_path_and_wm_class() {
+        let entry = parse(
+            "game.desktop",
+            "[Desktop Entry]\nType=Application\nName=Game\nExec=./run.sh\nPath=/opt/game\nStartupWMClass=GameWindow\n",
+        )
+        .unwrap();
+        assert_eq!(entry.working_dir, Some(PathBuf::from("/opt/game")));
+        assert_eq!(entry.startup_wm_class.as_deref(), Some("GameWindow"));
+
+        let entry = parse(
+            "editor.desktop",
+            "[Desktop Entry]\nType=Application\nName=Editor\nExec=editor %F\nPath=\n",
+        )
+        .unwrap();
+        assert_eq!(entry.working_dir, None);
+    }
+}
@@ -16,20 +16,29 @@ pub fn scan_applications() -> Vec<DesktopEntry> {
     result
 }
 
-fn get_xdg_application_dirs() -> Vec<PathBuf> {
+/// Get the XDG application directories, most important first.
+pub(crate) fn get_xdg_application_dirs() -> Vec<PathBuf> {
+    get_xdg_data_dirs()
+        .into_iter()
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// Get the XDG data directories, most important first.
+pub(crate) fn get_xdg_data_dirs() -> Vec<PathBuf> {
     let mut dirs = Vec::new();
 
     if let Some(data_home) = dirs::data_local_dir() {
-        dirs.push(data_home.join("applications"));
+        dirs.push(data_home);
     }
 
     if let Ok(xdg_dirs) = std::env::var("XDG_DATA_DIRS") {
         for dir in xdg_dirs.split(':') {
-            dirs.push(PathBuf::from(dir).join("applications"));
+            dirs.push(PathBuf::from(dir));
         }
     } else {
-        dirs.push(PathBuf::from("/usr/local/share/applications"));
-        dirs.push(PathBuf::from("/usr/share/applications"));
+        dirs.push(PathBuf::from("/usr/local/share"));
+        dirs.push(PathBuf::from("/usr/share"));
     }
 
     dirs
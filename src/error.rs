@@ -23,6 +23,10 @@ pub enum IpcError {
     #[error("Theme '{0}' not found")]
     ThemeNotFound(String),
 
+    /// An environment variable name was empty or contained `=`.
+    #[error("Invalid environment variable name '{0}'")]
+    InvalidEnvName(String),
+
     /// A general internal error occurred.
     #[error("{0}")]
     Internal(String),
@@ -35,8 +39,14 @@ pub enum ProcessError {
     #[error("Empty exec command")]
     EmptyCommand,
 
+    /// The exec command string could not be parsed.
+    #[error("Invalid exec command: {0}")]
+    InvalidExec(String),
+
     /// No terminal emulator could be found.
-    #[error("No terminal emulator found. Set $TERMINAL environment variable.")]
+    #[error(
+        "No terminal emulator found. Set `command` in the [terminal] config section or $TERMINAL."
+    )]
     NoTerminal,
 
     /// Failed to spawn the process.
@@ -1,7 +1,7 @@
 //! tarpc client for communicating with the daemon.
 
 use crate::config::LauncherMode;
-use crate::ipc::commands::{ThemeInfo, ZlaunchServiceClient};
+use crate::ipc::commands::{SelectionInfo, StatusInfo, ThemeInfo, ZlaunchServiceClient};
 use crate::ipc::server::get_socket_path;
 use tarpc::client;
 use tarpc::context;
@@ -27,11 +27,11 @@ async fn connect() -> anyhow::Result<ZlaunchServiceClient> {
     Ok(client)
 }
 
-/// Show the launcher window with optional modes.
-pub fn show(modes: Option<Vec<LauncherMode>>) -> anyhow::Result<()> {
+/// Show the launcher window with optional modes, on an output or the focused one.
+pub fn show(modes: Option<Vec<LauncherMode>>, output: Option<String>) -> anyhow::Result<()> {
     run_async(async {
         let client = connect().await?;
-        Ok(client.show(context::current(), modes).await??)
+        Ok(client.show(context::current(), modes, output).await??)
     })
 }
 
@@ -43,11 +43,11 @@ pub fn hide() -> anyhow::Result<()> {
     })
 }
 
-/// Toggle the launcher window visibility with optional modes.
-pub fn toggle(modes: Option<Vec<LauncherMode>>) -> anyhow::Result<()> {
+/// Toggle the launcher window visibility with optional modes, on an output or the focused one.
+pub fn toggle(modes: Option<Vec<LauncherMode>>, output: Option<String>) -> anyhow::Result<()> {
     run_async(async {
         let client = connect().await?;
-        Ok(client.toggle(context::current(), modes).await??)
+        Ok(client.toggle(context::current(), modes, output).await??)
     })
 }
 
@@ -92,6 +92,65 @@ pub fn set_theme(name: &str) -> anyhow::Result<()> {
     })
 }
 
+/// List learned query selections.
+pub fn list_selections() -> anyhow::Result<Vec<SelectionInfo>> {
+    run_async(async {
+        let client = connect().await?;
+        Ok(client.list_selections(context::current()).await?)
+    })
+}
+
+/// Forget learned selections for one query, or all if none is given.
+pub fn reset_selections(query: Option<&str>) -> anyhow::Result<usize> {
+    let query = query.map(str::to_string);
+    run_async(async {
+        let client = connect().await?;
+        Ok(client.reset_selections(context::current(), query).await?)
+    })
+}
+
+/// List the environment passed to launched applications.
+pub fn list_env() -> anyhow::Result<Vec<(String, String)>> {
+    run_async(async {
+        let client = connect().await?;
+        Ok(client.list_env(context::current()).await?)
+    })
+}
+
+/// Set an environment variable for launched applications.
+pub fn set_env(name: &str, value: &str) -> anyhow::Result<()> {
+    let (name, value) = (name.to_string(), value.to_string());
+    run_async(async {
+        let client = connect().await?;
+        Ok(client.set_env(context::current(), name, value).await??)
+    })
+}
+
+/// Unset an environment variable for launched applications.
+pub fn unset_env(name: &str) -> anyhow::Result<()> {
+    let name = name.to_string();
+    run_async(async {
+        let client = connect().await?;
+        Ok(client.unset_env(context::current(), name).await??)
+    })
+}
+
+/// Re-read the systemd user environment, returning the number of changed variables.
+pub fn refresh_env() -> anyhow::Result<usize> {
+    run_async(async {
+        let client = connect().await?;
+        Ok(client.refresh_env(context::current()).await??)
+    })
+}
+
+/// Get the daemon status.
+pub fn status() -> anyhow::Result<StatusInfo> {
+    run_async(async {
+        let client = connect().await?;
+        Ok(client.status(context::current()).await?)
+    })
+}
+
 /// Run an async operation synchronously using a temporary tokio runtime.
 fn run_async<F, T>(future: F) -> anyhow::Result<T>
 where
@@ -13,17 +13,54 @@ pub struct ThemeInfo {
     pub is_bundled: bool,
 }
 
+/// A learned query selection returned by the IPC service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionInfo {
+    /// Normalized query the item was chosen for
+    pub query: String,
+    /// Usage key of the chosen item (e.g. `app:firefox`)
+    pub item: String,
+    /// Number of consecutive times the item was chosen
+    pub count: u32,
+}
+
+/// Daemon status returned by the IPC service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusInfo {
+    /// Process ID of the daemon
+    pub pid: u32,
+    /// Recent launch failures, oldest first
+    pub failures: Vec<LaunchFailureInfo>,
+}
+
+/// A failed launch returned by the IPC service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchFailureInfo {
+    /// The launched command line
+    pub command: String,
+    /// Why the launch failed
+    pub reason: String,
+    /// Last lines the process wrote to stderr
+    pub stderr: Vec<String>,
+    /// Seconds since the failure
+    pub age_secs: u64,
+}
+
 /// The zlaunch RPC service definition.
 #[tarpc::service]
 pub trait ZlaunchService {
-    /// Show the launcher window with optional modes.
-    async fn show(modes: Option<Vec<LauncherMode>>) -> Result<(), IpcError>;
+    /// Show the launcher window with optional modes, on an output or the focused one.
+    async fn show(modes: Option<Vec<LauncherMode>>, output: Option<String>)
+    -> Result<(), IpcError>;
 
     /// Hide the launcher window.
     async fn hide() -> Result<(), IpcError>;
 
-    /// Toggle the launcher window visibility with optional modes.
-    async fn toggle(modes: Option<Vec<LauncherMode>>) -> Result<(), IpcError>;
+    /// Toggle the launcher window visibility with optional modes, on an output or the focused one.
+    async fn toggle(
+        modes: Option<Vec<LauncherMode>>,
+        output: Option<String>,
+    ) -> Result<(), IpcError>;
 
     /// Quit the daemon.
     async fn quit() -> Result<(), IpcError>;
@@ -40,4 +77,27 @@ pub trait ZlaunchService {
     /// Set the active theme by name.
     /// Returns Ok(()) if successful, Err with IpcError if theme not found.
     async fn set_theme(name: String) -> Result<(), IpcError>;
+
+    /// List learned query selections.
+    async fn list_selections() -> Vec<SelectionInfo>;
+
+    /// Forget learned selections for one query, or all if none is given.
+    /// Returns the number of removed selections.
+    async fn reset_selections(query: Option<String>) -> usize;
+
+    /// List the environment passed to launched applications, sorted by name.
+    async fn list_env() -> Vec<(String, String)>;
+
+    /// Set an environment variable for launched applications.
+    async fn set_env(name: String, value: String) -> Result<(), IpcError>;
+
+    /// Unset an environment variable for launched applications.
+    async fn unset_env(name: String) -> Result<(), IpcError>;
+
+    /// Re-read the systemd user environment.
+    /// Returns the number of changed variables.
+    async fn refresh_env() -> Result<usize, IpcError>;
+
+    /// Get the daemon status, including recent launch failures.
+    async fn status() -> StatusInfo;
 }
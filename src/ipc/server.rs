@@ -2,8 +2,14 @@
 
 use crate::app::DaemonEvent;
 use crate::config::LauncherMode;
+use crate::desktop::env::{
+    get_session_environment, is_valid_variable_name, refresh_session_environment,
+    set_session_variable, unset_session_variable,
+};
 use crate::error::IpcError;
-use crate::ipc::commands::{ThemeInfo, ZlaunchService};
+use crate::ipc::commands::{
+    LaunchFailureInfo, SelectionInfo, StatusInfo, ThemeInfo, ZlaunchService,
+};
 use crate::items::ThemeSource;
 use futures::prelude::*;
 use std::path::PathBuf;
@@ -52,10 +58,19 @@ struct ZlaunchServer {
 }
 
 impl ZlaunchService for ZlaunchServer {
-    async fn show(self, _: Context, modes: Option<Vec<LauncherMode>>) -> Result<(), IpcError> {
+    async fn show(
+        self,
+        _: Context,
+        modes: Option<Vec<LauncherMode>>,
+        output: Option<String>,
+    ) -> Result<(), IpcError> {
         let (response_tx, response_rx) = oneshot::channel();
         self.event_tx
-            .send(DaemonEvent::Show { modes, response_tx })
+            .send(DaemonEvent::Show {
+                modes,
+                output,
+                response_tx,
+            })
             .map_err(|_| IpcError::ChannelClosed)?;
         response_rx.await.unwrap_or(Err(IpcError::ResponseClosed))
     }
@@ -68,10 +83,19 @@ impl ZlaunchService for ZlaunchServer {
         response_rx.await.unwrap_or(Err(IpcError::ResponseClosed))
     }
 
-    async fn toggle(self, _: Context, modes: Option<Vec<LauncherMode>>) -> Result<(), IpcError> {
+    async fn toggle(
+        self,
+        _: Context,
+        modes: Option<Vec<LauncherMode>>,
+        output: Option<String>,
+    ) -> Result<(), IpcError> {
         let (response_tx, response_rx) = oneshot::channel();
         self.event_tx
-            .send(DaemonEvent::Toggle { modes, response_tx })
+            .send(DaemonEvent::Toggle {
+                modes,
+                output,
+                response_tx,
+            })
             .map_err(|_| IpcError::ChannelClosed)?;
         response_rx.await.unwrap_or(Err(IpcError::ResponseClosed))
     }
@@ -115,6 +139,79 @@ impl ZlaunchService for ZlaunchServer {
             .map_err(|_| IpcError::ChannelClosed)?;
         response_rx.await.unwrap_or(Err(IpcError::ResponseClosed))
     }
+
+    async fn list_selections(self, _: Context) -> Vec<SelectionInfo> {
+        // Read-only operation - can be answered directly
+        crate::usage::list_selections()
+            .into_iter()
+            .map(|(query, selection)| SelectionInfo {
+                query,
+                item: selection.key,
+                count: selection.count,
+            })
+            .collect()
+    }
+
+    async fn reset_selections(self, _: Context, query: Option<String>) -> usize {
+        // The usage store is globally synchronized - can be answered directly
+        crate::usage::reset_selections(query.as_deref())
+    }
+
+    async fn list_env(self, _: Context) -> Vec<(String, String)> {
+        // Read-only operation - can be answered directly
+        let mut vars: Vec<(String, String)> = get_session_environment()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        vars.sort();
+        vars
+    }
+
+    async fn set_env(self, _: Context, name: String, value: String) -> Result<(), IpcError> {
+        if !is_valid_variable_name(&name) {
+            return Err(IpcError::InvalidEnvName(name));
+        }
+        set_session_variable(&name, &value);
+        Ok(())
+    }
+
+    async fn unset_env(self, _: Context, name: String) -> Result<(), IpcError> {
+        if !is_valid_variable_name(&name) {
+            return Err(IpcError::InvalidEnvName(name));
+        }
+        unset_session_variable(&name);
+        Ok(())
+    }
+
+    async fn refresh_env(self, _: Context) -> Result<usize, IpcError> {
+        // Runs systemctl, so keep it off the async workers
+        tokio::task::spawn_blocking(refresh_session_environment)
+            .await
+            .map_err(|e| IpcError::Internal(e.to_string()))?
+            .map_err(|e| IpcError::Internal(e.to_string()))
+    }
+
+    async fn status(self, _: Context) -> StatusInfo {
+        // Read-only operation - can be answered directly
+        let failures = crate::process::recent_failures()
+            .into_iter()
+            .map(|failure| LaunchFailureInfo {
+                age_secs: failure
+                    .time
+                    .elapsed()
+                    .map(|age| age.as_secs())
+                    .unwrap_or_default(),
+                command: failure.command,
+                reason: failure.reason,
+                stderr: failure.stderr,
+            })
+            .collect();
+
+        StatusInfo {
+            pid: std::process::id(),
+            failures,
+        }
+    }
 }
 
 /// Prepare the IPC socket, checking for existing instances.
@@ -1,5 +1,11 @@
+use crate::compositor::WindowInfo;
+use crate::config::ApplicationOverride;
 use crate::desktop::DesktopEntry;
-use std::path::PathBuf;
+use crate::desktop::env::is_valid_variable_name;
+use crate::desktop::exec::{quote_exec_arg, split_exec};
+use crate::ui::icon::resolve_icon_path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use super::traits::{Categorizable, DisplayItem, Executable, IconProvider};
 
@@ -13,6 +19,20 @@ pub struct ApplicationItem {
     pub description: Option<String>,
     pub terminal: bool,
     pub desktop_path: PathBuf,
+    /// MIME types the application can open.
+    pub mime_types: Vec<String>,
+    /// Files to open with the application ("Open With" items).
+    pub files: Vec<PathBuf>,
+    /// Extra search terms matched like the name.
+    pub aliases: Vec<String>,
+    /// Extra environment variables for the launched process.
+    pub env: Vec<(String, String)>,
+    /// Working directory for the launched process.
+    pub working_dir: Option<PathBuf>,
+    /// Window class from `StartupWMClass=`, used to find open windows.
+    pub startup_wm_class: Option<String>,
+    /// Whether to focus an open window instead of launching (None = global setting).
+    pub focus_or_launch: Option<bool>,
 }
 
 impl ApplicationItem {
@@ -33,10 +53,120 @@ impl ApplicationItem {
             description,
             terminal,
             desktop_path,
+            mime_types: Vec::new(),
+            files: Vec::new(),
+            aliases: Vec::new(),
+            env: Vec::new(),
+            working_dir: None,
+            startup_wm_class: None,
+            focus_or_launch: None,
+        }
+    }
+
+    /// Builder method to set the files to open with the application.
+    pub fn with_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files = files;
+        self
+    }
+
+    /// Check whether a window with the given class belongs to this application.
+    ///
+    /// Matches `StartupWMClass`, the desktop ID, or heuristically the last
+    /// segment of a reverse-DNS desktop ID or the name of the executable.
+    pub fn matches_window_class(&self, class: &str) -> bool {
+        if class.is_empty() {
+            return false;
+        }
+        if let Some(wm_class) = &self.startup_wm_class
+            && wm_class.eq_ignore_ascii_case(class)
+        {
+            return true;
+        }
+
+        let id = self.id.strip_suffix(".desktop").unwrap_or(&self.id);
+        if id.eq_ignore_ascii_case(class) {
+            return true;
+        }
+
+        // Heuristics for entries whose ID differs from the window class
+        let id_name = id.rsplit('.').next().unwrap_or(id);
+        if id_name.eq_ignore_ascii_case(class) {
+            return true;
+        }
+        split_exec(&self.exec)
+            .and_then(|args| args.into_iter().next())
+            .is_some_and(|program| {
+                Path::new(&program)
+                    .file_name()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(class))
+            })
+    }
+
+    /// Find an open window of this application to focus.
+    ///
+    /// Expects windows in most recently focused order, and prefers the most
+    /// recent one that is not already focused, falling back to the focused one.
+    pub fn find_window<'a>(&self, windows: &'a [WindowInfo]) -> Option<&'a WindowInfo> {
+        windows
+            .iter()
+            .filter(|window| self.matches_window_class(&window.class))
+            .min_by_key(|window| window.focused)
+    }
+
+    /// Apply a configured override to the application.
+    pub fn apply_override(&mut self, config: &ApplicationOverride) {
+        if let Some(name) = &config.name {
+            self.name = name.clone();
+        }
+        if config.focus_or_launch.is_some() {
+            self.focus_or_launch = config.focus_or_launch;
+        }
+        // Keep the entry's own icon if the override cannot be resolved
+        if let Some(icon_path) = config.icon.as_deref().and_then(resolve_icon_path) {
+            self.icon_path = Some(icon_path);
+        }
+        self.aliases.extend(config.aliases.iter().cloned());
+        self.env.extend(
+            config
+                .env
+                .iter()
+                .filter(|(k, _)| is_valid_variable_name(k))
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
+        for arg in &config.args {
+            self.exec.push(' ');
+            self.exec.push_str(&quote_exec_arg(arg));
         }
     }
 }
 
+/// Apply configured overrides to loaded applications, dropping hidden ones.
+///
+/// Overrides are keyed by desktop ID, with or without the `.desktop` suffix.
+pub fn apply_overrides(
+    apps: Vec<ApplicationItem>,
+    overrides: &BTreeMap<String, ApplicationOverride>,
+) -> Vec<ApplicationItem> {
+    if overrides.is_empty() {
+        return apps;
+    }
+
+    apps.into_iter()
+        .filter_map(|mut app| {
+            let config = overrides
+                .get(&app.id)
+                .or_else(|| overrides.get(&format!("{}.desktop", app.id)));
+            if let Some(config) = config {
+                if config.hidden {
+                    return None;
+                }
+                app.apply_override(config);
+            }
+            Some(app)
+        })
+        .collect()
+}
+
 impl From<DesktopEntry> for ApplicationItem {
     fn from(entry: DesktopEntry) -> Self {
         Self {
@@ -47,6 +177,13 @@ impl From<DesktopEntry> for ApplicationItem {
             description: entry.comment,
             terminal: entry.terminal,
             desktop_path: entry.path,
+            mime_types: entry.mime_types,
+            files: Vec::new(),
+            aliases: Vec::new(),
+            env: entry.env,
+            working_dir: entry.working_dir,
+            startup_wm_class: entry.startup_wm_class,
+            focus_or_launch: None,
         }
     }
 }
@@ -61,10 +198,38 @@ impl From<&DesktopEntry> for ApplicationItem {
             description: entry.comment.clone(),
             terminal: entry.terminal,
             desktop_path: entry.path.clone(),
+            mime_types: entry.mime_types.clone(),
+            files: Vec::new(),
+            aliases: Vec::new(),
+            env: entry.env.clone(),
+            working_dir: entry.working_dir.clone(),
+            startup_wm_class: entry.startup_wm_class.clone(),
+            focus_or_launch: None,
         }
     }
 }
 
+impl From<&ApplicationItem> for DesktopEntry {
+    fn from(app: &ApplicationItem) -> Self {
+        let mut entry = DesktopEntry::new(
+            app.id.clone(),
+            app.name.clone(),
+            app.exec.clone(),
+            None,
+            app.icon_path.clone(),
+            app.description.clone(),
+            vec![],
+            app.terminal,
+            app.desktop_path.clone(),
+            app.mime_types.clone(),
+            app.working_dir.clone(),
+            app.startup_wm_class.clone(),
+        );
+        entry.env = app.env.clone();
+        entry
+    }
+}
+
 impl DisplayItem for ApplicationItem {
     fn id(&self) -> &str {
         &self.id
@@ -112,3 +277,95 @@ impl From<ApplicationItem> for super::ListItem {
         Self::Application(item)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mock_application;
+
+    #[test]
+    fn test_apply_overrides_hides_and_renames() {
+        let apps = vec![mock_application("Firefox"), mock_application("Avahi")];
+        let mut overrides = BTreeMap::new();
+        overrides.insert(
+            "app-avahi.desktop".to_string(),
+            ApplicationOverride {
+                hidden: true,
+                ..Default::default()
+            },
+        );
+        overrides.insert(
+            "app-firefox".to_string(),
+            ApplicationOverride {
+                name: Some("Web".to_string()),
+                aliases: vec!["ff".to_string()],
+                ..Default::default()
+            },
+        );
+
+        let apps = apply_overrides(apps, &overrides);
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].name, "Web");
+        assert_eq!(apps[0].aliases, vec!["ff".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_override_args_and_env() {
+        let mut app = mock_application("Editor");
+        let mut env = BTreeMap::new();
+        env.insert("GDK_BACKEND".to_string(), "wayland".to_string());
+        app.apply_override(&ApplicationOverride {
+            args: vec!["--profile".to_string(), "my work".to_string()],
+            env,
+            ..Default::default()
+        });
+
+        assert_eq!(app.exec, r#"/usr/bin/editor --profile "my work""#);
+        let entry = DesktopEntry::from(&app);
+        assert_eq!(
+            entry.env,
+            vec![("GDK_BACKEND".to_string(), "wayland".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_matches_window_class() {
+        let mut app = mock_application("Dolphin");
+        app.id = "org.kde.dolphin".to_string();
+        assert!(app.matches_window_class("org.kde.dolphin"));
+        assert!(app.matches_window_class("dolphin"));
+        assert!(!app.matches_window_class("firefox"));
+        assert!(!app.matches_window_class(""));
+
+        let mut app = mock_application("Code");
+        app.id = "visual-studio-code".to_string();
+        assert!(app.matches_window_class("code"));
+        app.startup_wm_class = Some("Code-OSS".to_string());
+        assert!(app.matches_window_class("code-oss"));
+    }
+
+    #[test]
+    fn test_find_window_prefers_unfocused() {
+        let window = |address: &str, class: &str, focused: bool| WindowInfo {
+            address: address.to_string(),
+            title: class.to_string(),
+            class: class.to_string(),
+            workspace: 1,
+            focused,
+            urgent: false,
+            last_focused: None,
+        };
+        let app = mock_application("Kitty");
+        let windows = vec![
+            window("1", "kitty", true),
+            window("2", "firefox", false),
+            window("3", "kitty", false),
+            window("4", "kitty", false),
+        ];
+
+        // The most recent window that is not focused
+        assert_eq!(app.find_window(&windows).unwrap().address, "3");
+        assert_eq!(app.find_window(&windows[..2]).unwrap().address, "1");
+        assert!(app.find_window(&windows[1..2]).is_none());
+    }
+}
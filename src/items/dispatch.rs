@@ -15,6 +15,7 @@ macro_rules! dispatch_item {
         match $self {
             Self::Application(item) => item.$method($($arg),*),
             Self::Window(item) => item.$method($($arg),*),
+            Self::Workspace(item) => item.$method($($arg),*),
             Self::Action(item) => item.$method($($arg),*),
             Self::Submenu(item) => item.$method($($arg),*),
             Self::Calculator(item) => item.$method($($arg),*),
@@ -9,6 +9,7 @@
 //!
 //! - [`ApplicationItem`] - Desktop applications (from .desktop files)
 //! - [`WindowItem`] - Open windows for window switching
+//! - [`WorkspaceItem`] - Workspaces for workspace switching
 //! - [`ActionItem`] - System actions (shutdown, reboot, logout)
 //! - [`CalculatorItem`] - Mathematical calculation results
 //! - [`SearchItem`] - Web search queries
@@ -50,18 +51,20 @@ mod submenu;
 mod theme;
 mod traits;
 mod window;
+mod workspace;
 
 use dispatch::dispatch_item;
 
 pub use action::{ActionItem, ActionKind};
 pub use ai::AiItem;
-pub use application::ApplicationItem;
+pub use application::{ApplicationItem, apply_overrides};
 pub use calculator::CalculatorItem;
 pub use search::SearchItem;
 pub use submenu::{SubmenuItem, SubmenuLayout};
 pub use theme::{ThemeItem, ThemeSource};
 pub use traits::{Categorizable, DisplayItem, Executable, IconProvider, Previewable};
 pub use window::WindowItem;
+pub use workspace::WorkspaceItem;
 
 use crate::config::ConfigModule;
 use std::path::PathBuf;
@@ -74,6 +77,8 @@ pub enum ListItem {
     Application(ApplicationItem),
     /// An open window (for window switching)
     Window(WindowItem),
+    /// A workspace (for workspace switching)
+    Workspace(WorkspaceItem),
     /// A functional action (shutdown, reboot, etc.)
     Action(ActionItem),
     /// A submenu that opens a nested view
@@ -104,6 +109,14 @@ impl ListItem {
         dispatch_item!(self, description)
     }
 
+    /// Get the extra search terms for this item (configured application aliases).
+    pub fn aliases(&self) -> &[String] {
+        match self {
+            Self::Application(app) => &app.aliases,
+            _ => &[],
+        }
+    }
+
     /// Get the icon path for this item.
     pub fn icon_path(&self) -> Option<&PathBuf> {
         dispatch_item!(self, icon_path)
@@ -129,6 +142,11 @@ impl ListItem {
         matches!(self, Self::Window(_))
     }
 
+    /// Check if this item is a workspace.
+    pub fn is_workspace(&self) -> bool {
+        matches!(self, Self::Workspace(_))
+    }
+
     /// Check if this item is an action.
     pub fn is_action(&self) -> bool {
         matches!(self, Self::Action(_))
@@ -155,12 +173,31 @@ impl ListItem {
         dispatch_item!(self, section_name)
     }
 
+    /// Get the key used to track launches of this item in the usage store.
+    ///
+    /// Windows are tracked by application class so that frecency carries
+    /// over between window instances. Returns None for items that are not
+    /// tracked (calculator results, submenus, etc.).
+    pub fn usage_key(&self) -> Option<String> {
+        match self {
+            Self::Application(app) => Some(format!("app:{}", app.id)),
+            Self::Window(win) => Some(format!("window:{}", win.app_id)),
+            Self::Action(act) => Some(format!("action:{}", act.id)),
+            Self::Search(search) => Some(search.usage_key()),
+            Self::Workspace(_)
+            | Self::Submenu(_)
+            | Self::Calculator(_)
+            | Self::Ai(_)
+            | Self::Theme(_) => None,
+        }
+    }
+
     /// Get the ConfigModule this item belongs to.
     /// This method has custom logic per variant and cannot use dispatch_item!.
     pub fn config_module(&self) -> ConfigModule {
         match self {
             Self::Application(_) => ConfigModule::Applications,
-            Self::Window(_) => ConfigModule::Windows,
+            Self::Window(_) | Self::Workspace(_) => ConfigModule::Windows,
             Self::Action(_) => ConfigModule::Actions,
             Self::Submenu(item) => {
                 // Map submenu IDs to their modules
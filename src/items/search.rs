@@ -38,6 +38,11 @@ impl SearchItem {
     pub fn icon(&self) -> PhosphorIcon {
         self.provider.icon
     }
+
+    /// Get the key used to track uses of this item's provider in the usage store.
+    pub fn usage_key(&self) -> String {
+        format!("search:{}", self.provider.name)
+    }
 }
 
 impl DisplayItem for SearchItem {
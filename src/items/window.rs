@@ -19,10 +19,12 @@ pub struct WindowItem {
     pub description: String,
     /// Resolved icon path
     pub icon_path: Option<PathBuf>,
-    /// Workspace number
+    /// Workspace ID
     pub workspace: i32,
     /// Whether this window is currently focused
     pub focused: bool,
+    /// Whether this window demands attention
+    pub urgent: bool,
 }
 
 impl WindowItem {
@@ -51,13 +53,17 @@ impl WindowItem {
             icon_path,
             workspace,
             focused,
+            urgent: false,
         }
     }
 
     /// Create a WindowItem from compositor WindowInfo.
     pub fn from_window_info(info: WindowInfo, icon_path: Option<PathBuf>) -> Self {
         let app_name = titlecase_app_name(&info.class);
-        let description = format!("{} - Workspace {}", app_name, info.workspace);
+        let mut description = format!("{} - Workspace {}", app_name, info.workspace);
+        if info.urgent {
+            description.push_str(" - Urgent");
+        }
         Self {
             id: format!("window-{}", info.address),
             address: info.address,
@@ -68,6 +74,15 @@ impl WindowItem {
             icon_path,
             workspace: info.workspace,
             focused: info.focused,
+            urgent: info.urgent,
+        }
+    }
+
+    /// Describe the workspace by its title (e.g., "Workspace 2") instead of its ID.
+    pub fn set_workspace_title(&mut self, title: &str) {
+        self.description = format!("{} - {}", self.app_name, title);
+        if self.urgent {
+            self.description.push_str(" - Urgent");
         }
     }
 }
@@ -0,0 +1,146 @@
+use super::traits::{Categorizable, DisplayItem, Executable, IconProvider};
+use crate::compositor::WorkspaceInfo;
+
+/// A workspace item for switching workspaces.
+#[derive(Clone, Debug)]
+pub struct WorkspaceItem {
+    /// Internal ID for the list
+    pub id: String,
+    /// Compositor-specific workspace address (used for switching)
+    pub address: String,
+    /// Workspace ID, as in [`WindowInfo::workspace`](crate::compositor::WindowInfo)
+    pub workspace: i32,
+    /// Workspace title (e.g., "Workspace 3")
+    pub title: String,
+    /// Display name (e.g., "Workspace 3 — firefox, kitty")
+    pub name: String,
+    /// Pre-computed description (e.g., "DP-1 - 2 windows")
+    pub description: String,
+    /// Whether this is a special (scratchpad) workspace
+    pub special: bool,
+    /// Whether this workspace is currently focused
+    pub focused: bool,
+}
+
+impl WorkspaceItem {
+    /// Create a WorkspaceItem from compositor WorkspaceInfo.
+    ///
+    /// `apps` are the classes of the windows on the workspace.
+    pub fn from_workspace_info(info: WorkspaceInfo, apps: &[&str]) -> Self {
+        let title = if info.special {
+            format!("Special workspace {}", info.name)
+        } else {
+            format!("Workspace {}", info.name)
+        };
+        let name = if apps.is_empty() {
+            title.clone()
+        } else {
+            format!("{} — {}", title, apps.join(", "))
+        };
+
+        let windows = match info.windows {
+            0 => "Empty".to_string(),
+            1 => "1 window".to_string(),
+            n => format!("{} windows", n),
+        };
+        let description = match &info.output {
+            Some(output) => format!("{} - {}", output, windows),
+            None => windows,
+        };
+
+        Self {
+            id: format!("workspace-{}", info.address),
+            address: info.address,
+            workspace: info.id,
+            title,
+            name,
+            description,
+            special: info.special,
+            focused: info.focused,
+        }
+    }
+}
+
+impl DisplayItem for WorkspaceItem {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+
+    fn action_label(&self) -> &'static str {
+        "Switch"
+    }
+}
+
+impl IconProvider for WorkspaceItem {
+    fn icon_name(&self) -> Option<&str> {
+        Some("squares-four")
+    }
+}
+
+impl Executable for WorkspaceItem {
+    fn execute(&self) -> anyhow::Result<()> {
+        // Switching needs the compositor, handled in the UI layer like windows
+        Ok(())
+    }
+}
+
+impl Categorizable for WorkspaceItem {
+    fn section_name(&self) -> &'static str {
+        "Windows"
+    }
+
+    fn sort_priority(&self) -> u8 {
+        2
+    }
+}
+
+impl From<WorkspaceItem> for super::ListItem {
+    fn from(item: WorkspaceItem) -> Self {
+        Self::Workspace(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, output: Option<&str>, windows: usize, special: bool) -> WorkspaceInfo {
+        WorkspaceInfo {
+            address: name.to_string(),
+            id: 1,
+            name: name.to_string(),
+            output: output.map(String::from),
+            windows,
+            special,
+            focused: false,
+        }
+    }
+
+    #[test]
+    fn test_from_workspace_info() {
+        let item = WorkspaceItem::from_workspace_info(
+            info("3", Some("DP-1"), 2, false),
+            &["firefox", "kitty"],
+        );
+        assert_eq!(item.id, "workspace-3");
+        assert_eq!(item.title, "Workspace 3");
+        assert_eq!(item.name, "Workspace 3 — firefox, kitty");
+        assert_eq!(item.description, "DP-1 - 2 windows");
+    }
+
+    #[test]
+    fn test_from_empty_special_workspace() {
+        let item = WorkspaceItem::from_workspace_info(info("scratchpad", None, 0, true), &[]);
+        assert_eq!(item.name, "Special workspace scratchpad");
+        assert_eq!(item.description, "Empty");
+        assert!(item.special);
+    }
+}
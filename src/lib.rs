@@ -16,6 +16,7 @@ pub mod process;
 pub mod search;
 pub mod tokio_runtime;
 pub mod ui;
+pub mod usage;
 
 #[cfg(test)]
 pub mod test_utils;
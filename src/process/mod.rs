@@ -3,17 +3,27 @@
 //! This module provides safe abstractions for spawning processes that outlive
 //! the launcher daemon. All spawned processes are detached using `setsid()`
 //! to create a new session, preventing them from being killed when the daemon exits.
+//! Unless a working directory is given, processes start in the user's home directory.
+
+mod monitor;
+mod terminal;
 
 use crate::desktop::env::get_session_environment;
 use crate::error::ProcessError;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use tracing::warn;
+
+pub use monitor::{FAILURE_WINDOW, LaunchFailure, recent_failures, report_failure};
+pub use terminal::{COMMAND_PLACEHOLDER, Terminal, find_program, resolve_terminal};
 
 /// Builder for creating detached processes.
 ///
 /// A detached process runs in its own session (via `setsid()`) and survives
-/// when the parent (launcher daemon) exits. All stdio is redirected to null.
+/// when the parent (launcher daemon) exits. Stdin and stdout are redirected
+/// to null, stderr is read by the launch monitor to report early failures.
 ///
 /// # Example
 /// ```ignore
@@ -27,6 +37,8 @@ pub struct DetachedProcess {
     command: Command,
     use_session_env: bool,
     shell_command: Option<String>,
+    env: Vec<(OsString, OsString)>,
+    current_dir: Option<PathBuf>,
 }
 
 impl DetachedProcess {
@@ -36,6 +48,8 @@ impl DetachedProcess {
             command: Command::new(program),
             use_session_env: false,
             shell_command: None,
+            env: Vec::new(),
+            current_dir: None,
         }
     }
 
@@ -48,6 +62,8 @@ impl DetachedProcess {
             command: Command::new("sh"),
             use_session_env: false,
             shell_command: Some(cmd),
+            env: Vec::new(),
+            current_dir: None,
         }
     }
 
@@ -77,11 +93,38 @@ impl DetachedProcess {
         self
     }
 
+    /// Set additional environment variables for the process.
+    ///
+    /// These are applied on top of the inherited or session environment.
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.env.extend(
+            vars.into_iter()
+                .map(|(k, v)| (k.as_ref().to_owned(), v.as_ref().to_owned())),
+        );
+        self
+    }
+
+    /// Set the working directory of the process.
+    ///
+    /// Falls back to the home directory if unset or not an existing directory.
+    pub fn current_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.current_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
     /// Spawn the detached process.
     ///
     /// The spawned process:
     /// - Runs in a new session (calls `setsid()`)
-    /// - Has stdin/stdout/stderr redirected to /dev/null
+    /// - Has stdin/stdout redirected to /dev/null
+    /// - Has stderr piped to the launch monitor, which keeps it only briefly
+    /// - Is watched for an unsuccessful exit shortly after launch
+    /// - Starts in the given working directory, or the home directory
     /// - Survives when the parent process exits
     ///
     /// # Safety
@@ -98,12 +141,26 @@ impl DetachedProcess {
             self.command.env_clear();
             self.command.envs(get_session_environment().iter());
         }
+        self.command.envs(self.env.drain(..));
+
+        // Start in the requested directory, or home instead of the daemon's cwd
+        let dir = match self.current_dir.take() {
+            Some(dir) if dir.is_dir() => Some(dir),
+            Some(dir) => {
+                warn!("Working directory {:?} does not exist, using home", dir);
+                home_dir()
+            }
+            None => home_dir(),
+        };
+        if let Some(dir) = dir {
+            self.command.current_dir(dir);
+        }
 
-        // Redirect stdio to null
+        // Redirect stdin/stdout to null, keep stderr for failure reports
         self.command
             .stdin(Stdio::null())
             .stdout(Stdio::null())
-            .stderr(Stdio::null());
+            .stderr(Stdio::piped());
 
         // SAFETY: setsid() is async-signal-safe and creates a new session,
         // detaching the child from the parent's process group so it survives
@@ -115,10 +172,24 @@ impl DetachedProcess {
             });
         }
 
-        self.command.spawn().map_err(ProcessError::SpawnFailed)?;
+        let command = self.describe();
+        let child = self.command.spawn().map_err(ProcessError::SpawnFailed)?;
+        monitor::watch(child, command);
 
         Ok(())
     }
+
+    /// Get the command line for failure reports.
+    fn describe(&self) -> String {
+        if let Some(cmd) = &self.shell_command {
+            return cmd.clone();
+        }
+        std::iter::once(self.command.get_program())
+            .chain(self.command.get_args())
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 /// Launch an application with the given executable string.
@@ -140,19 +211,29 @@ pub fn launch_exec(exec: &str) -> Result<(), ProcessError> {
         .spawn()
 }
 
-/// Launch an application in a terminal emulator.
+/// Launch an application from an already split argument vector.
 ///
-/// Uses the `$TERMINAL` environment variable, falling back to `xterm`.
-pub fn launch_in_terminal(exec: &str) -> Result<(), ProcessError> {
-    let terminal = get_terminal()?;
+/// Unlike [`launch_exec`], arguments may contain whitespace.
+pub fn launch_args(args: &[String]) -> Result<(), ProcessError> {
+    let (program, args) = args.split_first().ok_or(ProcessError::EmptyCommand)?;
 
-    DetachedProcess::new(&terminal)
-        .arg("-e")
-        .arg(exec)
+    DetachedProcess::new(program)
+        .args(args)
         .with_session_env()
         .spawn()
 }
 
+/// Launch an application in a terminal emulator.
+///
+/// The terminal and its argument convention are chosen by [`resolve_terminal`].
+pub fn launch_in_terminal(args: &[String]) -> Result<(), ProcessError> {
+    if args.is_empty() {
+        return Err(ProcessError::EmptyCommand);
+    }
+
+    launch_args(&resolve_terminal()?.command(args))
+}
+
 /// Open a URL using the system default handler (`xdg-open`).
 pub fn open_url(url: &str) -> Result<(), ProcessError> {
     DetachedProcess::new("xdg-open").arg(url).spawn()
@@ -163,22 +244,13 @@ pub fn run_shell_command(command: &str) -> Result<(), ProcessError> {
     DetachedProcess::shell(command).spawn()
 }
 
-/// Get the terminal emulator to use.
-fn get_terminal() -> Result<String, ProcessError> {
-    if let Ok(terminal) = std::env::var("TERMINAL") {
-        return Ok(terminal);
-    }
-
-    // Check for xterm
-    if std::process::Command::new("which")
-        .arg("xterm")
-        .output()
-        .is_ok_and(|o| o.status.success())
-    {
-        return Ok("xterm".to_string());
-    }
-
-    Err(ProcessError::NoTerminal)
+/// Get the user's home directory from the session environment.
+fn home_dir() -> Option<PathBuf> {
+    get_session_environment()
+        .get("HOME")
+        .map(PathBuf::from)
+        .or_else(dirs::home_dir)
+        .filter(|dir| dir.is_dir())
 }
 
 #[cfg(test)]
@@ -196,4 +268,10 @@ mod tests {
         let result = launch_exec("   ");
         assert!(matches!(result, Err(ProcessError::EmptyCommand)));
     }
+
+    #[test]
+    fn test_launch_args_empty() {
+        let result = launch_args(&[]);
+        assert!(matches!(result, Err(ProcessError::EmptyCommand)));
+    }
 }
@@ -0,0 +1,447 @@
+//! Monitoring of launched processes.
+//!
+//! A single reaper thread waits for all launched processes and reads their
+//! stderr pipes. Output is kept in a small in-memory buffer only during
+//! [`FAILURE_WINDOW`], and read and discarded afterwards until the pipe is
+//! closed, so neither the process nor children it leaves behind ever block
+//! on a full pipe or fill anything while the daemon runs. A process that exits
+//! unsuccessfully within the window is recorded as a [`LaunchFailure`]
+//! together with its last stderr lines, and the user is notified with a
+//! desktop notification.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, PipeReader, PipeWriter, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Child, ChildStderr, ExitStatus};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, warn};
+use zbus::blocking::Connection;
+use zbus::zvariant::Value;
+
+/// Exits within this time after launch are reported as failures.
+pub const FAILURE_WINDOW: Duration = Duration::from_secs(3);
+
+/// Number of stderr lines kept per process.
+const STDERR_LINES: usize = 20;
+
+/// Maximum length of a kept stderr line.
+const MAX_LINE_LENGTH: usize = 500;
+
+/// Maximum number of failures kept for `zlaunch status`.
+const MAX_FAILURES: usize = 10;
+
+/// Number of stderr lines shown in a notification.
+const NOTIFICATION_LINES: usize = 3;
+
+/// Unterminated stderr output beyond this size is kept as a line.
+const MAX_PARTIAL_LINE: usize = 4 * MAX_LINE_LENGTH;
+
+/// How often the reaper checks processes within the failure window.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the reaper checks processes past the failure window.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static FAILURES: Mutex<VecDeque<LaunchFailure>> = Mutex::new(VecDeque::new());
+
+/// The reaper thread, started with the first launch.
+static REAPER: Mutex<Option<Reaper>> = Mutex::new(None);
+
+/// Handle to the reaper thread.
+struct Reaper {
+    tx: Sender<Watched>,
+    /// Wakes the reaper up to pick up a new process
+    wake: PipeWriter,
+}
+
+/// A launched process waited for by the reaper.
+struct Watched {
+    /// The process, until it has exited
+    child: Option<Child>,
+    command: String,
+    started: Instant,
+    /// Stderr pipe, read until closed, also by children the process left behind
+    stderr: Option<ChildStderr>,
+    /// Stderr lines written within the failure window
+    lines: StderrLines,
+}
+
+/// Stderr lines collected from raw output.
+#[derive(Debug, Default)]
+struct StderrLines {
+    lines: VecDeque<String>,
+    /// Start of a line not terminated yet
+    partial: Vec<u8>,
+}
+
+/// A launch that failed to start or exited right away.
+#[derive(Debug, Clone)]
+pub struct LaunchFailure {
+    /// The launched command line
+    pub command: String,
+    /// Why the launch failed (spawn error or exit status)
+    pub reason: String,
+    /// Last lines the process wrote to stderr
+    pub stderr: Vec<String>,
+    /// When the failure was recorded
+    pub time: SystemTime,
+}
+
+impl LaunchFailure {
+    pub fn new(command: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            reason: reason.into(),
+            stderr: Vec::new(),
+            time: SystemTime::now(),
+        }
+    }
+
+    /// Builder method to set the captured stderr lines.
+    pub fn with_stderr(mut self, stderr: Vec<String>) -> Self {
+        self.stderr = stderr;
+        self
+    }
+}
+
+/// Record a launch failure and notify the user.
+pub fn report_failure(failure: LaunchFailure) {
+    warn!(
+        command = %failure.command,
+        reason = %failure.reason,
+        "Launch failed"
+    );
+
+    {
+        let mut failures = FAILURES.lock().unwrap();
+        if failures.len() == MAX_FAILURES {
+            failures.pop_front();
+        }
+        failures.push_back(failure.clone());
+    }
+
+    // Don't block the caller (possibly the UI thread) on D-Bus
+    thread::spawn(move || {
+        if let Err(e) = send_notification(&failure) {
+            debug!(%e, "Failed to send launch failure notification");
+        }
+    });
+}
+
+/// Get the recorded launch failures, oldest first.
+pub fn recent_failures() -> Vec<LaunchFailure> {
+    FAILURES.lock().unwrap().iter().cloned().collect()
+}
+
+/// Watch a spawned process for an early unsuccessful exit.
+///
+/// The process's stderr is taken from `child` if it was piped.
+pub(crate) fn watch(mut child: Child, command: String) {
+    // Never let a process that stops writing block the reaper
+    let stderr = child
+        .stderr
+        .take()
+        .filter(|stderr| match set_nonblocking(stderr) {
+            Ok(()) => true,
+            Err(e) => {
+                debug!(%e, "Failed to make stderr pipe non-blocking, not reading it");
+                false
+            }
+        });
+    let watched = Watched {
+        child: Some(child),
+        command,
+        started: Instant::now(),
+        stderr,
+        lines: StderrLines::default(),
+    };
+
+    let mut reaper = REAPER.lock().unwrap();
+    let watched = match reaper.as_mut() {
+        Some(running) => match running.tx.send(watched) {
+            Ok(()) => {
+                let _ = running.wake.write(&[0]);
+                return;
+            }
+            Err(mpsc::SendError(watched)) => watched,
+        },
+        None => watched,
+    };
+
+    // Start the reaper with the first launch, or restart it if it is gone
+    let result = io::pipe().and_then(|(wake_rx, wake)| {
+        let (tx, rx) = mpsc::channel();
+        let _ = tx.send(watched);
+        thread::Builder::new()
+            .name("launch-reaper".to_string())
+            .spawn(move || reap(rx, wake_rx))?;
+        *reaper = Some(Reaper { tx, wake });
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!(%e, "Failed to start launch monitor");
+    }
+}
+
+/// Wait for launched processes and read their stderr, reporting the
+/// processes that fail right away.
+fn reap(rx: Receiver<Watched>, mut wake: PipeReader) {
+    let mut watched: Vec<Watched> = Vec::new();
+
+    loop {
+        loop {
+            match rx.try_recv() {
+                Ok(new) => watched.push(new),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) if watched.is_empty() => return,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+
+        for w in &mut watched {
+            w.read_stderr();
+            w.check_exit();
+        }
+        watched.retain(|w| w.child.is_some() || w.stderr.is_some());
+
+        // Wait for output, a new process or the next check. Poll faster
+        // while a launch can still fail, and block while there is nothing
+        // to wait for.
+        let timeout = if watched.iter().any(Watched::in_failure_window) {
+            Some(POLL_INTERVAL)
+        } else if watched.iter().any(|w| w.child.is_some()) {
+            Some(IDLE_POLL_INTERVAL)
+        } else {
+            None
+        };
+        let mut fds: Vec<libc::pollfd> = std::iter::once(wake.as_raw_fd())
+            .chain(
+                watched
+                    .iter()
+                    .filter_map(|w| Some(w.stderr.as_ref()?.as_raw_fd())),
+            )
+            .map(|fd| libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            })
+            .collect();
+        let timeout = timeout.map_or(-1, |t| t.as_millis() as libc::c_int);
+        // SAFETY: fds is a valid array of pollfd entries that outlives the call
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) };
+        if ready > 0 && fds[0].revents != 0 {
+            let _ = wake.read(&mut [0; 64]);
+        }
+    }
+}
+
+impl Watched {
+    fn in_failure_window(&self) -> bool {
+        self.child.is_some() && self.started.elapsed() <= FAILURE_WINDOW
+    }
+
+    /// Read the available stderr output, keeping it only within the failure
+    /// window, and close the pipe at its end.
+    fn read_stderr(&mut self) {
+        let Some(stderr) = &mut self.stderr else {
+            return;
+        };
+        let mut buf = [0u8; 8192];
+        loop {
+            match stderr.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if self.started.elapsed() <= FAILURE_WINDOW {
+                        self.lines.feed(&buf[..n]);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    debug!(%e, command = %self.command, "Failed to read stderr of launched process");
+                    break;
+                }
+            }
+        }
+        self.stderr = None;
+    }
+
+    /// Reap the process if it has exited, reporting an early failure.
+    fn check_exit(&mut self) {
+        let status = match self.child.as_mut().map(Child::try_wait) {
+            None | Some(Ok(None)) => return,
+            Some(Ok(Some(status))) => status,
+            Some(Err(e)) => {
+                debug!(%e, command = %self.command, "Failed to wait for launched process");
+                self.child = None;
+                return;
+            }
+        };
+        self.child = None;
+
+        if !status.success() && self.started.elapsed() <= FAILURE_WINDOW {
+            // Collect output written right before exiting
+            self.read_stderr();
+            let stderr = std::mem::take(&mut self.lines).finish();
+            let failure = LaunchFailure::new(self.command.clone(), describe_status(status));
+            report_failure(failure.with_stderr(stderr));
+        }
+        self.lines = StderrLines::default();
+    }
+}
+
+impl StderrLines {
+    /// Add raw output, splitting it into lines.
+    fn feed(&mut self, data: &[u8]) {
+        self.partial.extend_from_slice(data);
+        while let Some(end) = self.partial.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=end).collect();
+            self.push(&line[..end]);
+        }
+        if self.partial.len() > MAX_PARTIAL_LINE {
+            let line = std::mem::take(&mut self.partial);
+            self.push(&line);
+        }
+    }
+
+    fn push(&mut self, line: &[u8]) {
+        let line = String::from_utf8_lossy(line);
+        push_line(&mut self.lines, line.trim_end_matches('\r').to_string());
+    }
+
+    /// Get the last lines, including an unterminated last line.
+    fn finish(mut self) -> Vec<String> {
+        if !self.partial.is_empty() {
+            let line = std::mem::take(&mut self.partial);
+            self.push(&line);
+        }
+        self.lines.into()
+    }
+}
+
+/// Make a pipe non-blocking.
+fn set_nonblocking(pipe: &impl AsRawFd) -> io::Result<()> {
+    let fd = pipe.as_raw_fd();
+    // SAFETY: fcntl on a valid, owned file descriptor
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Append a line to the ring buffer, dropping the oldest line when full.
+fn push_line(lines: &mut VecDeque<String>, mut line: String) {
+    if line.len() > MAX_LINE_LENGTH {
+        let mut end = MAX_LINE_LENGTH;
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        line.truncate(end);
+        line.push('…');
+    }
+    if lines.len() == STDERR_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+/// Describe an unsuccessful exit status.
+fn describe_status(status: ExitStatus) -> String {
+    match (status.code(), status.signal()) {
+        (Some(127), _) => "Command not found (exit status 127)".to_string(),
+        (Some(126), _) => "Command not executable (exit status 126)".to_string(),
+        (Some(code), _) => format!("Exited with status {}", code),
+        (None, Some(signal)) => format!("Killed by signal {}", signal),
+        (None, None) => "Exited abnormally".to_string(),
+    }
+}
+
+/// Show a desktop notification for a failure.
+fn send_notification(failure: &LaunchFailure) -> zbus::Result<()> {
+    let connection = Connection::session()?;
+
+    let mut body = format!("{}\n{}", failure.command, failure.reason);
+    let skip = failure.stderr.len().saturating_sub(NOTIFICATION_LINES);
+    for line in &failure.stderr[skip..] {
+        body.push('\n');
+        body.push_str(line);
+    }
+
+    let actions: &[&str] = &[];
+    let hints: HashMap<&str, Value> = HashMap::new();
+    connection.call_method(
+        Some("org.freedesktop.Notifications"),
+        "/org/freedesktop/Notifications",
+        Some("org.freedesktop.Notifications"),
+        "Notify",
+        &(
+            "zlaunch",
+            0u32,
+            "dialog-error",
+            "Launch failed",
+            body,
+            actions,
+            hints,
+            -1i32,
+        ),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn test_push_line_keeps_last_lines() {
+        let mut lines = VecDeque::new();
+        for i in 0..STDERR_LINES + 5 {
+            push_line(&mut lines, i.to_string());
+        }
+        assert_eq!(lines.len(), STDERR_LINES);
+        assert_eq!(lines.front().unwrap(), "5");
+
+        push_line(&mut lines, "é".repeat(MAX_LINE_LENGTH));
+        assert!(lines.back().unwrap().ends_with('…'));
+    }
+
+    #[test]
+    fn test_stderr_lines() {
+        let mut lines = StderrLines::default();
+        lines.feed(b"first\r\n\xff inv");
+        lines.feed(b"alid\nunterminated");
+        assert_eq!(
+            lines.finish(),
+            ["first", "\u{fffd} invalid", "unterminated"]
+        );
+
+        // Output without newlines is cut into lines
+        let mut lines = StderrLines::default();
+        lines.feed(&[b'x'; MAX_PARTIAL_LINE + 1]);
+        assert!(lines.partial.is_empty());
+        assert_eq!(lines.finish().len(), 1);
+    }
+
+    #[test]
+    fn test_describe_status() {
+        let status = |cmd: &str| {
+            Command::new("sh")
+                .args(["-c", cmd])
+                .stderr(Stdio::null())
+                .status()
+                .unwrap()
+        };
+        assert_eq!(
+            describe_status(status("no-such-command-zlaunch")),
+            "Command not found (exit status 127)"
+        );
+        assert_eq!(describe_status(status("exit 3")), "Exited with status 3");
+        assert_eq!(describe_status(status("kill -9 $$")), "Killed by signal 9");
+    }
+}
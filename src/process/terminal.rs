@@ -0,0 +1,413 @@
+//! Terminal emulator detection.
+//!
+//! The terminal used for `Terminal=true` applications is resolved in order:
+//! 1. The `[terminal]` config section
+//! 2. `$TERMINAL`
+//! 3. `xdg-terminal-exec`, if installed
+//! 4. The first installed entry of `xdg-terminals.list`
+//! 5. The first common terminal found in `$PATH`
+
+use crate::config;
+use crate::desktop::env::get_session_environment;
+use crate::desktop::exec::split_exec;
+use crate::desktop::scanner::{get_xdg_application_dirs, get_xdg_data_dirs};
+use crate::error::ProcessError;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Placeholder in terminal argument templates replaced by the command to run.
+pub const COMMAND_PLACEHOLDER: &str = "{cmd}";
+
+/// Common terminals and the arguments each expects before the command.
+const KNOWN_TERMINALS: &[(&str, &[&str])] = &[
+    ("foot", &[]),
+    ("kitty", &[]),
+    ("alacritty", &["-e"]),
+    ("wezterm", &["start", "--"]),
+    ("ghostty", &["-e"]),
+    ("kgx", &["--"]),
+    ("gnome-console", &["--"]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("urxvt", &["-e"]),
+    ("st", &["-e"]),
+    ("xterm", &["-e"]),
+];
+
+/// A terminal emulator and the arguments placed before the command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Terminal {
+    /// Terminal program.
+    pub program: String,
+    /// Argument template, see [`COMMAND_PLACEHOLDER`].
+    pub args: Vec<String>,
+}
+
+impl Terminal {
+    /// Create a terminal with an explicit argument template.
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+        }
+    }
+
+    /// Create a terminal using the known convention for its program name.
+    ///
+    /// Unknown terminals are assumed to accept `-e`.
+    pub fn with_known_args(program: impl Into<String>) -> Self {
+        let program = program.into();
+        let name = Path::new(&program)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let args = KNOWN_TERMINALS
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, args)| args.iter().map(|arg| arg.to_string()).collect())
+            .unwrap_or_else(|| vec!["-e".to_string()]);
+
+        Self { program, args }
+    }
+
+    /// Create a terminal from a command line such as `$TERMINAL`.
+    ///
+    /// The line is split like an exec string. Its arguments are followed by
+    /// the known convention for the program, unless they already end with it.
+    pub fn from_command_line(line: &str) -> Option<Self> {
+        let mut args = split_exec(line)?;
+        if args.is_empty() {
+            return None;
+        }
+        let known = Self::with_known_args(args.remove(0));
+        if !args.ends_with(&known.args) {
+            args.extend(known.args);
+        }
+        Some(Self {
+            program: known.program,
+            args,
+        })
+    }
+
+    /// Build the full argument vector running `cmd` in this terminal.
+    ///
+    /// Each [`COMMAND_PLACEHOLDER`] is replaced by the command's arguments.
+    /// Without a placeholder the command is appended.
+    pub fn command(&self, cmd: &[String]) -> Vec<String> {
+        let mut argv = vec![self.program.clone()];
+        let mut substituted = false;
+
+        for arg in &self.args {
+            if arg == COMMAND_PLACEHOLDER {
+                argv.extend(cmd.iter().cloned());
+                substituted = true;
+            } else {
+                argv.push(arg.clone());
+            }
+        }
+
+        if !substituted {
+            argv.extend(cmd.iter().cloned());
+        }
+
+        argv
+    }
+}
+
+/// Resolve the terminal emulator to use.
+pub fn resolve_terminal() -> Result<Terminal, ProcessError> {
+    let terminal_config = config::config().terminal;
+    if let Some(command) = terminal_config.command.filter(|c| !c.trim().is_empty()) {
+        return Ok(match terminal_config.args {
+            Some(args) => Terminal::new(command, args),
+            None => Terminal::with_known_args(command),
+        });
+    }
+
+    if let Some(terminal) = get_session_environment()
+        .get("TERMINAL")
+        .and_then(|line| Terminal::from_command_line(line))
+    {
+        return Ok(terminal);
+    }
+
+    // xdg-terminal-exec takes the command as plain arguments
+    if find_program("xdg-terminal-exec").is_some() {
+        return Ok(Terminal::new("xdg-terminal-exec", vec![]));
+    }
+
+    if let Some(terminal) = preferred_terminal() {
+        return Ok(terminal);
+    }
+
+    KNOWN_TERMINALS
+        .iter()
+        .find(|(name, _)| find_program(name).is_some())
+        .map(|(name, _)| Terminal::with_known_args(*name))
+        .ok_or(ProcessError::NoTerminal)
+}
+
+/// Find an executable in the session's `$PATH`.
+///
+/// Names containing a slash are checked directly.
+pub fn find_program(name: &str) -> Option<PathBuf> {
+    if name.contains('/') {
+        let path = PathBuf::from(name);
+        return is_executable(&path).then_some(path);
+    }
+
+    let path = get_session_environment()
+        .get("PATH")
+        .cloned()
+        .or_else(|| std::env::var("PATH").ok())?;
+    find_in_dirs(name, std::env::split_paths(&path))
+}
+
+/// Find an executable named `name` in the given directories.
+fn find_in_dirs(name: &str, dirs: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    dirs.into_iter()
+        .map(|dir| dir.join(name))
+        .find(|path| is_executable(path))
+}
+
+/// Check whether a path is an executable file.
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Get the first installed terminal listed in `xdg-terminals.list`.
+fn preferred_terminal() -> Option<Terminal> {
+    let app_dirs = get_xdg_application_dirs();
+
+    terminal_list_paths()
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|content| parse_terminal_list(&content))
+        .find_map(|id| {
+            let content = app_dirs
+                .iter()
+                .find_map(|dir| std::fs::read_to_string(dir.join(&id)).ok())?;
+            parse_terminal_entry(&content).filter(|t| find_program(&t.program).is_some())
+        })
+}
+
+/// Get the `xdg-terminals.list` locations, most important first.
+fn terminal_list_paths() -> Vec<PathBuf> {
+    let desktops: Vec<String> = get_session_environment()
+        .get("XDG_CURRENT_DESKTOP")
+        .map(|d| d.split(':').map(|s| s.to_lowercase()).collect())
+        .unwrap_or_default();
+
+    let mut dirs = Vec::new();
+    if let Some(config_home) = dirs::config_dir() {
+        dirs.push(config_home);
+    }
+    match std::env::var("XDG_CONFIG_DIRS") {
+        Ok(config_dirs) => dirs.extend(config_dirs.split(':').map(PathBuf::from)),
+        Err(_) => dirs.push(PathBuf::from("/etc/xdg")),
+    }
+    dirs.extend(
+        get_xdg_data_dirs()
+            .into_iter()
+            .map(|dir| dir.join("xdg-terminal-exec")),
+    );
+
+    let mut paths = Vec::new();
+    for dir in dirs {
+        for desktop in &desktops {
+            paths.push(dir.join(format!("{}-xdg-terminals.list", desktop)));
+        }
+        paths.push(dir.join("xdg-terminals.list"));
+    }
+    paths
+}
+
+/// Parse the desktop entry IDs listed in an `xdg-terminals.list` file.
+///
+/// Comments and excluded (`-`) entries are skipped, and `:action`
+/// suffixes are dropped.
+fn parse_terminal_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('-'))
+        .map(|line| line.trim_start_matches('+'))
+        .map(|line| line.split(':').next().unwrap_or(line).to_string())
+        .filter(|id| id.ends_with(".desktop"))
+        .collect()
+}
+
+/// Build a terminal from a terminal desktop entry.
+///
+/// The argument preceding the command comes from `X-TerminalArgExec`
+/// (or the older `X-ExecArg`), defaulting to `-e`. An empty value means
+/// the terminal takes the command directly.
+fn parse_terminal_entry(content: &str) -> Option<Terminal> {
+    let mut exec = None;
+    let mut exec_arg = None;
+    let mut legacy_exec_arg = None;
+    let mut in_main_group = false;
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_main_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Exec" => exec = Some(value.to_string()),
+            "X-TerminalArgExec" => exec_arg = Some(value.to_string()),
+            "X-ExecArg" => legacy_exec_arg = Some(value.to_string()),
+            "Hidden" if value == "true" => return None,
+            _ => {}
+        }
+    }
+
+    let mut args: Vec<String> = split_exec(&exec?)?
+        .into_iter()
+        .filter(|arg| !(arg.len() == 2 && arg.starts_with('%')))
+        .collect();
+    if args.is_empty() {
+        return None;
+    }
+    let program = args.remove(0);
+
+    let exec_arg = exec_arg
+        .or(legacy_exec_arg)
+        .unwrap_or_else(|| "-e".to_string());
+    args.extend(split_exec(&exec_arg)?);
+
+    Some(Terminal::new(program, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_known_terminal_conventions() {
+        let cmd = strings(&["htop", "-d", "10"]);
+
+        assert_eq!(
+            Terminal::with_known_args("foot").command(&cmd),
+            strings(&["foot", "htop", "-d", "10"])
+        );
+        assert_eq!(
+            Terminal::with_known_args("/usr/bin/wezterm").command(&cmd),
+            strings(&["/usr/bin/wezterm", "start", "--", "htop", "-d", "10"])
+        );
+        assert_eq!(
+            Terminal::with_known_args("kgx").command(&cmd),
+            strings(&["kgx", "--", "htop", "-d", "10"])
+        );
+        assert_eq!(
+            Terminal::with_known_args("my-terminal").command(&cmd),
+            strings(&["my-terminal", "-e", "htop", "-d", "10"])
+        );
+    }
+
+    #[test]
+    fn test_from_command_line() {
+        let cmd = strings(&["htop"]);
+
+        let terminal = Terminal::from_command_line(" kitty --single-instance ").unwrap();
+        assert_eq!(
+            terminal.command(&cmd),
+            strings(&["kitty", "--single-instance", "htop"])
+        );
+        let terminal = Terminal::from_command_line(r#"alacritty --title "My Term""#).unwrap();
+        assert_eq!(
+            terminal.command(&cmd),
+            strings(&["alacritty", "--title", "My Term", "-e", "htop"])
+        );
+        // The convention is not repeated
+        let terminal = Terminal::from_command_line("wezterm start --").unwrap();
+        assert_eq!(
+            terminal.command(&cmd),
+            strings(&["wezterm", "start", "--", "htop"])
+        );
+
+        assert_eq!(Terminal::from_command_line("  "), None);
+        assert_eq!(Terminal::from_command_line(r#"foot "unterminated"#), None);
+    }
+
+    #[test]
+    fn test_command_placeholder() {
+        let terminal = Terminal::new("kitty", strings(&["--hold", "{cmd}", "--title"]));
+        assert_eq!(
+            terminal.command(&strings(&["nvim", "my file.txt"])),
+            strings(&["kitty", "--hold", "nvim", "my file.txt", "--title"])
+        );
+    }
+
+    #[test]
+    fn test_parse_terminal_list() {
+        let content = "# preferred terminals\n\
+                       foot.desktop\n\
+                       -kitty.desktop\n\
+                       +org.wezfurlong.wezterm.desktop:new-window\n\
+                       \n\
+                       not-a-desktop-id\n";
+        assert_eq!(
+            parse_terminal_list(content),
+            strings(&["foot.desktop", "org.wezfurlong.wezterm.desktop"])
+        );
+    }
+
+    #[test]
+    fn test_parse_terminal_entry() {
+        let entry = "[Desktop Entry]\nExec=kitty --single-instance %U\nX-TerminalArgExec=\n";
+        assert_eq!(
+            parse_terminal_entry(entry),
+            Some(Terminal::new("kitty", strings(&["--single-instance"])))
+        );
+
+        let entry = "[Desktop Entry]\nExec=alacritty\n[Desktop Action New]\nExec=other\n";
+        assert_eq!(
+            parse_terminal_entry(entry),
+            Some(Terminal::new("alacritty", strings(&["-e"])))
+        );
+
+        let entry = "[Desktop Entry]\nExec=wezterm\nX-ExecArg=start --\n";
+        assert_eq!(
+            parse_terminal_entry(entry),
+            Some(Terminal::new("wezterm", strings(&["start", "--"])))
+        );
+
+        assert_eq!(
+            parse_terminal_entry("[Desktop Entry]\nExec=foot\nHidden=true\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_in_dirs() {
+        let dir = std::env::temp_dir().join(format!("zlaunch-path-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let exe = dir.join("fake-terminal");
+        std::fs::write(&exe, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let plain = dir.join("not-executable");
+        std::fs::write(&plain, "").unwrap();
+        std::fs::set_permissions(&plain, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        assert_eq!(find_in_dirs("fake-terminal", [dir.clone()]), Some(exe));
+        assert_eq!(find_in_dirs("not-executable", [dir.clone()]), None);
+        assert_eq!(find_in_dirs("missing", [dir.clone()]), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
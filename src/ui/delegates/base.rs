@@ -128,6 +128,12 @@ impl<T: Clone> BaseDelegate<T> {
             .and_then(|idx| self.get_filtered_item(idx))
     }
 
+    /// Get the currently selected item for modification
+    pub fn selected_item_mut(&mut self) -> Option<&mut T> {
+        let item_idx = *self.filtered_indices.get(self.selected_index?)?;
+        self.items.get_mut(item_idx)
+    }
+
     /// Execute the confirm callback
     pub fn do_confirm(&self) {
         if let Some(item) = self.selected_item()
@@ -95,6 +95,11 @@ impl ClipboardListDelegate {
         self.base.selected_item()
     }
 
+    /// Get the currently selected item for modification
+    pub fn selected_item_mut(&mut self) -> Option<&mut ClipboardItem> {
+        self.base.selected_item_mut()
+    }
+
     /// Execute confirm callback
     pub fn do_confirm(&self) {
         self.base.do_confirm();
@@ -1,11 +1,13 @@
-//! Dynamic item detection for calculator, AI, and search.
+//! Dynamic item detection for calculator, AI, search, and "Open With".
 //!
 //! These items are generated on-the-fly based on the user's query,
 //! rather than being static items in the list.
 
 use crate::calculator::evaluate_expression;
-use crate::items::{AiItem, CalculatorItem, SearchItem};
+use crate::desktop::mime::{MimeApps, handlers_for_files};
+use crate::items::{AiItem, ApplicationItem, CalculatorItem, ListItem, SearchItem};
 use crate::search::{SearchDetection, detect_search, get_providers};
+use std::path::PathBuf;
 
 /// Container for dynamically generated items based on user query.
 #[derive(Clone, Default)]
@@ -16,6 +18,8 @@ pub struct DynamicItems {
     pub ai_item: Option<AiItem>,
     /// Search provider items (shown when query triggers search).
     pub search_items: Vec<SearchItem>,
+    /// Applications able to open the targeted files, default handler first.
+    pub open_with_items: Vec<ApplicationItem>,
 }
 
 impl DynamicItems {
@@ -95,13 +99,14 @@ impl DynamicItems {
         self.calculator_item = None;
         self.ai_item = None;
         self.search_items.clear();
+        self.open_with_items.clear();
     }
 
     /// Get the total count of dynamic items.
     pub fn count(&self) -> usize {
         let calc_count = if self.calculator_item.is_some() { 1 } else { 0 };
         let ai_count = if self.ai_item.is_some() { 1 } else { 0 };
-        calc_count + ai_count + self.search_items.len()
+        calc_count + ai_count + self.search_items.len() + self.open_with_items.len()
     }
 
     /// Check if there's a calculator item.
@@ -118,6 +123,76 @@ impl DynamicItems {
     pub fn search_count(&self) -> usize {
         self.search_items.len()
     }
+
+    /// Get the "Open With" items count.
+    pub fn open_with_count(&self) -> usize {
+        self.open_with_items.len()
+    }
+}
+
+/// Interpret a query as the path of an existing file.
+///
+/// Accepts absolute paths, `~/` paths and `file://` URIs.
+pub fn query_file_path(query: &str) -> Option<PathBuf> {
+    let trimmed = query.trim();
+    let path = if let Some(uri) = trimmed.strip_prefix("file://") {
+        PathBuf::from(urlencoding::decode(uri).ok()?.into_owned())
+    } else if let Some(rest) = trimmed.strip_prefix("~/") {
+        dirs::home_dir()?.join(rest)
+    } else {
+        PathBuf::from(trimmed)
+    };
+
+    (path.is_absolute() && path.exists()).then_some(path)
+}
+
+/// Build "Open With" items for the applications able to open all files.
+pub fn open_with_items(
+    items: &[ListItem],
+    files: &[PathBuf],
+    mime_apps: &MimeApps,
+) -> Vec<ApplicationItem> {
+    let apps: Vec<&ApplicationItem> = items
+        .iter()
+        .filter_map(|item| match item {
+            ListItem::Application(app) => Some(app),
+            _ => None,
+        })
+        .collect();
+    let candidates: Vec<(&str, &[String])> = apps
+        .iter()
+        .map(|app| (app.id.as_str(), app.mime_types.as_slice()))
+        .collect();
+
+    let handlers = handlers_for_files(files, &candidates, mime_apps);
+    let target = files_label(files);
+
+    handlers
+        .indices
+        .iter()
+        .enumerate()
+        .map(|(pos, &index)| {
+            let description = if pos == 0 && handlers.has_default {
+                format!("Open {} (default)", target)
+            } else {
+                format!("Open {}", target)
+            };
+            let mut app = apps[index].clone().with_files(files.to_vec());
+            app.description = Some(description);
+            app
+        })
+        .collect()
+}
+
+/// Describe a set of files for display.
+fn files_label(files: &[PathBuf]) -> String {
+    match files {
+        [file] => file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| file.display().to_string()),
+        _ => format!("{} files", files.len()),
+    }
 }
 
 #[cfg(test)]
@@ -164,4 +239,31 @@ mod tests {
         assert!(items.has_calculator());
         assert!(!items.has_ai());
     }
+
+    #[test]
+    fn test_query_file_path() {
+        let dir = std::env::temp_dir();
+        let dir_str = dir.to_string_lossy().to_string();
+
+        assert_eq!(
+            query_file_path(&format!("  {}  ", dir_str)),
+            Some(dir.clone())
+        );
+        assert_eq!(query_file_path(&format!("file://{}", dir_str)), Some(dir));
+        assert_eq!(query_file_path("relative/path"), None);
+        assert_eq!(query_file_path("/definitely/not/an/existing/path"), None);
+        assert_eq!(query_file_path("firefox"), None);
+    }
+
+    #[test]
+    fn test_files_label() {
+        assert_eq!(
+            files_label(&[PathBuf::from("/tmp/report.pdf")]),
+            "report.pdf"
+        );
+        assert_eq!(
+            files_label(&[PathBuf::from("/tmp/a.png"), PathBuf::from("/tmp/b.png")]),
+            "2 files"
+        );
+    }
 }
@@ -1,22 +1,26 @@
 //! Enhanced delegate for the main item list.
 //!
-//! Composes BaseDelegate with dynamic items (calculator, AI, search)
-//! and section management.
+//! Composes BaseDelegate with dynamic items (calculator, AI, search,
+//! "Open With"), frecency-based ranking and section management.
 
 use crate::ai::LLMClient;
-use crate::config::{ConfigModule, config};
+use crate::config::{ConfigModule, WindowGrouping, config};
+use crate::desktop::mime::MimeApps;
 use crate::items::{ActionItem, ListItem, SubmenuItem};
 use crate::ui::delegates::BaseDelegate;
 use crate::ui::theme::theme;
 use crate::ui::views::render_item;
+use crate::usage;
 use gpui::{App, Context, SharedString, Task, Window, div, prelude::*};
 use gpui_component::IndexPath;
 use gpui_component::list::{ListDelegate, ListItem as GpuiListItem, ListState};
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use super::dynamic_items::DynamicItems;
+use super::dynamic_items::{DynamicItems, open_with_items, query_file_path};
 use super::item_filter::ItemFilter;
-use super::section_manager::{SectionManager, SectionType};
+use super::section_manager::{SectionManager, SectionType, WindowGroup, group_windows};
 
 /// Type alias for confirm callback.
 type ConfirmCallback = Arc<dyn Fn(&ListItem) + Send + Sync>;
@@ -27,6 +31,7 @@ type ConfirmCallback = Arc<dyn Fn(&ListItem) + Send + Sync>;
 /// - Dynamic calculator results
 /// - AI query detection
 /// - Web search suggestions
+/// - "Open With" applications for a file path query or picked files
 /// - Section management
 pub struct ItemListDelegate {
     /// Base delegate handling common behavior.
@@ -41,6 +46,16 @@ pub struct ItemListDelegate {
     on_confirm: Option<ConfirmCallback>,
     /// Modules enabled in combined view (for filtering).
     combined_modules: Vec<ConfigModule>,
+    /// Files picked elsewhere (e.g. clipboard history) to open with an application.
+    open_with_files: Vec<PathBuf>,
+    /// Application associations, loaded once per launcher on the first "Open With".
+    mime_apps: Option<MimeApps>,
+    /// Whether to show recently launched items on an empty query.
+    show_recent: bool,
+    /// How windows are grouped in the Windows section.
+    window_grouping: WindowGrouping,
+    /// Application groups expanded with Tab.
+    expanded_apps: HashSet<String>,
 }
 
 impl ItemListDelegate {
@@ -49,7 +64,9 @@ impl ItemListDelegate {
         // Filter items based on combined_modules
         items.retain(|item| match item {
             ListItem::Application(_) => combined_modules.contains(&ConfigModule::Applications),
-            ListItem::Window(_) => combined_modules.contains(&ConfigModule::Windows),
+            ListItem::Window(_) | ListItem::Workspace(_) => {
+                combined_modules.contains(&ConfigModule::Windows)
+            }
             _ => true, // Keep other items for now
         });
 
@@ -109,21 +126,43 @@ impl ItemListDelegate {
         }
 
         // Get fuzzy match config from application config
-        let fuzzy_config = config().fuzzy_match.clone();
+        let config = config();
+        let filter = ItemFilter::new(config.fuzzy_match.clone())
+            .with_frecency(usage::frecency_scores())
+            .with_selections(usage::learned_selections());
 
-        let mut sections =
-            SectionManager::new(combined_modules.clone(), fuzzy_config.show_best_match);
-        let filtered_indices: Vec<usize> = (0..items.len()).collect();
-        sections.update(&items, &filtered_indices, false, false, 0);
+        let sections = SectionManager::new(combined_modules.clone(), filter.config.show_best_match);
 
-        Self {
+        let mut delegate = Self {
             base: BaseDelegate::new(items),
-            filter: ItemFilter::new(fuzzy_config),
+            filter,
             dynamic: DynamicItems::new(),
             sections,
             on_confirm: None,
             combined_modules,
-        }
+            open_with_files: Vec::new(),
+            mime_apps: None,
+            show_recent: true,
+            window_grouping: config.windows.group_by,
+            expanded_apps: HashSet::new(),
+        };
+        delegate.filter_items();
+        delegate
+    }
+
+    /// Create a delegate for the Alt-Tab style window switcher.
+    ///
+    /// Lists only windows, in the given (most recently focused) order, and
+    /// preselects the previously focused window.
+    pub fn window_switcher(mut items: Vec<ListItem>) -> Self {
+        items.retain(ListItem::is_window);
+        let mut delegate = Self::new(items, vec![ConfigModule::Windows]);
+        delegate.show_recent = false;
+        // Grouping would break the most recently focused order
+        delegate.window_grouping = WindowGrouping::None;
+        delegate.filter_items();
+        delegate.set_selected(1);
+        delegate
     }
 
     /// Set the confirm callback.
@@ -148,9 +187,11 @@ impl ItemListDelegate {
         }
     }
 
-    /// Get the total count of filtered items (including dynamic items).
+    /// Get the total count of filtered items (including dynamic and recent items).
     pub fn filtered_count(&self) -> usize {
-        self.base.filtered_count() + self.dynamic.count()
+        self.base.filtered_count()
+            + self.dynamic.count()
+            + self.sections.section_item_count(SectionType::Recent)
     }
 
     /// Get the current query.
@@ -161,6 +202,7 @@ impl ItemListDelegate {
     /// Clear the query and reset all dynamic items.
     pub fn clear_query(&mut self) {
         self.dynamic.clear();
+        self.open_with_files.clear();
         self.base.clear_query();
         // Re-filter to reset sections
         self.filter_items();
@@ -172,6 +214,15 @@ impl ItemListDelegate {
         self.process_query(&query);
     }
 
+    /// Offer applications to open the given files.
+    ///
+    /// The query then narrows down the offered applications by name.
+    pub fn set_open_with_files(&mut self, files: Vec<PathBuf>) {
+        self.open_with_files = files;
+        let query = self.query().to_string();
+        self.process_query(&query);
+    }
+
     /// Process the query to detect special items.
     fn process_query(&mut self, query: &str) {
         let ai_enabled =
@@ -182,6 +233,9 @@ impl ItemListDelegate {
         // Process dynamic items
         self.dynamic
             .process_query(query, calculator_enabled, ai_enabled, search_enabled);
+        self.filter
+            .sort_search_items(&mut self.dynamic.search_items);
+        self.update_open_with(query);
 
         // Filter the base items
         self.filter_items();
@@ -192,15 +246,53 @@ impl ItemListDelegate {
         }
     }
 
+    /// Update "Open With" items for picked files or a file path query.
+    fn update_open_with(&mut self, query: &str) {
+        let (files, name_query) = if !self.open_with_files.is_empty() {
+            (self.open_with_files.clone(), query)
+        } else if let Some(path) = query_file_path(query) {
+            (vec![path], "")
+        } else {
+            return;
+        };
+
+        let mime_apps = self.mime_apps.get_or_insert_with(MimeApps::load);
+        let candidates = open_with_items(self.base.items(), &files, mime_apps);
+        if name_query.is_empty() {
+            self.dynamic.open_with_items = candidates;
+            return;
+        }
+
+        // Narrow the candidates down by the typed application name
+        let items: Vec<ListItem> = candidates.into_iter().map(ListItem::Application).collect();
+        self.dynamic.open_with_items = self
+            .filter
+            .filter_with_scores(&items, name_query, &[])
+            .into_iter()
+            .filter_map(|f| match &items[f.index] {
+                ListItem::Application(app) => Some(app.clone()),
+                _ => None,
+            })
+            .collect();
+    }
+
     /// Filter items based on the current query.
     fn filter_items(&mut self) {
         let query = self.base.query();
         let items = self.base.items();
+        let show_recent = self.show_recent && query.is_empty();
 
         // Get filtered items with scores for best-match detection
-        let filtered = self
+        let mut filtered = self
             .filter
             .filter_with_scores(items, query, &self.combined_modules);
+        let window_groups = group_windows(
+            items,
+            &mut filtered,
+            self.window_grouping,
+            &self.expanded_apps,
+            query.is_empty(),
+        );
 
         // Extract indices for base delegate
         let filtered_indices: Vec<usize> = filtered.iter().map(|f| f.index).collect();
@@ -214,6 +306,14 @@ impl ItemListDelegate {
             self.dynamic.has_ai(),
             self.dynamic.search_count(),
         );
+        self.sections.set_open_with(self.dynamic.open_with_count());
+        self.sections.set_window_groups(window_groups);
+
+        // Show recently launched items on an empty query
+        if show_recent {
+            let recent = self.filter.recent_positions(self.base.items(), &filtered);
+            self.sections.set_recent(recent);
+        }
 
         // Ensure selection is initialized
         if self.base.selected_index().is_none() && self.filtered_count() > 0 {
@@ -221,6 +321,39 @@ impl ItemListDelegate {
         }
     }
 
+    /// Expand the collapsed application group of the selected window.
+    ///
+    /// Returns false if the selected item is not a collapsed group.
+    pub fn expand_window_group(&mut self) -> bool {
+        let Some(selected) = self.selected_index() else {
+            return false;
+        };
+        let Some(ListItem::Window(window)) = self.get_item_at(selected) else {
+            return false;
+        };
+        let collapsed = self
+            .sections
+            .window_group_starting_with(&window.id)
+            .is_some_and(|group| group.collapsed);
+        if !collapsed {
+            return false;
+        }
+
+        self.expanded_apps.insert(window.app_id);
+        // The group's first window keeps its position
+        self.filter_items();
+        self.set_selected(selected);
+        true
+    }
+
+    /// Get the window group starting at a position of a section, if any.
+    fn window_group_at(&self, section_type: SectionType, item: &ListItem) -> Option<&WindowGroup> {
+        if section_type != SectionType::Windows {
+            return None;
+        }
+        self.sections.window_group_starting_with(item.id())
+    }
+
     /// Get an item at a global index (including dynamic items).
     pub fn get_item_at(&self, global_index: usize) -> Option<ListItem> {
         // Track offset within regular items (excluding best match)
@@ -238,6 +371,16 @@ impl ItemListDelegate {
                 let row = global_index - current_start;
 
                 return match section_type {
+                    SectionType::OpenWith => self
+                        .dynamic
+                        .open_with_items
+                        .get(row)
+                        .cloned()
+                        .map(ListItem::Application),
+                    SectionType::Recent => {
+                        let pos = *self.sections.recent_filtered_positions().get(row)?;
+                        self.base.get_filtered_item(pos).cloned()
+                    }
                     SectionType::BestMatch => {
                         // Return the promoted best match item
                         let best_pos = best_match_pos?;
@@ -322,6 +465,10 @@ impl ItemListDelegate {
             && let Some(item) = self.get_item_at(idx)
             && let Some(ref callback) = self.on_confirm
         {
+            // Remember which item was chosen for this query
+            if let Some(key) = item.usage_key() {
+                usage::record_selection(self.query(), &key);
+            }
             callback(&item);
         }
     }
@@ -366,6 +513,20 @@ impl ItemListDelegate {
     }
 }
 
+/// Render the sub-header of a window group.
+fn render_window_group_header(title: &str) -> impl IntoElement {
+    let theme = theme();
+    div()
+        .w_full()
+        .px(theme.item_margin_x + theme.item_padding_x)
+        .pt(theme.section_header.margin_bottom)
+        .pb(theme.section_header.margin_bottom)
+        .text_xs()
+        .font_weight(gpui::FontWeight::SEMIBOLD)
+        .text_color(theme.section_header.color.opacity(0.8))
+        .child(SharedString::from(title.to_string()))
+}
+
 /// Implement ListDelegate trait for GPUI integration.
 impl ListDelegate for ItemListDelegate {
     type Item = GpuiListItem;
@@ -427,14 +588,35 @@ impl ListDelegate for ItemListDelegate {
         let global_idx = self.sections.section_row_to_global(ix.section, ix.row);
         let selected = self.base.selected_index() == Some(global_idx);
 
-        let item = self.get_item_at(global_idx)?;
+        let mut item = self.get_item_at(global_idx)?;
+        let section_type = self.sections.section_type_at(ix.section);
+        let group = self.window_group_at(section_type, &item).cloned();
+
+        // A collapsed group shows its window count instead of the workspace
+        if let Some(group) = group.as_ref().filter(|group| group.collapsed)
+            && let ListItem::Window(window) = &mut item
+        {
+            window.description = format!(
+                "{} - {} windows - Tab to expand",
+                window.app_name, group.count
+            );
+        }
         let item_content = render_item(&item, selected, global_idx);
 
         Some(
             GpuiListItem::new(("list-item", global_idx))
                 .py_0()
                 .px_0()
-                .child(item_content),
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .w_full()
+                        .when_some(group.filter(|group| !group.collapsed), |row, group| {
+                            row.child(render_window_group_header(&group.title))
+                        })
+                        .child(item_content),
+                ),
         )
     }
 
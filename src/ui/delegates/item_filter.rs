@@ -7,14 +7,20 @@
 //! - Word prefix matches (query matches start of any word)
 //! - Contiguous character matches
 //!
+//! - Frequently and recently launched items (frecency)
+//! - The item previously chosen for the typed query (selection learning)
+//!
 //! And penalizes:
 //! - Description-only matches (name doesn't match, only description does)
 //! - Action/submenu items in combined mode (demotes system actions)
 
 use crate::config::{ConfigModule, FuzzyMatchConfig};
-use crate::items::ListItem;
+use crate::items::{ListItem, SearchItem};
+use crate::usage;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 
 /// A filtered item with its index and score.
 #[derive(Debug, Clone, Copy)]
@@ -31,6 +37,10 @@ pub struct ItemFilter {
     matcher: SkimMatcherV2,
     /// Configuration for scoring adjustments.
     pub config: FuzzyMatchConfig,
+    /// Frecency scores (0.0-1.0) keyed by item usage key.
+    frecency: HashMap<String, f64>,
+    /// Learned selections: normalized query to the usage key chosen for it.
+    selections: HashMap<String, String>,
 }
 
 impl Default for ItemFilter {
@@ -45,9 +55,23 @@ impl ItemFilter {
         Self {
             matcher: SkimMatcherV2::default(),
             config,
+            frecency: HashMap::new(),
+            selections: HashMap::new(),
         }
     }
 
+    /// Builder method to set the frecency scores used for ranking.
+    pub fn with_frecency(mut self, frecency: HashMap<String, f64>) -> Self {
+        self.frecency = frecency;
+        self
+    }
+
+    /// Builder method to set the learned query selections used for ranking.
+    pub fn with_selections(mut self, selections: HashMap<String, String>) -> Self {
+        self.selections = selections;
+        self
+    }
+
     /// Filter items by query, returning indices of matching items.
     ///
     /// This is a convenience method that wraps `filter_with_scores`
@@ -73,7 +97,8 @@ impl ItemFilter {
     /// When query is empty, returns all items with score 0.
     /// When query is non-empty, returns matching items sorted by:
     /// 1. Module position in combined_modules (primary)
-    /// 2. Enhanced fuzzy score (secondary, higher is better)
+    /// 2. Enhanced fuzzy score including frecency and learned selection
+    ///    bonuses (secondary, higher is better)
     pub fn filter_with_scores(
         &self,
         items: &[ListItem],
@@ -86,11 +111,16 @@ impl ItemFilter {
                 .collect();
         }
 
+        let learned = self.learned_selection(query);
+
         let mut scored: Vec<FilteredItem> = items
             .iter()
             .enumerate()
             .filter_map(|(idx, item)| {
-                let score = self.score_item(item, query)?;
+                let mut score = self.score_item(item, query)?;
+                if learned.is_some() && item.usage_key().as_deref() == learned {
+                    score += self.config.selection_bonus;
+                }
                 Some(FilteredItem { index: idx, score })
             })
             .collect();
@@ -116,29 +146,86 @@ impl ItemFilter {
         scored
     }
 
+    /// Get the filtered positions of recently launched items, most frecent first.
+    ///
+    /// Items sharing a usage key (e.g. several windows of the same app) are
+    /// only returned once. At most `config.recent_items` positions are returned.
+    pub fn recent_positions(&self, items: &[ListItem], filtered: &[FilteredItem]) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        let mut recent: Vec<(usize, f64)> = filtered
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, f)| {
+                let key = items.get(f.index)?.usage_key()?;
+                let score = *self.frecency.get(&key)?;
+                seen.insert(key).then_some((pos, score))
+            })
+            .collect();
+
+        recent.sort_by(|a, b| b.1.total_cmp(&a.1));
+        recent.truncate(self.config.recent_items);
+        recent.into_iter().map(|(pos, _)| pos).collect()
+    }
+
+    /// Sort search provider items by frecency, keeping provider order for ties.
+    pub fn sort_search_items(&self, search_items: &mut [SearchItem]) {
+        search_items.sort_by_cached_key(|item| {
+            let score = self.frecency.get(&item.usage_key()).copied().unwrap_or(0.0);
+            // Scores are never negative, so their bits order like their values
+            Reverse(score.to_bits())
+        });
+    }
+
+    /// Get the usage key learned for a query.
+    ///
+    /// Uses the longest learned query that is a prefix of the typed one, so a
+    /// choice made after typing "fi" still applies when typing "fir".
+    fn learned_selection(&self, query: &str) -> Option<&str> {
+        if self.config.selection_bonus == 0 {
+            return None;
+        }
+
+        let query = usage::normalize_query(query);
+        self.selections
+            .iter()
+            .filter(|(learned, _)| query.starts_with(learned.as_str()))
+            .max_by_key(|(learned, _)| learned.len())
+            .map(|(_, key)| key.as_str())
+    }
+
+    /// Get the frecency score (0.0-1.0) for an item.
+    fn frecency_for(&self, item: &ListItem) -> f64 {
+        item.usage_key()
+            .and_then(|key| self.frecency.get(&key).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// Get the score bonus for an item based on how often and recently it was launched.
+    fn frecency_bonus(&self, item: &ListItem) -> i64 {
+        (self.frecency_for(item) * self.config.frecency_weight as f64) as i64
+    }
+
     /// Get the enhanced fuzzy score for an item against a query.
     ///
     /// The scoring algorithm:
-    /// 1. Try matching against the name first (preferred)
+    /// 1. Try matching against the name and aliases first (preferred)
     /// 2. Fall back to description match with penalty
     /// 3. Apply bonuses for exact/prefix/contiguous matches
     /// 4. Apply item type multipliers (demote actions/submenus)
+    /// 5. Add the frecency bonus for frequently launched items
     fn score_item(&self, item: &ListItem, query: &str) -> Option<i64> {
-        let name = item.name();
-
-        // Try name match first (preferred)
-        if let Some(score) = self.score_text_match(name, query, item, false) {
-            return Some(score);
-        }
-
-        // Fall back to description match (with penalty)
-        if let Some(desc) = item.description() {
-            if let Some(score) = self.score_text_match(desc, query, item, true) {
-                return Some(score);
-            }
-        }
-
-        None
+        let names = std::iter::once(item.name()).chain(item.aliases().iter().map(String::as_str));
+
+        // Try name and alias matches first (preferred), then fall back to description (with penalty)
+        let score = names
+            .filter_map(|name| self.score_text_match(name, query, item, false))
+            .max()
+            .or_else(|| {
+                item.description()
+                    .and_then(|desc| self.score_text_match(desc, query, item, true))
+            })?;
+
+        Some(score + self.frecency_bonus(item))
     }
 
     /// Score a text match against a query, trying multiple query normalizations.
@@ -324,6 +411,19 @@ mod tests {
         assert_eq!(result[0], 0); // App1 with "Web Browser" description
     }
 
+    #[test]
+    fn test_alias_matches_like_name() {
+        let filter = ItemFilter::default();
+        let mut vscode = mock_application("Visual Studio Code");
+        vscode.aliases = vec!["vsc".to_string()];
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Vscan")),
+            ListItem::Application(vscode),
+        ];
+        let result = filter.filter_indices(&items, "vsc", &[]);
+        assert_eq!(result[0], 1); // Exact alias beats the fuzzy name match
+    }
+
     #[test]
     fn test_filter_no_matches() {
         let filter = ItemFilter::default();
@@ -519,6 +619,149 @@ mod tests {
         assert_eq!(result3.len(), 1);
     }
 
+    #[test]
+    fn test_frecency_boosts_ranking() {
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Text Editor")),
+            ListItem::Application(mock_application("Terminal")),
+        ];
+
+        // Without usage data, "Text Editor" wins on contiguity
+        let filter = ItemFilter::default();
+        let result = filter.filter_indices(&items, "te", &[]);
+        assert_eq!(result[0], 0);
+
+        // A frequently launched terminal should overtake it
+        let frecency = HashMap::from([("app:app-terminal".to_string(), 1.0)]);
+        let filter = ItemFilter::default().with_frecency(frecency);
+        let result = filter.filter_indices(&items, "te", &[]);
+        assert_eq!(result[0], 1);
+    }
+
+    #[test]
+    fn test_frecency_does_not_beat_exact_match() {
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Code")),
+            ListItem::Application(mock_application("Code Insiders")),
+        ];
+        let frecency = HashMap::from([("app:app-code-insiders".to_string(), 1.0)]);
+        let filter = ItemFilter::default().with_frecency(frecency);
+        let result = filter.filter_indices(&items, "code", &[]);
+        assert_eq!(result[0], 0);
+    }
+
+    #[test]
+    fn test_recent_positions() {
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Firefox")),
+            ListItem::Application(mock_application("Chrome")),
+            ListItem::Application(mock_application("Code")),
+        ];
+        let frecency = HashMap::from([
+            ("app:app-code".to_string(), 0.9),
+            ("app:app-firefox".to_string(), 0.4),
+        ]);
+        let filter = ItemFilter::default().with_frecency(frecency);
+        let filtered = filter.filter_with_scores(&items, "", &[]);
+
+        // Most frecent first, unused items excluded
+        assert_eq!(filter.recent_positions(&items, &filtered), vec![2, 0]);
+    }
+
+    #[test]
+    fn test_recent_positions_respects_limit() {
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Firefox")),
+            ListItem::Application(mock_application("Chrome")),
+        ];
+        let frecency = HashMap::from([
+            ("app:app-firefox".to_string(), 0.5),
+            ("app:app-chrome".to_string(), 0.6),
+        ]);
+        let config = FuzzyMatchConfig {
+            recent_items: 1,
+            ..Default::default()
+        };
+        let filter = ItemFilter::new(config).with_frecency(frecency);
+        let filtered = filter.filter_with_scores(&items, "", &[]);
+        assert_eq!(filter.recent_positions(&items, &filtered), vec![1]);
+    }
+
+    #[test]
+    fn test_sort_search_items() {
+        let search = |name: &str| {
+            let provider = crate::search::SearchProvider {
+                name: name.to_string(),
+                trigger: String::new(),
+                url_template: "https://example.com/?q={query}".to_string(),
+                icon: crate::assets::PhosphorIcon::MagnifyingGlass,
+            };
+            SearchItem::new(provider, "rust".to_string())
+        };
+        let mut items = ["Google", "Bing", "Wiki", "DuckDuckGo"].map(search);
+        let frecency = HashMap::from([
+            ("search:DuckDuckGo".to_string(), 0.5),
+            ("search:Wiki".to_string(), 0.2),
+        ]);
+        ItemFilter::default()
+            .with_frecency(frecency)
+            .sort_search_items(&mut items);
+
+        // Unused providers keep their order
+        let names: Vec<&str> = items.iter().map(|i| i.provider.name.as_str()).collect();
+        assert_eq!(names, ["DuckDuckGo", "Wiki", "Google", "Bing"]);
+    }
+
+    #[test]
+    fn test_learned_selection_beats_exact_match() {
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Code")),
+            ListItem::Application(mock_application("Code Insiders")),
+        ];
+        let selections = HashMap::from([("code".to_string(), "app:app-code-insiders".to_string())]);
+        let filter = ItemFilter::default().with_selections(selections);
+        let result = filter.filter_indices(&items, "Code", &[]);
+        assert_eq!(result[0], 1);
+    }
+
+    #[test]
+    fn test_learned_selection_applies_to_longer_query() {
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Text Editor")),
+            ListItem::Application(mock_application("Terminal")),
+        ];
+        let selections = HashMap::from([
+            ("t".to_string(), "app:app-text-editor".to_string()),
+            ("te".to_string(), "app:app-terminal".to_string()),
+        ]);
+        let filter = ItemFilter::default().with_selections(selections);
+
+        // The longest learned prefix wins
+        assert_eq!(filter.filter_indices(&items, "te", &[])[0], 1);
+        assert_eq!(filter.filter_indices(&items, "ter", &[])[0], 1);
+        assert_eq!(filter.filter_indices(&items, "t", &[])[0], 0);
+
+        // A shorter query than any learned one gets no boost
+        let selections = HashMap::from([("ter".to_string(), "app:app-terminal".to_string())]);
+        let filter = ItemFilter::default().with_selections(selections);
+        assert_eq!(filter.filter_indices(&items, "te", &[])[0], 0);
+    }
+
+    #[test]
+    fn test_learned_selection_disabled() {
+        let items: Vec<ListItem> = vec![
+            ListItem::Application(mock_application("Code")),
+            ListItem::Application(mock_application("Code Insiders")),
+        ];
+        let selections = HashMap::from([("code".to_string(), "app:app-code-insiders".to_string())]);
+        let config = FuzzyMatchConfig {
+            selection_bonus: 0,
+            ..Default::default()
+        };
+        let filter = ItemFilter::new(config).with_selections(selections);
+        assert_eq!(filter.filter_indices(&items, "code", &[])[0], 0);
+    }
+
     #[test]
     fn test_multi_word_query() {
         let filter = ItemFilter::default();
@@ -3,20 +3,25 @@
 //! Handles organizing items into sections and converting between
 //! global indices and section-based IndexPaths.
 
-use crate::config::ConfigModule;
+use crate::config::{ConfigModule, WindowGrouping};
 use crate::items::ListItem;
 use gpui_component::IndexPath;
+use std::collections::HashSet;
 
 use super::item_filter::FilteredItem;
 
 /// Section types for organizing items in the list.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SectionType {
+    /// Applications able to open the targeted files.
+    OpenWith,
+    /// Recently launched items (shown on an empty query).
+    Recent,
     /// Best match item promoted to top (when enabled).
     BestMatch,
     /// Calculator result (always first if present, after best match).
     Calculator,
-    /// Open windows.
+    /// Open windows and workspaces.
     Windows,
     /// Submenus and actions (emojis, clipboard, themes, actions).
     Commands,
@@ -30,6 +35,8 @@ impl SectionType {
     /// Get the display title for this section.
     pub fn title(&self) -> &'static str {
         match self {
+            SectionType::OpenWith => "Open With",
+            SectionType::Recent => "Recent",
             SectionType::BestMatch => "Best Match",
             SectionType::Calculator => "Calculator",
             SectionType::Windows => "Windows",
@@ -45,7 +52,7 @@ impl SectionType {
 pub struct SectionInfo {
     /// Number of search items.
     pub search_count: usize,
-    /// Number of window items.
+    /// Number of window and workspace items.
     pub window_count: usize,
     /// Number of command items (submenus + actions).
     pub command_count: usize,
@@ -60,7 +67,7 @@ impl SectionInfo {
 
         for &idx in filtered_indices {
             if let Some(item) = items.get(idx) {
-                if item.is_window() {
+                if item.is_window() || item.is_workspace() {
                     info.window_count += 1;
                 } else if item.is_submenu() || item.is_action() {
                     info.command_count += 1;
@@ -74,6 +81,143 @@ impl SectionInfo {
     }
 }
 
+/// A group of items shown under a sub-header in the Windows section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowGroup {
+    /// Sub-header title (workspace or application name).
+    pub title: String,
+    /// ID of the first item shown in the group.
+    pub first_item: String,
+    /// Number of items in the group, including hidden ones.
+    pub count: usize,
+    /// Whether only the first window of the group is shown.
+    pub collapsed: bool,
+}
+
+/// Reorder the windows in filtered results into groups.
+///
+/// Windows keep their relative order within a group. Workspace grouping puts
+/// the current workspace first, followed by the others in ascending order,
+/// and the workspaces themselves last. Application groups follow the order
+/// of their first window. With `collapse`, they only show their first window
+/// unless the application is in `expanded_apps`; it is cleared while
+/// searching, so every matching window stays visible.
+pub fn group_windows(
+    items: &[ListItem],
+    filtered: &mut Vec<FilteredItem>,
+    grouping: WindowGrouping,
+    expanded_apps: &HashSet<String>,
+    collapse: bool,
+) -> Vec<WindowGroup> {
+    if grouping == WindowGrouping::None {
+        return Vec::new();
+    }
+    let in_section = |f: &FilteredItem| items[f.index].config_module() == ConfigModule::Windows;
+    let (Some(start), Some(last)) = (
+        filtered.iter().position(in_section),
+        filtered.iter().rposition(in_section),
+    ) else {
+        return Vec::new();
+    };
+
+    // Anything else inside the block is kept after it
+    let (mut windows, mut workspaces, mut others) = (Vec::new(), Vec::new(), Vec::new());
+    for f in filtered.drain(start..=last) {
+        match &items[f.index] {
+            ListItem::Window(_) => windows.push(f),
+            ListItem::Workspace(_) => workspaces.push(f),
+            _ => others.push(f),
+        }
+    }
+
+    // Workspaces are named as the compositor lists them, which need not
+    // match their IDs
+    let listed_workspace = |id: i32| {
+        items.iter().find_map(|item| match item {
+            ListItem::Workspace(workspace) if workspace.workspace == id => Some(workspace),
+            _ => None,
+        })
+    };
+
+    // Bucket the windows by group key, in order of first appearance
+    let mut buckets: Vec<(String, String, Vec<FilteredItem>)> = Vec::new();
+    let mut current_workspace = None;
+    for f in windows {
+        let ListItem::Window(window) = &items[f.index] else {
+            continue;
+        };
+        let (key, title) = match grouping {
+            WindowGrouping::Workspace => (
+                window.workspace.to_string(),
+                listed_workspace(window.workspace).map_or_else(
+                    || format!("Workspace {}", window.workspace),
+                    |workspace| workspace.title.clone(),
+                ),
+            ),
+            _ => (window.app_id.clone(), window.app_name.clone()),
+        };
+        if window.focused {
+            current_workspace = Some(window.workspace);
+        }
+        match buckets.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, members)) => members.push(f),
+            None => buckets.push((key, title, vec![f])),
+        }
+    }
+
+    if grouping == WindowGrouping::Workspace {
+        let workspace = |members: &[FilteredItem]| match &items[members[0].index] {
+            ListItem::Window(window) => window.workspace,
+            _ => 0,
+        };
+        // Current workspace first and special workspaces last
+        buckets.sort_by_key(|(_, _, members)| {
+            let id = workspace(members);
+            let special = listed_workspace(id).is_some_and(|workspace| workspace.special);
+            (Some(id) != current_workspace, special, id)
+        });
+    }
+
+    let mut groups = Vec::new();
+    let mut block = Vec::new();
+    for (key, title, members) in buckets {
+        let count = members.len();
+        let collapsed = collapse
+            && grouping == WindowGrouping::App
+            && count > 1
+            && !expanded_apps.contains(&key);
+        // Lone windows of an application need no sub-header
+        if grouping == WindowGrouping::Workspace || count > 1 {
+            groups.push(WindowGroup {
+                title,
+                first_item: items[members[0].index].id().to_string(),
+                count,
+                collapsed,
+            });
+        }
+        if collapsed {
+            block.push(members[0]);
+        } else {
+            block.extend(members);
+        }
+    }
+
+    // Keep workspaces apart from the last workspace group
+    if grouping == WindowGrouping::Workspace && !workspaces.is_empty() {
+        groups.push(WindowGroup {
+            title: "Workspaces".to_string(),
+            first_item: items[workspaces[0].index].id().to_string(),
+            count: workspaces.len(),
+            collapsed: false,
+        });
+    }
+    block.extend(workspaces);
+    block.extend(others);
+
+    filtered.splice(start..start, block);
+    groups
+}
+
 /// Manages section organization and index conversions for the item list.
 pub struct SectionManager {
     /// Current section information.
@@ -93,6 +237,13 @@ pub struct SectionManager {
     best_match_filtered_pos: Option<usize>,
     /// The section type that the best match was promoted from.
     best_match_original_section: Option<SectionType>,
+    /// Positions in the filtered results of recently launched items.
+    /// These are shown in addition to their regular section.
+    recent_filtered_positions: Vec<usize>,
+    /// Number of "Open With" items.
+    open_with_count: usize,
+    /// Groups of the Windows section.
+    window_groups: Vec<WindowGroup>,
 }
 
 impl SectionManager {
@@ -107,6 +258,9 @@ impl SectionManager {
             show_best_match,
             best_match_filtered_pos: None,
             best_match_original_section: None,
+            recent_filtered_positions: Vec::new(),
+            open_with_count: 0,
+            window_groups: Vec::new(),
         }
     }
 
@@ -143,9 +297,12 @@ impl SectionManager {
         self.has_ai = has_ai;
         self.search_count = search_count;
 
-        // Reset best match
+        // Reset best match, recent, "Open With" items and window groups
         self.best_match_filtered_pos = None;
         self.best_match_original_section = None;
+        self.recent_filtered_positions.clear();
+        self.open_with_count = 0;
+        self.window_groups.clear();
 
         // Determine if we should promote a best match
         if self.show_best_match && !filtered.is_empty() {
@@ -191,6 +348,49 @@ impl SectionManager {
         }
     }
 
+    /// Set the filtered positions of recently launched items.
+    ///
+    /// Must be called after `update_with_scores`, which clears them.
+    pub fn set_recent(&mut self, positions: Vec<usize>) {
+        self.recent_filtered_positions = positions;
+    }
+
+    /// Get the filtered positions of recently launched items.
+    pub fn recent_filtered_positions(&self) -> &[usize] {
+        &self.recent_filtered_positions
+    }
+
+    /// Check if a recent section is shown.
+    pub fn has_recent(&self) -> bool {
+        !self.recent_filtered_positions.is_empty()
+    }
+
+    /// Set the number of "Open With" items.
+    ///
+    /// Must be called after `update_with_scores`, which clears it.
+    pub fn set_open_with(&mut self, count: usize) {
+        self.open_with_count = count;
+    }
+
+    /// Check if an "Open With" section is shown.
+    pub fn has_open_with(&self) -> bool {
+        self.open_with_count > 0
+    }
+
+    /// Set the groups of the Windows section.
+    ///
+    /// Must be called after `update_with_scores`, which clears them.
+    pub fn set_window_groups(&mut self, groups: Vec<WindowGroup>) {
+        self.window_groups = groups;
+    }
+
+    /// Get the window group starting with an item, if any.
+    pub fn window_group_starting_with(&self, item_id: &str) -> Option<&WindowGroup> {
+        self.window_groups
+            .iter()
+            .find(|group| group.first_item == item_id)
+    }
+
     /// Map a ConfigModule to its SectionType.
     fn section_type_for_module(&self, module: &ConfigModule) -> SectionType {
         match module {
@@ -264,10 +464,20 @@ impl SectionManager {
     }
 
     /// Get the ordered list of section types based on combined_modules.
-    /// If a best match is promoted, BestMatch appears first.
+    /// "Open With" items, recent items and a promoted best match appear first.
     pub fn ordered_section_types(&self) -> Vec<SectionType> {
         let mut sections = Vec::new();
 
+        // Add OpenWith section if files are targeted
+        if self.has_open_with() {
+            sections.push(SectionType::OpenWith);
+        }
+
+        // Add Recent section if we have recently launched items
+        if self.has_recent() {
+            sections.push(SectionType::Recent);
+        }
+
         // Add BestMatch section if we have a promoted item
         if self.has_best_match() {
             sections.push(SectionType::BestMatch);
@@ -279,9 +489,16 @@ impl SectionManager {
         sections
     }
 
-    /// Get the total number of sections (including calculator and best match if present).
+    /// Get the total number of sections (including open with, recent, calculator
+    /// and best match if present).
     pub fn sections_count(&self) -> usize {
         let mut count = 0;
+        if self.has_open_with() {
+            count += 1;
+        }
+        if self.has_recent() {
+            count += 1;
+        }
         if self.has_best_match() {
             count += 1;
         }
@@ -296,7 +513,23 @@ impl SectionManager {
     pub fn section_type_at(&self, section: usize) -> SectionType {
         let mut current_section = 0;
 
-        // BestMatch always first (if present)
+        // OpenWith always first (if present)
+        if self.has_open_with() {
+            if section == current_section {
+                return SectionType::OpenWith;
+            }
+            current_section += 1;
+        }
+
+        // Recent next (if present)
+        if self.has_recent() {
+            if section == current_section {
+                return SectionType::Recent;
+            }
+            current_section += 1;
+        }
+
+        // BestMatch next (if present)
         if self.has_best_match() {
             if section == current_section {
                 return SectionType::BestMatch;
@@ -327,6 +560,8 @@ impl SectionManager {
     /// Get the number of items in a section type.
     pub fn section_item_count(&self, section_type: SectionType) -> usize {
         match section_type {
+            SectionType::OpenWith => self.open_with_count,
+            SectionType::Recent => self.recent_filtered_positions.len(),
             SectionType::BestMatch => {
                 if self.has_best_match() {
                     1
@@ -438,10 +673,13 @@ impl SectionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::items::WorkspaceItem;
     use crate::test_utils::{mock_application, mock_window};
 
     #[test]
     fn test_section_type_title() {
+        assert_eq!(SectionType::OpenWith.title(), "Open With");
+        assert_eq!(SectionType::Recent.title(), "Recent");
         assert_eq!(SectionType::BestMatch.title(), "Best Match");
         assert_eq!(SectionType::Calculator.title(), "Calculator");
         assert_eq!(SectionType::Windows.title(), "Windows");
@@ -563,6 +801,56 @@ mod tests {
         assert!(!manager.has_best_match());
     }
 
+    #[test]
+    fn test_recent_section_first() {
+        let mut manager = SectionManager::new(
+            vec![ConfigModule::Windows, ConfigModule::Applications],
+            true,
+        );
+
+        let items: Vec<ListItem> = vec![
+            ListItem::Window(mock_window("Window", "window")),
+            ListItem::Application(mock_application("App 1")),
+            ListItem::Application(mock_application("App 2")),
+        ];
+
+        manager.update(&items, &[0, 1, 2], false, false, 0);
+        manager.set_recent(vec![2, 1]);
+
+        assert!(manager.has_recent());
+        assert_eq!(manager.sections_count(), 3);
+        assert_eq!(manager.section_type_at(0), SectionType::Recent);
+        assert_eq!(manager.section_item_count(SectionType::Recent), 2);
+        // Recent items stay in their regular section as well
+        assert_eq!(manager.section_item_count(SectionType::Applications), 2);
+        assert_eq!(manager.section_start_index(SectionType::Windows), 2);
+
+        // Updating clears the recent items until they are set again
+        manager.update(&items, &[1], false, false, 0);
+        assert!(!manager.has_recent());
+    }
+
+    #[test]
+    fn test_open_with_section_first() {
+        let mut manager = SectionManager::new(vec![ConfigModule::Applications], true);
+
+        let items: Vec<ListItem> = vec![ListItem::Application(mock_application("App 1"))];
+
+        manager.update(&items, &[0], false, false, 0);
+        manager.set_recent(vec![0]);
+        manager.set_open_with(2);
+
+        assert_eq!(manager.sections_count(), 3);
+        assert_eq!(manager.section_type_at(0), SectionType::OpenWith);
+        assert_eq!(manager.section_type_at(1), SectionType::Recent);
+        assert_eq!(manager.section_item_count(SectionType::OpenWith), 2);
+        assert_eq!(manager.section_start_index(SectionType::Recent), 2);
+
+        // Updating clears the "Open With" items until they are set again
+        manager.update(&items, &[0], false, false, 0);
+        assert!(!manager.has_open_with());
+    }
+
     #[test]
     fn test_section_item_count_with_best_match() {
         let mut manager = SectionManager::new(
@@ -602,4 +890,173 @@ mod tests {
         // Applications has 2 - 1 (promoted) = 1 item
         assert_eq!(manager.section_item_count(SectionType::Applications), 1);
     }
+
+    fn window(title: &str, app_id: &str, workspace: i32, focused: bool) -> ListItem {
+        let mut window = mock_window(title, app_id);
+        window.workspace = workspace;
+        window.focused = focused;
+        ListItem::Window(window)
+    }
+
+    fn workspace(id: i32, name: &str, special: bool) -> ListItem {
+        let info = crate::compositor::WorkspaceInfo {
+            address: id.to_string(),
+            id,
+            name: name.to_string(),
+            output: None,
+            windows: 1,
+            special,
+            focused: false,
+        };
+        ListItem::Workspace(WorkspaceItem::from_workspace_info(info, &[]))
+    }
+
+    fn unscored(items: &[ListItem]) -> Vec<FilteredItem> {
+        (0..items.len())
+            .map(|index| FilteredItem { index, score: 0 })
+            .collect()
+    }
+
+    fn titles(items: &[ListItem], filtered: &[FilteredItem]) -> Vec<String> {
+        filtered
+            .iter()
+            .map(|f| items[f.index].name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_group_windows_none() {
+        let items = vec![window("A", "foot", 2, false), window("B", "foot", 1, true)];
+        let mut filtered = unscored(&items);
+        let groups = group_windows(
+            &items,
+            &mut filtered,
+            WindowGrouping::None,
+            &HashSet::new(),
+            true,
+        );
+        assert!(groups.is_empty());
+        assert_eq!(titles(&items, &filtered), ["A", "B"]);
+    }
+
+    #[test]
+    fn test_group_windows_by_workspace() {
+        let items = vec![
+            window("A", "foot", 3, false),
+            window("B", "firefox", 1, false),
+            window("C", "foot", 2, true),
+            window("D", "code", 3, false),
+            ListItem::Application(mock_application("App")),
+        ];
+        let mut filtered = unscored(&items);
+        let groups = group_windows(
+            &items,
+            &mut filtered,
+            WindowGrouping::Workspace,
+            &HashSet::new(),
+            true,
+        );
+
+        // Current workspace first, then ascending
+        assert_eq!(titles(&items, &filtered), ["C", "B", "A", "D", "App"]);
+        let headers: Vec<&str> = groups.iter().map(|g| g.title.as_str()).collect();
+        assert_eq!(headers, ["Workspace 2", "Workspace 1", "Workspace 3"]);
+        assert_eq!(groups[2].first_item, items[0].id());
+        assert_eq!(groups[2].count, 2);
+        assert!(groups.iter().all(|g| !g.collapsed));
+    }
+
+    #[test]
+    fn test_group_windows_by_listed_workspace() {
+        // Niri workspace IDs differ from the indices shown to users, and
+        // Hyprland special workspaces have negative IDs
+        let items = vec![
+            window("A", "foot", 9, false),
+            window("B", "firefox", -98, false),
+            window("C", "foot", 5, true),
+            window("D", "code", 7, false),
+            workspace(5, "1", false),
+            workspace(9, "2", false),
+            workspace(-98, "scratch", true),
+        ];
+        let mut filtered = unscored(&items);
+        let groups = group_windows(
+            &items,
+            &mut filtered,
+            WindowGrouping::Workspace,
+            &HashSet::new(),
+            true,
+        );
+
+        assert_eq!(
+            titles(&items, &filtered)[..4],
+            ["C", "D", "A", "B"].map(String::from)
+        );
+        let headers: Vec<&str> = groups.iter().map(|g| g.title.as_str()).collect();
+        assert_eq!(
+            headers,
+            [
+                "Workspace 1",
+                "Workspace 7",
+                "Workspace 2",
+                "Special workspace scratch",
+                "Workspaces"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_windows_by_app() {
+        let items = vec![
+            window("A", "firefox", 1, false),
+            window("B", "foot", 1, false),
+            window("C", "firefox", 2, false),
+            window("D", "code", 1, false),
+            window("E", "foot", 3, false),
+        ];
+        let mut filtered = unscored(&items);
+        let groups = group_windows(
+            &items,
+            &mut filtered,
+            WindowGrouping::App,
+            &HashSet::new(),
+            true,
+        );
+
+        // Groups of several windows are collapsed to their first window
+        assert_eq!(titles(&items, &filtered), ["A", "B", "D"]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].title, "firefox");
+        assert_eq!(groups[0].count, 2);
+        assert!(groups[0].collapsed);
+
+        let expanded = HashSet::from(["foot".to_string()]);
+        let mut filtered = unscored(&items);
+        let groups = group_windows(&items, &mut filtered, WindowGrouping::App, &expanded, true);
+        assert_eq!(titles(&items, &filtered), ["A", "B", "E", "D"]);
+        assert!(groups[0].collapsed);
+        assert!(!groups[1].collapsed);
+    }
+
+    #[test]
+    fn test_group_windows_by_app_while_searching() {
+        let items = vec![
+            window("A", "firefox", 1, false),
+            window("B", "foot", 1, false),
+            window("C", "firefox", 2, false),
+        ];
+        let mut filtered = unscored(&items);
+        let groups = group_windows(
+            &items,
+            &mut filtered,
+            WindowGrouping::App,
+            &HashSet::new(),
+            false,
+        );
+
+        // Matching windows are not hidden in collapsed groups
+        assert_eq!(titles(&items, &filtered), ["A", "C", "B"]);
+        assert_eq!(groups.len(), 1);
+        assert!(!groups[0].collapsed);
+    }
 }
@@ -1,19 +1,29 @@
 //! Action handlers for LauncherView.
 //!
-//! Handles confirm, cancel, and go_back actions.
+//! Handles confirm, launch_new_instance, launch_on_workspace, cancel, go_back,
+//! open_with, hide_application and window management actions.
 
 use std::sync::Arc;
 
 use gpui::{Context, Window};
 
-use crate::clipboard::copy_to_clipboard;
-use crate::compositor::Compositor;
+use crate::clipboard::{ClipboardContent, copy_to_clipboard};
+use crate::compositor::{Compositor, CompositorCapabilities, WindowTracker, WorkspaceTarget};
 use crate::config::LauncherMode;
-use crate::desktop::launch_application;
-use crate::items::{Executable, ListItem};
+use crate::desktop::exec::{accepts_files, accepts_multiple_files};
+use crate::desktop::file_chooser::pick_files;
+use crate::desktop::{
+    DesktopEntry, launch_application_on_workspace, launch_application_with_files,
+};
+use crate::items::{ApplicationItem, Executable, ListItem, WindowItem};
+use crate::process::{LaunchFailure, report_failure};
 
 use super::state::ViewMode;
-use super::{Cancel, Confirm, GoBack, LauncherView};
+use super::{
+    Cancel, CloseWindow, Confirm, GoBack, HideApplication, LaunchNewInstance, LauncherView,
+    MoveWindowHere, OpenWith, PinClipboardItem, PinWindow, ToggleWindowFloating,
+    ToggleWindowFullscreen,
+};
 
 impl LauncherView {
     /// Handle confirming the selected item.
@@ -150,29 +160,415 @@ impl LauncherView {
         }
     }
 
+    /// Handle "Open with…".
+    ///
+    /// On an application, asks for files via the portal file dialog and launches
+    /// the application with them. On a clipboard file entry, returns to the main
+    /// view listing the applications able to open the files.
+    pub fn open_with(&mut self, _: &OpenWith, window: &mut Window, cx: &mut Context<Self>) {
+        match self.view_mode {
+            ViewMode::Main => {
+                let delegate = self.list_state.read(cx).delegate();
+                let Some(item) = delegate.get_item_at(delegate.selected_index().unwrap_or(0))
+                else {
+                    return;
+                };
+                let ListItem::Application(app) = &item else {
+                    return;
+                };
+                if !accepts_files(&app.exec) {
+                    return;
+                }
+
+                let entry = DesktopEntry::from(app);
+                let title = format!("Open with {}", app.name);
+                let multiple = accepts_multiple_files(&app.exec);
+
+                // Hide first so the file dialog can take focus
+                (self.on_hide)();
+
+                std::thread::spawn(move || match pick_files(&title, multiple) {
+                    Ok(files) if files.is_empty() => {}
+                    Ok(files) => {
+                        if let Some(key) = item.usage_key() {
+                            crate::usage::record_launch(&key);
+                        }
+                        if let Err(e) = launch_application_with_files(&entry, &files) {
+                            report_failure(LaunchFailure::new(&entry.exec, e.to_string()));
+                        }
+                    }
+                    Err(e) => tracing::warn!(%e, "Failed to pick files"),
+                });
+            }
+            ViewMode::ClipboardHistory => {
+                let paths =
+                    self.clipboard_mode_handler.as_ref().and_then(|handler| {
+                        match &handler
+                            .list_state()
+                            .read(cx)
+                            .delegate()
+                            .selected_item()?
+                            .content
+                        {
+                            ClipboardContent::FilePaths(paths) => Some(paths.clone()),
+                            _ => None,
+                        }
+                    });
+                let Some(paths) = paths else {
+                    return;
+                };
+
+                self.exit_clipboard_mode(window, cx);
+                self.list_state.update(cx, |state, cx| {
+                    state.delegate_mut().set_open_with_files(paths);
+                    cx.notify();
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle "Hide this app".
+    ///
+    /// Hides the selected application by setting `hidden = true` in its
+    /// `[applications.<desktop-id>]` config section, creating the config
+    /// file if needed.
+    pub fn hide_application(
+        &mut self,
+        _: &HideApplication,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(self.view_mode, ViewMode::Main) {
+            return;
+        }
+
+        let delegate = self.list_state.read(cx).delegate();
+        let Some(ListItem::Application(app)) =
+            delegate.get_item_at(delegate.selected_index().unwrap_or(0))
+        else {
+            return;
+        };
+
+        match crate::config::hide_application(&app.id) {
+            Ok(()) => tracing::info!(id = %app.id, "Hid application"),
+            Err(e) => tracing::warn!(
+                id = %app.id,
+                "Hid application until restart, failed to save config: {:#}",
+                e
+            ),
+        }
+
+        self.remove_item(&app.id, window, cx);
+    }
+
+    /// Remove an item from the list, keeping the current query.
+    fn remove_item(&mut self, id: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.original_items.retain(|item| item.id() != id);
+
+        let query = self.input_state.read(cx).value().to_string();
+        self.recreate_delegate_for_mode(window, cx);
+        self.list_state.update(cx, |state, cx| {
+            state.delegate_mut().set_query(query);
+            cx.notify();
+        });
+    }
+
+    /// Get the selected window item in the main view.
+    fn selected_window(&self, cx: &Context<Self>) -> Option<WindowItem> {
+        if !matches!(self.view_mode, ViewMode::Main) {
+            return None;
+        }
+
+        let delegate = self.list_state.read(cx).delegate();
+        match delegate.get_item_at(delegate.selected_index().unwrap_or(0))? {
+            ListItem::Window(win) => Some(win),
+            _ => None,
+        }
+    }
+
+    /// Run a window management action on the selected window.
+    ///
+    /// Does nothing unless a window is selected and the compositor supports
+    /// the action. Returns the window if the action succeeded.
+    fn run_window_action(
+        &self,
+        supported: impl FnOnce(&CompositorCapabilities) -> bool,
+        action: impl FnOnce(&dyn Compositor, &str) -> anyhow::Result<()>,
+        cx: &Context<Self>,
+    ) -> Option<WindowItem> {
+        let win = self.selected_window(cx)?;
+        if !supported(&self.compositor.capabilities()) {
+            return None;
+        }
+
+        match action(self.compositor.as_ref(), &win.address) {
+            Ok(()) => Some(win),
+            Err(e) => {
+                tracing::warn!(%e, "Failed to run window action");
+                None
+            }
+        }
+    }
+
+    /// Handle "Close window".
+    ///
+    /// Closes the selected window and removes it from the list, keeping the
+    /// launcher open.
+    pub fn close_window(&mut self, _: &CloseWindow, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(win) = self.run_window_action(
+            |caps| caps.close_window,
+            |compositor, address| compositor.close_window(address),
+            cx,
+        ) {
+            self.remove_item(&win.id, window, cx);
+        }
+    }
+
+    /// Handle "Toggle floating" on the selected window.
+    pub fn toggle_window_floating(
+        &mut self,
+        _: &ToggleWindowFloating,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .run_window_action(
+                |caps| caps.toggle_floating,
+                |compositor, address| compositor.toggle_floating(address),
+                cx,
+            )
+            .is_some()
+        {
+            (self.on_hide)();
+        }
+    }
+
+    /// Handle "Toggle fullscreen" on the selected window.
+    pub fn toggle_window_fullscreen(
+        &mut self,
+        _: &ToggleWindowFullscreen,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .run_window_action(
+                |caps| caps.toggle_fullscreen,
+                |compositor, address| compositor.toggle_fullscreen(address),
+                cx,
+            )
+            .is_some()
+        {
+            (self.on_hide)();
+        }
+    }
+
+    /// Handle "Pin window" on the selected window.
+    pub fn pin_window(&mut self, _: &PinWindow, _: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .run_window_action(
+                |caps| caps.pin_window,
+                |compositor, address| compositor.pin_window(address),
+                cx,
+            )
+            .is_some()
+        {
+            (self.on_hide)();
+        }
+    }
+
+    /// Handle "Pin clipboard item", pinning or unpinning the selected item of
+    /// the clipboard history so it is never removed.
+    pub fn pin_clipboard_item(
+        &mut self,
+        _: &PinClipboardItem,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(self.view_mode, ViewMode::ClipboardHistory) {
+            return;
+        }
+        let Some(clipboard_state) = self.clipboard_mode_handler.as_ref().map(|h| h.list_state())
+        else {
+            return;
+        };
+
+        clipboard_state.update(cx, |state, cx| {
+            if let Some(item) = state.delegate_mut().selected_item_mut()
+                && let Some(pinned) = crate::clipboard::data::toggle_pinned(item.id)
+            {
+                item.pinned = pinned;
+                cx.notify();
+            }
+        });
+    }
+
+    /// Handle "Move window here", moving the selected window to the current
+    /// workspace.
+    pub fn move_window_here(&mut self, _: &MoveWindowHere, _: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .run_window_action(
+                |caps| caps.move_window,
+                |compositor, address| compositor.move_window_to_workspace(address, None),
+                cx,
+            )
+            .is_some()
+        {
+            (self.on_hide)();
+        }
+    }
+
+    /// Handle "Launch new instance".
+    ///
+    /// Launches the selected application even if one of its windows is open
+    /// and focus-or-launch is enabled. Other items are confirmed as usual.
+    pub fn launch_new_instance(
+        &mut self,
+        _: &LaunchNewInstance,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selected = matches!(self.view_mode, ViewMode::Main)
+            .then(|| {
+                let delegate = self.list_state.read(cx).delegate();
+                let item = delegate.get_item_at(delegate.selected_index().unwrap_or(0))?;
+                Some((item, delegate.query().to_string()))
+            })
+            .flatten();
+
+        if let Some((item, query)) = selected
+            && let ListItem::Application(app) = &item
+        {
+            if let Some(key) = item.usage_key() {
+                crate::usage::record_selection(&query, &key);
+                crate::usage::record_launch(&key);
+            }
+            Self::launch_application_item(app);
+            (self.on_hide)();
+        } else {
+            self.confirm(&Confirm, window, cx);
+        }
+    }
+
+    /// Handle "Launch on workspace".
+    ///
+    /// Launches the selected application on the given workspace, or normally
+    /// if the compositor does not support it. A selected window is moved to
+    /// the numbered workspace instead.
+    pub fn launch_on_workspace(&mut self, workspace: WorkspaceTarget, cx: &mut Context<Self>) {
+        if !matches!(self.view_mode, ViewMode::Main) {
+            return;
+        }
+
+        let delegate = self.list_state.read(cx).delegate();
+        let Some(item) = delegate.get_item_at(delegate.selected_index().unwrap_or(0)) else {
+            return;
+        };
+        if let ListItem::Window(_) = &item {
+            if let WorkspaceTarget::Number(number) = workspace
+                && self
+                    .run_window_action(
+                        |caps| caps.move_window,
+                        |compositor, address| {
+                            compositor.move_window_to_workspace(address, Some(number))
+                        },
+                        cx,
+                    )
+                    .is_some()
+            {
+                (self.on_hide)();
+            }
+            return;
+        }
+        let ListItem::Application(app) = &item else {
+            return;
+        };
+
+        if let Some(key) = item.usage_key() {
+            crate::usage::record_selection(delegate.query(), &key);
+            crate::usage::record_launch(&key);
+        }
+        let entry = DesktopEntry::from(app);
+        if let Err(e) =
+            launch_application_on_workspace(&entry, &app.files, self.compositor.as_ref(), workspace)
+        {
+            report_failure(LaunchFailure::new(&app.exec, e.to_string()));
+        }
+        (self.on_hide)();
+    }
+
+    /// Focus an open window of the application if focus-or-launch applies.
+    ///
+    /// Windows come from the tracker, most recently focused first. Returns
+    /// true if a window was focused.
+    fn focus_open_window(
+        app: &ApplicationItem,
+        compositor: &Arc<dyn Compositor>,
+        window_tracker: &WindowTracker,
+    ) -> bool {
+        let enabled = app
+            .focus_or_launch
+            .unwrap_or_else(|| crate::config::config().focus_or_launch);
+        // "Open With" items always launch so the files get opened
+        if !enabled || !app.files.is_empty() || !compositor.capabilities().window_switching {
+            return false;
+        }
+
+        let windows = match window_tracker.windows() {
+            Ok(windows) => windows,
+            Err(e) => {
+                tracing::warn!(%e, "Failed to list windows");
+                return false;
+            }
+        };
+        let Some(window) = app.find_window(&windows) else {
+            return false;
+        };
+
+        match compositor.focus_window(&window.address) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(%e, "Failed to focus window");
+                false
+            }
+        }
+    }
+
+    /// Launch an application item, with its files for "Open With" items.
+    fn launch_application_item(app: &ApplicationItem) {
+        let entry = DesktopEntry::from(app);
+        if let Err(e) = launch_application_with_files(&entry, &app.files) {
+            report_failure(LaunchFailure::new(&app.exec, e.to_string()));
+        }
+    }
+
     /// Handle confirming an item (static method for callbacks).
-    pub fn handle_item_confirm(item: &ListItem, compositor: &Arc<dyn Compositor>) {
+    pub fn handle_item_confirm(
+        item: &ListItem,
+        compositor: &Arc<dyn Compositor>,
+        window_tracker: &WindowTracker,
+    ) {
+        // Track launches for frecency-based ranking
+        if let Some(key) = item.usage_key() {
+            crate::usage::record_launch(&key);
+        }
+
         match item {
             ListItem::Application(app) => {
-                // Convert to DesktopEntry and launch
-                let entry = crate::desktop::DesktopEntry::new(
-                    app.id.clone(),
-                    app.name.clone(),
-                    app.exec.clone(),
-                    None,
-                    app.icon_path.clone(),
-                    app.description.clone(),
-                    vec![],
-                    app.terminal,
-                    app.desktop_path.clone(),
-                );
-                let _ = launch_application(&entry);
+                if !Self::focus_open_window(app, compositor, window_tracker) {
+                    Self::launch_application_item(app);
+                }
             }
             ListItem::Window(win) => {
                 if let Err(e) = compositor.focus_window(&win.address) {
                     tracing::warn!(%e, "Failed to focus window");
                 }
             }
+            ListItem::Workspace(ws) => {
+                if let Err(e) = compositor.focus_workspace(&ws.address) {
+                    tracing::warn!(%e, "Failed to switch workspace");
+                }
+            }
             ListItem::Calculator(calc) => {
                 if let Err(e) = copy_to_clipboard(calc.text_for_clipboard()) {
                     tracing::warn!(%e, "Failed to copy to clipboard");
@@ -180,7 +576,7 @@ impl LauncherView {
             }
             ListItem::Action(act) => {
                 if let Err(e) = act.execute() {
-                    tracing::warn!(%e, "Failed to execute action");
+                    report_failure(LaunchFailure::new(&act.name, e.to_string()));
                 }
             }
             ListItem::Search(search) => {
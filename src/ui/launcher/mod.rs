@@ -11,6 +11,7 @@
 //! - [`actions`] - Action handlers for keyboard/mouse events
 //! - [`mode_switching`] - Logic for switching between launcher modes
 //! - [`navigation`] - Item selection and list navigation
+//! - [`placement`] - Panel position on the output ([`PanelPlacement`])
 //! - [`render`] - UI rendering implementation
 //!
 //! # View Modes
@@ -23,23 +24,39 @@
 //! - **AiResponse** - Streaming AI chat interface
 //! - **ThemePicker** - Theme selection with live preview
 //! - **Combined** - Customizable combined view with module ordering
+//! - **WindowsMru** - Alt-Tab style window switcher, confirming on modifier release
 //!
 //! # Key Bindings
 //!
 //! - `Up/Down` - Navigate items
 //! - `Tab/Shift+Tab` - Grid navigation (emoji mode)
 //! - `Ctrl+Tab/Ctrl+Shift+Tab` - Switch between modes
+//! - `Alt+Tab/Alt+Shift+Tab` - Navigate items (window switcher)
 //! - `Enter` - Execute selected item
+//! - `Shift+Enter` - Launch a new instance even if the app has an open window
+//! - `Alt+1`…`Alt+9` - Launch the selected app on workspace 1-9
+//! - `Alt+0` - Launch the selected app on a new empty workspace
+//! - `Alt+1`…`Alt+9` on a window - Move the window to workspace 1-9
+//! - `Alt+Enter` - Move the selected window to the current workspace
+//! - `Ctrl+Shift+W` - Close the selected window
+//! - `Ctrl+Shift+F` - Toggle floating for the selected window
+//! - `Ctrl+Shift+M` - Toggle fullscreen for the selected window
+//! - `Ctrl+Shift+P` - Pin the selected window to all workspaces
+//! - `Ctrl+P` - Pin or unpin the selected clipboard history item
+//! - `Ctrl+O` - Open with… (files for the selected app, or apps for clipboard files)
+//! - `Ctrl+Shift+H` - Hide the selected application
 //! - `Escape` - Hide launcher or go back
 //! - `Backspace` (empty input) - Return to previous mode
 
 mod actions;
 mod mode_switching;
 mod navigation;
+mod placement;
 mod render;
 mod state;
 
-pub use state::{ModeState, ViewMode};
+pub use placement::{Align, PanelPlacement};
+pub use state::{ModeState, SwitcherRelease, ViewMode};
 
 use std::sync::Arc;
 
@@ -47,9 +64,9 @@ use gpui::{App, AppContext, Context, Entity, FocusHandle, Focusable, KeyBinding,
 use gpui_component::input::{InputEvent, InputState};
 use gpui_component::list::ListState;
 
-use crate::compositor::Compositor;
+use crate::compositor::{Compositor, WindowTracker};
 use crate::config::{ConfigModule, LauncherMode, get_combined_modules};
-use crate::items::ListItem;
+use crate::items::{ListItem, apply_overrides};
 use crate::ui::delegates::ItemListDelegate;
 use crate::ui::modes::{
     AiModeAccess, AiModeHandler, ClipboardModeHandler, EmojiModeHandler, ThemeModeHandler,
@@ -68,7 +85,26 @@ actions!(
         Cancel,
         GoBack,
         SwitchModeNext,
-        SwitchModePrev
+        SwitchModePrev,
+        OpenWith,
+        HideApplication,
+        LaunchNewInstance,
+        LaunchOnWorkspace1,
+        LaunchOnWorkspace2,
+        LaunchOnWorkspace3,
+        LaunchOnWorkspace4,
+        LaunchOnWorkspace5,
+        LaunchOnWorkspace6,
+        LaunchOnWorkspace7,
+        LaunchOnWorkspace8,
+        LaunchOnWorkspace9,
+        LaunchOnNewWorkspace,
+        CloseWindow,
+        ToggleWindowFloating,
+        ToggleWindowFullscreen,
+        PinWindow,
+        PinClipboardItem,
+        MoveWindowHere
     ]
 );
 
@@ -79,11 +115,32 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("down", SelectNext, Some("LauncherView")),
         KeyBinding::new("tab", SelectTab, Some("LauncherView")),
         KeyBinding::new("shift-tab", SelectTabPrev, Some("LauncherView")),
+        KeyBinding::new("alt-tab", SelectNext, Some("LauncherView")),
+        KeyBinding::new("alt-shift-tab", SelectPrev, Some("LauncherView")),
         KeyBinding::new("enter", Confirm, Some("LauncherView")),
         KeyBinding::new("escape", Cancel, Some("LauncherView")),
         KeyBinding::new("backspace", GoBack, Some("LauncherView")),
         KeyBinding::new("ctrl-tab", SwitchModeNext, Some("LauncherView")),
         KeyBinding::new("ctrl-shift-tab", SwitchModePrev, Some("LauncherView")),
+        KeyBinding::new("ctrl-o", OpenWith, Some("LauncherView")),
+        KeyBinding::new("ctrl-shift-h", HideApplication, Some("LauncherView")),
+        KeyBinding::new("shift-enter", LaunchNewInstance, Some("LauncherView")),
+        KeyBinding::new("alt-1", LaunchOnWorkspace1, Some("LauncherView")),
+        KeyBinding::new("alt-2", LaunchOnWorkspace2, Some("LauncherView")),
+        KeyBinding::new("alt-3", LaunchOnWorkspace3, Some("LauncherView")),
+        KeyBinding::new("alt-4", LaunchOnWorkspace4, Some("LauncherView")),
+        KeyBinding::new("alt-5", LaunchOnWorkspace5, Some("LauncherView")),
+        KeyBinding::new("alt-6", LaunchOnWorkspace6, Some("LauncherView")),
+        KeyBinding::new("alt-7", LaunchOnWorkspace7, Some("LauncherView")),
+        KeyBinding::new("alt-8", LaunchOnWorkspace8, Some("LauncherView")),
+        KeyBinding::new("alt-9", LaunchOnWorkspace9, Some("LauncherView")),
+        KeyBinding::new("alt-0", LaunchOnNewWorkspace, Some("LauncherView")),
+        KeyBinding::new("ctrl-shift-w", CloseWindow, Some("LauncherView")),
+        KeyBinding::new("ctrl-shift-f", ToggleWindowFloating, Some("LauncherView")),
+        KeyBinding::new("ctrl-shift-m", ToggleWindowFullscreen, Some("LauncherView")),
+        KeyBinding::new("ctrl-shift-p", PinWindow, Some("LauncherView")),
+        KeyBinding::new("ctrl-p", PinClipboardItem, Some("LauncherView")),
+        KeyBinding::new("alt-enter", MoveWindowHere, Some("LauncherView")),
     ]);
 }
 
@@ -101,6 +158,8 @@ pub struct LauncherView {
     pub(crate) original_items: Vec<ListItem>,
     /// Compositor reference (for item confirm callbacks)
     pub(crate) compositor: Arc<dyn Compositor>,
+    /// Window tracker (for focusing open windows of applications)
+    pub(crate) window_tracker: WindowTracker,
     /// Emoji mode handler (created on demand)
     pub(crate) emoji_mode_handler: Option<EmojiModeHandler>,
     /// Clipboard mode handler (created on demand)
@@ -119,14 +178,21 @@ pub struct LauncherView {
     pub(crate) focus_handle: FocusHandle,
     /// Callback to hide the launcher
     pub(crate) on_hide: Arc<dyn Fn() + Send + Sync>,
+    /// When the window switcher confirms on modifier release
+    pub(crate) switcher_release: SwitcherRelease,
+    /// Position of the panel on the output
+    pub(crate) placement: PanelPlacement,
 }
 
 impl LauncherView {
-    /// Create a new launcher view with specified modes.
+    /// Create a new launcher view with specified modes and panel placement.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         items: Vec<ListItem>,
         compositor: Arc<dyn Compositor>,
+        window_tracker: WindowTracker,
         modes: Vec<LauncherMode>,
+        placement: PanelPlacement,
         on_hide: impl Fn() + Send + Sync + 'static,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -134,16 +200,14 @@ impl LauncherView {
         let on_hide = Arc::new(on_hide);
         let mode_state = ModeState::new(modes);
 
-        // Determine modules to show based on current mode
-        let modules_for_delegate = Self::modules_for_mode(mode_state.current_mode());
-
-        // Create main delegate with callbacks
-        let mut delegate = ItemListDelegate::new(items.clone(), modules_for_delegate);
+        // Create main delegate for the current mode with callbacks
+        let mut delegate = Self::delegate_for_mode(mode_state.current_mode(), items.clone());
         let on_hide_for_confirm = on_hide.clone();
         let compositor_for_confirm = compositor.clone();
+        let tracker_for_confirm = window_tracker.clone();
 
         delegate.set_on_confirm(move |item| {
-            Self::handle_item_confirm(item, &compositor_for_confirm);
+            Self::handle_item_confirm(item, &compositor_for_confirm, &tracker_for_confirm);
             on_hide_for_confirm();
         });
 
@@ -205,6 +269,7 @@ impl LauncherView {
             list_state,
             original_items: items,
             compositor,
+            window_tracker,
             emoji_mode_handler: None,
             clipboard_mode_handler: None,
             ai_mode_handler: None,
@@ -214,6 +279,8 @@ impl LauncherView {
             input_state,
             focus_handle,
             on_hide,
+            switcher_release: SwitcherRelease::new(),
+            placement,
         };
 
         // Initialize mode handler if starting in a direct mode
@@ -222,6 +289,14 @@ impl LauncherView {
         launcher
     }
 
+    /// Create the main delegate for a launcher mode.
+    pub fn delegate_for_mode(mode: &LauncherMode, items: Vec<ListItem>) -> ItemListDelegate {
+        match mode {
+            LauncherMode::WindowsMru => ItemListDelegate::window_switcher(items),
+            _ => ItemListDelegate::new(items, Self::modules_for_mode(mode)),
+        }
+    }
+
     /// Get the modules to show for a given launcher mode.
     pub fn modules_for_mode(mode: &LauncherMode) -> Vec<ConfigModule> {
         match mode {
@@ -234,7 +309,7 @@ impl LauncherView {
             | LauncherMode::Ai => get_combined_modules(),
             // Single-module modes - return just that module
             LauncherMode::Applications => vec![ConfigModule::Applications],
-            LauncherMode::Windows => vec![ConfigModule::Windows],
+            LauncherMode::Windows | LauncherMode::WindowsMru => vec![ConfigModule::Windows],
             LauncherMode::Actions => vec![ConfigModule::Actions],
             LauncherMode::Search => vec![ConfigModule::Search],
             LauncherMode::Calculator => vec![ConfigModule::Calculator],
@@ -247,6 +322,7 @@ impl LauncherView {
             LauncherMode::Combined => "Search anything...",
             LauncherMode::Applications => "Search applications...",
             LauncherMode::Windows => "Search windows...",
+            LauncherMode::WindowsMru => "Switch windows...",
             LauncherMode::Actions => "Search actions...",
             LauncherMode::Emojis => "Search emojis...",
             LauncherMode::Clipboard => "Search clipboard...",
@@ -290,7 +366,7 @@ impl LauncherView {
         cx: &mut Context<Self>,
     ) {
         // Update original_items with new applications
-        self.original_items = applications
+        self.original_items = apply_overrides(applications, &crate::config::config().applications)
             .into_iter()
             .map(ListItem::Application)
             .collect();
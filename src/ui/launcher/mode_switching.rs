@@ -272,16 +272,16 @@ impl LauncherView {
 
     /// Recreate the main delegate for the current mode with appropriate module filtering.
     pub fn recreate_delegate_for_mode(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let modules = Self::modules_for_mode(self.mode_state.current_mode());
-
         // Create new delegate with filtered modules
-        let mut delegate = ItemListDelegate::new(self.original_items.clone(), modules);
+        let mut delegate =
+            Self::delegate_for_mode(self.mode_state.current_mode(), self.original_items.clone());
 
         // Set up callbacks
         let on_hide = self.on_hide.clone();
         let compositor = self.compositor.clone();
+        let window_tracker = self.window_tracker.clone();
         delegate.set_on_confirm(move |item| {
-            Self::handle_item_confirm(item, &compositor);
+            Self::handle_item_confirm(item, &compositor, &window_tracker);
             on_hide();
         });
 
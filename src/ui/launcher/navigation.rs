@@ -1,12 +1,14 @@
 //! Navigation methods for LauncherView.
 //!
-//! Handles up/down/tab navigation across all view modes.
+//! Handles up/down/tab navigation across all view modes, and the window
+//! switcher's advance and confirm-on-release behavior.
 
-use gpui::{Context, ScrollStrategy, Window};
+use gpui::{Context, ModifiersChangedEvent, ScrollStrategy, Window};
 use gpui_component::IndexPath;
 
 use super::state::ViewMode;
-use super::{LauncherView, SelectNext, SelectPrev, SelectTab, SelectTabPrev};
+use super::{Confirm, LauncherView, SelectNext, SelectPrev, SelectTab, SelectTabPrev};
+use crate::config::LauncherMode;
 
 impl LauncherView {
     /// Navigate to the next item.
@@ -163,6 +165,11 @@ impl LauncherView {
             ViewMode::Main => {
                 self.list_state.update(cx, |state, cx| {
                     let delegate = state.delegate_mut();
+                    // Tab on a collapsed application group expands it
+                    if delegate.expand_window_group() {
+                        cx.notify();
+                        return;
+                    }
                     let count = delegate.filtered_count();
                     if count == 0 {
                         return;
@@ -319,4 +326,42 @@ impl LauncherView {
             }
         }
     }
+
+    /// Check if the window switcher is shown.
+    pub fn is_window_switcher(&self) -> bool {
+        matches!(self.view_mode, ViewMode::Main)
+            && matches!(self.mode_state.current_mode(), LauncherMode::WindowsMru)
+    }
+
+    /// Advance the window switcher selection, as when its binding is pressed
+    /// again while the launcher is open.
+    ///
+    /// Returns false if the window switcher is not shown.
+    pub fn advance_window_switcher(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        if !self.is_window_switcher() {
+            return false;
+        }
+        // The binding's modifiers are held while it is pressed again
+        self.switcher_release.arm(window.modifiers());
+        self.select_next(&SelectNext, window, cx);
+        true
+    }
+
+    /// Confirm the window switcher selection when the modifiers of its
+    /// binding are released.
+    ///
+    /// Only modifiers held when the launcher was shown, or when the switcher
+    /// was advanced, confirm. Opening the switcher without a modifier (e.g.
+    /// from a menu) never confirms on release.
+    pub fn modifiers_changed(
+        &mut self,
+        event: &ModifiersChangedEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let confirm = self.switcher_release.modifiers_changed(event.modifiers);
+        if confirm && self.is_window_switcher() {
+            self.confirm(&Confirm, window, cx);
+        }
+    }
 }
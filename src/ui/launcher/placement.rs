@@ -0,0 +1,237 @@
+//! Launcher panel placement.
+//!
+//! Resolves a [`PlacementConfig`] into the alignment and margins of the panel
+//! on the output. Without a backdrop these become the layer-shell anchor and
+//! margins; with a backdrop the panel is laid out inside the fullscreen window.
+
+use gpui::layer_shell::Anchor;
+
+use crate::config::{LauncherAnchor, PlacementConfig};
+
+/// Alignment of the panel along one axis of the output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    #[default]
+    Center,
+    End,
+}
+
+/// Resolved placement of the launcher panel on an output.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PanelPlacement {
+    pub horizontal: Align,
+    pub vertical: Align,
+    /// Distance from the output edges (top, right, bottom, left) in pixels.
+    pub margin: (f32, f32, f32, f32),
+    /// Stretch the panel to the output width.
+    pub fill_width: bool,
+    /// Stretch the panel to the output height.
+    pub fill_height: bool,
+}
+
+impl PanelPlacement {
+    /// Resolve a placement for a panel of `launcher_size`.
+    ///
+    /// `output_size` keeps a cursor-placed panel on the output, and `cursor`
+    /// is the pointer position relative to the output.
+    pub fn resolve(
+        config: &PlacementConfig,
+        launcher_size: (f32, f32),
+        output_size: Option<(f32, f32)>,
+        cursor: Option<(f32, f32)>,
+    ) -> Self {
+        let (x, y) = config.offset;
+
+        if config.anchor == LauncherAnchor::Cursor {
+            let Some((cursor_x, cursor_y)) = cursor else {
+                return Self::default();
+            };
+            let (mut left, mut top) = (cursor_x + x, cursor_y + y);
+            if let Some((output_w, output_h)) = output_size {
+                left = left.min(output_w - launcher_size.0);
+                top = top.min(output_h - launcher_size.1);
+            }
+            return Self {
+                horizontal: Align::Start,
+                vertical: Align::Start,
+                margin: (top.max(0.0), 0.0, 0.0, left.max(0.0)),
+                ..Self::default()
+            };
+        }
+
+        let horizontal = match config.anchor {
+            LauncherAnchor::Left | LauncherAnchor::TopLeft | LauncherAnchor::BottomLeft => {
+                Align::Start
+            }
+            LauncherAnchor::Right | LauncherAnchor::TopRight | LauncherAnchor::BottomRight => {
+                Align::End
+            }
+            _ => Align::Center,
+        };
+        let vertical = match config.anchor {
+            LauncherAnchor::Top | LauncherAnchor::TopLeft | LauncherAnchor::TopRight => {
+                Align::Start
+            }
+            LauncherAnchor::Bottom | LauncherAnchor::BottomLeft | LauncherAnchor::BottomRight => {
+                Align::End
+            }
+            _ => Align::Center,
+        };
+
+        // Offsets only apply to the anchored edges
+        let margin = (
+            if vertical == Align::Start { y } else { 0.0 },
+            if horizontal == Align::End { x } else { 0.0 },
+            if vertical == Align::End { y } else { 0.0 },
+            if horizontal == Align::Start { x } else { 0.0 },
+        );
+
+        let fill = config.fill && config.anchor.is_edge();
+        Self {
+            horizontal,
+            vertical,
+            margin,
+            fill_width: fill && horizontal == Align::Center,
+            fill_height: fill && vertical == Align::Center,
+        }
+    }
+
+    /// Layer-shell anchor of a window holding only the panel.
+    pub fn layer_anchor(&self) -> Anchor {
+        let mut anchor = Anchor::empty();
+        if self.horizontal == Align::Start || self.fill_width {
+            anchor |= Anchor::LEFT;
+        }
+        if self.horizontal == Align::End || self.fill_width {
+            anchor |= Anchor::RIGHT;
+        }
+        if self.vertical == Align::Start || self.fill_height {
+            anchor |= Anchor::TOP;
+        }
+        if self.vertical == Align::End || self.fill_height {
+            anchor |= Anchor::BOTTOM;
+        }
+        anchor
+    }
+
+    /// Size of a window holding only the panel.
+    ///
+    /// Filled dimensions take the output size, when known.
+    pub fn window_size(
+        &self,
+        launcher_size: (f32, f32),
+        output_size: Option<(f32, f32)>,
+    ) -> (f32, f32) {
+        let (mut w, mut h) = launcher_size;
+        if let Some((output_w, output_h)) = output_size {
+            let (top, right, bottom, left) = self.margin;
+            if self.fill_width {
+                w = output_w - left - right;
+            }
+            if self.fill_height {
+                h = output_h - top - bottom;
+            }
+        }
+        (w, h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placement(anchor: LauncherAnchor, offset: (f32, f32), fill: bool) -> PlacementConfig {
+        PlacementConfig {
+            anchor,
+            offset,
+            fill,
+        }
+    }
+
+    #[test]
+    fn test_resolve_center() {
+        let resolved = PanelPlacement::resolve(
+            &placement(LauncherAnchor::Center, (10.0, 20.0), true),
+            (600.0, 400.0),
+            None,
+            None,
+        );
+        assert_eq!(resolved, PanelPlacement::default());
+        assert_eq!(resolved.layer_anchor(), Anchor::empty());
+    }
+
+    #[test]
+    fn test_resolve_top() {
+        let resolved = PanelPlacement::resolve(
+            &placement(LauncherAnchor::Top, (10.0, 120.0), false),
+            (600.0, 400.0),
+            None,
+            None,
+        );
+        assert_eq!(resolved.horizontal, Align::Center);
+        assert_eq!(resolved.vertical, Align::Start);
+        assert_eq!(resolved.margin, (120.0, 0.0, 0.0, 0.0));
+        assert_eq!(resolved.layer_anchor(), Anchor::TOP);
+    }
+
+    #[test]
+    fn test_resolve_corner() {
+        let resolved = PanelPlacement::resolve(
+            &placement(LauncherAnchor::BottomRight, (8.0, 16.0), true),
+            (600.0, 400.0),
+            None,
+            None,
+        );
+        assert_eq!(resolved.margin, (0.0, 8.0, 16.0, 0.0));
+        assert!(!resolved.fill_width && !resolved.fill_height);
+        assert_eq!(resolved.layer_anchor(), Anchor::BOTTOM | Anchor::RIGHT);
+    }
+
+    #[test]
+    fn test_resolve_sidebar() {
+        let resolved = PanelPlacement::resolve(
+            &placement(LauncherAnchor::Left, (0.0, 0.0), true),
+            (400.0, 600.0),
+            Some((2560.0, 1440.0)),
+            None,
+        );
+        assert!(resolved.fill_height);
+        assert!(!resolved.fill_width);
+        assert_eq!(
+            resolved.layer_anchor(),
+            Anchor::LEFT | Anchor::TOP | Anchor::BOTTOM
+        );
+        assert_eq!(
+            resolved.window_size((400.0, 600.0), Some((2560.0, 1440.0))),
+            (400.0, 1440.0)
+        );
+        assert_eq!(resolved.window_size((400.0, 600.0), None), (400.0, 600.0));
+    }
+
+    #[test]
+    fn test_resolve_cursor() {
+        let config = placement(LauncherAnchor::Cursor, (10.0, 10.0), false);
+        let resolved = PanelPlacement::resolve(
+            &config,
+            (600.0, 400.0),
+            Some((1920.0, 1080.0)),
+            Some((100.0, 200.0)),
+        );
+        assert_eq!(resolved.margin, (210.0, 0.0, 0.0, 110.0));
+        assert_eq!(resolved.layer_anchor(), Anchor::TOP | Anchor::LEFT);
+
+        // Kept on the output
+        let resolved = PanelPlacement::resolve(
+            &config,
+            (600.0, 400.0),
+            Some((1920.0, 1080.0)),
+            Some((1800.0, 1000.0)),
+        );
+        assert_eq!(resolved.margin, (680.0, 0.0, 0.0, 1320.0));
+
+        // Unknown pointer position
+        let resolved = PanelPlacement::resolve(&config, (600.0, 400.0), None, None);
+        assert_eq!(resolved, PanelPlacement::default());
+    }
+}
@@ -4,15 +4,22 @@ use gpui::{Context, Length, Window, div, image_cache, prelude::*, px, retain_all
 use gpui_component::list::List;
 use gpui_component::{ActiveTheme, Icon, IconName};
 
-use super::LauncherView;
+use super::placement::Align;
 use super::state::ViewMode;
+use super::{
+    LaunchOnNewWorkspace, LaunchOnWorkspace1, LaunchOnWorkspace2, LaunchOnWorkspace3,
+    LaunchOnWorkspace4, LaunchOnWorkspace5, LaunchOnWorkspace6, LaunchOnWorkspace7,
+    LaunchOnWorkspace8, LaunchOnWorkspace9, LauncherView,
+};
+use crate::compositor::WorkspaceTarget;
 
 impl gpui::Render for LauncherView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Clone theme to avoid borrow conflicts
         let theme = self.current_theme.clone();
         let config = crate::config::config();
-        let (launcher_w, launcher_h) = config.get_launcher_size();
+        let (launcher_w, launcher_h) = crate::config::launcher_size();
+        let placement = self.placement.clone();
 
         // Input prefix (icon based on mode and navigation state)
         let input_prefix = self.render_input_prefix(cx);
@@ -23,8 +30,20 @@ impl gpui::Render for LauncherView {
         // Build the launcher panel
         let launcher_panel = div()
             .id("launcher-panel")
-            .w(px(launcher_w))
-            .h(px(launcher_h))
+            .map(|panel| {
+                if placement.fill_width {
+                    panel.w_full()
+                } else {
+                    panel.w(px(launcher_w))
+                }
+            })
+            .map(|panel| {
+                if placement.fill_height {
+                    panel.h_full()
+                } else {
+                    panel.h(px(launcher_h))
+                }
+            })
             .flex()
             .flex_col()
             .bg(if config.enable_transparency {
@@ -55,8 +74,9 @@ impl gpui::Render for LauncherView {
             .child(list_content);
 
         if config.enable_backdrop {
-            // With backdrop: fullscreen container with centered panel and click-outside-to-close
+            // With backdrop: fullscreen container with placed panel and click-outside-to-close
             let on_hide = self.on_hide.clone();
+            let (top, right, bottom, left) = placement.margin;
             div()
                 .track_focus(&self.focus_handle)
                 .key_context("LauncherView")
@@ -69,10 +89,33 @@ impl gpui::Render for LauncherView {
                 .on_action(cx.listener(Self::go_back))
                 .on_action(cx.listener(Self::switch_mode_next))
                 .on_action(cx.listener(Self::switch_mode_prev))
+                .on_action(cx.listener(Self::open_with))
+                .on_action(cx.listener(Self::hide_application))
+                .on_action(cx.listener(Self::launch_new_instance))
+                .on_action(cx.listener(Self::close_window))
+                .on_action(cx.listener(Self::toggle_window_floating))
+                .on_action(cx.listener(Self::toggle_window_fullscreen))
+                .on_action(cx.listener(Self::pin_window))
+                .on_action(cx.listener(Self::pin_clipboard_item))
+                .on_action(cx.listener(Self::move_window_here))
+                .on_modifiers_changed(cx.listener(Self::modifiers_changed))
+                .map(|element| Self::on_workspace_actions(element, cx))
                 .size_full()
                 .flex()
-                .items_center()
-                .justify_center()
+                .map(|element| match placement.horizontal {
+                    Align::Start => element.justify_start(),
+                    Align::Center => element.justify_center(),
+                    Align::End => element.justify_end(),
+                })
+                .map(|element| match placement.vertical {
+                    Align::Start => element.items_start(),
+                    Align::Center => element.items_center(),
+                    Align::End => element.items_end(),
+                })
+                .pt(px(top))
+                .pr(px(right))
+                .pb(px(bottom))
+                .pl(px(left))
                 // Click on backdrop to close
                 .on_mouse_down(gpui::MouseButton::Left, move |_event, _window, _cx| {
                     on_hide();
@@ -99,12 +142,58 @@ impl gpui::Render for LauncherView {
                 .on_action(cx.listener(Self::go_back))
                 .on_action(cx.listener(Self::switch_mode_next))
                 .on_action(cx.listener(Self::switch_mode_prev))
+                .on_action(cx.listener(Self::open_with))
+                .on_action(cx.listener(Self::hide_application))
+                .on_action(cx.listener(Self::launch_new_instance))
+                .on_action(cx.listener(Self::close_window))
+                .on_action(cx.listener(Self::toggle_window_floating))
+                .on_action(cx.listener(Self::toggle_window_fullscreen))
+                .on_action(cx.listener(Self::pin_window))
+                .on_action(cx.listener(Self::pin_clipboard_item))
+                .on_action(cx.listener(Self::move_window_here))
+                .on_modifiers_changed(cx.listener(Self::modifiers_changed))
+                .map(|element| Self::on_workspace_actions(element, cx))
                 .into_any_element()
         }
     }
 }
 
 impl LauncherView {
+    /// Register the "Launch on workspace" actions on an element.
+    fn on_workspace_actions<E: InteractiveElement>(element: E, cx: &mut Context<Self>) -> E {
+        element
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace1, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(1), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace2, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(2), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace3, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(3), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace4, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(4), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace5, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(5), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace6, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(6), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace7, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(7), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace8, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(8), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnWorkspace9, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Number(9), cx)
+            }))
+            .on_action(cx.listener(|this, _: &LaunchOnNewWorkspace, _, cx| {
+                this.launch_on_workspace(WorkspaceTarget::Empty, cx)
+            }))
+    }
+
     /// Render the input prefix icon based on current mode and navigation state.
     fn render_input_prefix(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
         match self.view_mode {
@@ -113,7 +202,8 @@ impl LauncherView {
                 let icon = match self.mode_state.current_mode() {
                     crate::config::LauncherMode::Combined => IconName::Search,
                     crate::config::LauncherMode::Applications => IconName::Search,
-                    crate::config::LauncherMode::Windows => IconName::LayoutDashboard,
+                    crate::config::LauncherMode::Windows
+                    | crate::config::LauncherMode::WindowsMru => IconName::LayoutDashboard,
                     crate::config::LauncherMode::Actions => IconName::Settings,
                     crate::config::LauncherMode::Search => IconName::Globe,
                     crate::config::LauncherMode::Calculator => IconName::Search,
@@ -1,9 +1,18 @@
 //! Launcher state management.
 //!
-//! Contains mode state tracking and view mode definitions.
+//! Contains mode state tracking, view mode definitions and the window
+//! switcher's confirm-on-release state.
+
+use std::time::{Duration, Instant};
+
+use gpui::Modifiers;
 
 use crate::config::LauncherMode;
 
+/// Modifiers reported this long after showing were not held by the binding
+/// that opened the launcher.
+const OPEN_MODIFIERS_WINDOW: Duration = Duration::from_millis(500);
+
 /// Tracks the active modes list and current mode index.
 #[derive(Clone, Debug)]
 pub struct ModeState {
@@ -68,3 +77,136 @@ pub enum ViewMode {
     /// Theme picker view.
     ThemePicker,
 }
+
+/// When the window switcher confirms its selection on modifier release.
+///
+/// Only the modifiers of the binding that opened or advanced the switcher
+/// confirm, so modifiers pressed while it is shown (e.g. Shift for a capital
+/// letter) do not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwitcherRelease {
+    /// Waiting for the modifiers held when the launcher was shown.
+    Pending(Instant),
+    /// Confirm once none of these modifiers is held anymore.
+    Armed(Modifiers),
+    /// Never confirm on release.
+    Disarmed,
+}
+
+impl SwitcherRelease {
+    /// State for a launcher shown now.
+    pub fn new() -> Self {
+        Self::Pending(Instant::now())
+    }
+
+    /// Confirm on release of the given held modifiers, if any are held.
+    pub fn arm(&mut self, held: Modifiers) {
+        if held.modified() {
+            *self = Self::Armed(held);
+        }
+    }
+
+    /// Update with changed modifiers, returning true if the selection should
+    /// be confirmed.
+    pub fn modifiers_changed(&mut self, held: Modifiers) -> bool {
+        match *self {
+            // The first report after showing tells what the binding held
+            Self::Pending(shown) => {
+                *self = Self::Disarmed;
+                if shown.elapsed() < OPEN_MODIFIERS_WINDOW {
+                    self.arm(held);
+                }
+                false
+            }
+            Self::Armed(armed) => {
+                let still_held = (armed.control && held.control)
+                    || (armed.alt && held.alt)
+                    || (armed.shift && held.shift)
+                    || (armed.platform && held.platform)
+                    || (armed.function && held.function);
+                if !still_held {
+                    *self = Self::Disarmed;
+                }
+                !still_held
+            }
+            Self::Disarmed => false,
+        }
+    }
+}
+
+impl Default for SwitcherRelease {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alt() -> Modifiers {
+        Modifiers {
+            alt: true,
+            ..Default::default()
+        }
+    }
+
+    fn alt_shift() -> Modifiers {
+        Modifiers {
+            alt: true,
+            shift: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_first_report_arms_within_window() {
+        let mut release = SwitcherRelease::new();
+        assert!(!release.modifiers_changed(alt()));
+        assert_eq!(release, SwitcherRelease::Armed(alt()));
+
+        // Shown without modifiers, e.g. from a command
+        let mut release = SwitcherRelease::new();
+        assert!(!release.modifiers_changed(Modifiers::default()));
+        assert_eq!(release, SwitcherRelease::Disarmed);
+    }
+
+    #[test]
+    fn test_first_report_disarms_after_window() {
+        let shown = Instant::now()
+            .checked_sub(OPEN_MODIFIERS_WINDOW + Duration::from_millis(10))
+            .unwrap();
+        let mut release = SwitcherRelease::Pending(shown);
+        assert!(!release.modifiers_changed(alt()));
+        assert_eq!(release, SwitcherRelease::Disarmed);
+        assert!(!release.modifiers_changed(Modifiers::default()));
+    }
+
+    #[test]
+    fn test_shift_does_not_confirm() {
+        let mut release = SwitcherRelease::new();
+        release.modifiers_changed(alt());
+
+        // Shift+Alt+Tab to go back
+        assert!(!release.modifiers_changed(alt_shift()));
+        assert!(!release.modifiers_changed(alt()));
+        assert_eq!(release, SwitcherRelease::Armed(alt()));
+
+        // Shift alone never arms a disarmed switcher
+        let mut release = SwitcherRelease::Disarmed;
+        assert!(!release.modifiers_changed(alt_shift()));
+        assert!(!release.modifiers_changed(Modifiers::default()));
+    }
+
+    #[test]
+    fn test_release_confirms_once() {
+        let mut release = SwitcherRelease::new();
+        release.modifiers_changed(alt_shift());
+
+        assert!(!release.modifiers_changed(alt()));
+        assert!(release.modifiers_changed(Modifiers::default()));
+        assert_eq!(release, SwitcherRelease::Disarmed);
+        assert!(!release.modifiers_changed(alt()));
+        assert!(!release.modifiers_changed(Modifiers::default()));
+    }
+}
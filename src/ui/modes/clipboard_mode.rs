@@ -41,15 +41,16 @@ impl ClipboardModeHandler {
                         tracing::warn!(%e, "Failed to copy text to clipboard");
                     }
                 }
-                ClipboardContent::Image {
-                    width,
-                    height,
-                    rgba_bytes,
-                } => {
-                    if let Err(e) = copy_image_to_clipboard(*width, *height, rgba_bytes) {
-                        tracing::warn!(%e, "Failed to copy image to clipboard");
+                ClipboardContent::Image(image) => match image.to_rgba() {
+                    Ok(rgba_bytes) => {
+                        if let Err(e) =
+                            copy_image_to_clipboard(image.width, image.height, &rgba_bytes)
+                        {
+                            tracing::warn!(%e, "Failed to copy image to clipboard");
+                        }
                     }
-                }
+                    Err(e) => tracing::warn!(%e, "Failed to decode clipboard image"),
+                },
                 ClipboardContent::FilePaths(paths) => {
                     let text = paths
                         .iter()
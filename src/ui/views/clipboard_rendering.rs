@@ -7,6 +7,7 @@ use crate::ui::utils::color::{Color, parse_color};
 use gpui::{Div, ElementId, SharedString, Stateful, div, img, prelude::*, px, svg};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 /// Render a clipboard item in the list.
@@ -20,7 +21,10 @@ pub fn render_clipboard_item(item: &ClipboardItem, selected: bool, row: usize) -
     };
 
     // Format timestamp
-    let timestamp_str = format_timestamp(&item.timestamp);
+    let mut timestamp_str = format_timestamp(&item.timestamp);
+    if item.pinned {
+        timestamp_str = format!("Pinned - {}", timestamp_str);
+    }
 
     // Get preview text
     let preview = get_item_preview(item);
@@ -333,14 +337,7 @@ pub fn render_preview_panel(item: Option<&ClipboardItem>) -> Div {
                     .child(SharedString::from(text.clone())),
             )
         }
-        ClipboardContent::Image {
-            width,
-            height,
-            rgba_bytes,
-        } => {
-            // Try to render the image
-            render_image_preview_full(panel, *width, *height, rgba_bytes)
-        }
+        ClipboardContent::Image(image) => render_image_preview_full(panel, image.thumbnail()),
         ClipboardContent::FilePaths(paths) => {
             if paths.len() == 1 {
                 let path = &paths[0];
@@ -436,41 +433,17 @@ pub fn render_preview_panel(item: Option<&ClipboardItem>) -> Div {
     }
 }
 
-/// Render an image from raw RGBA bytes in the preview panel.
-fn render_image_preview_full(panel: Div, width: usize, height: usize, rgba_bytes: &[u8]) -> Div {
-    use image::{ImageBuffer, ImageFormat, Rgba};
-    use std::io::Cursor;
-    use std::sync::Arc;
-    let t = theme();
-
-    // Create ImageBuffer from raw RGBA pixel data
-    if let Some(img_buffer) =
-        ImageBuffer::<Rgba<u8>, _>::from_raw(width as u32, height as u32, rgba_bytes.to_vec())
-    {
-        // Encode image as PNG in memory
-        let mut png_bytes = Vec::new();
-        let mut cursor = Cursor::new(&mut png_bytes);
-
-        if img_buffer.write_to(&mut cursor, ImageFormat::Png).is_ok() {
-            // Create GPUI Image from PNG bytes and wrap in Arc
-            let gpui_image = Arc::new(gpui::Image::from_bytes(gpui::ImageFormat::Png, png_bytes));
-
-            // Use the in-memory image directly
-            return panel.child(
-                img(gpui_image)
-                    .w_full()
-                    .h_full()
-                    .object_fit(gpui::ObjectFit::Contain),
-            );
-        }
-    }
-
-    // Fallback: show error message
+/// Render a PNG-encoded image in the preview panel.
+fn render_image_preview_full(panel: Div, png: &[u8]) -> Div {
+    let image = Arc::new(gpui::Image::from_bytes(
+        gpui::ImageFormat::Png,
+        png.to_vec(),
+    ));
     panel.child(
-        div()
-            .text_sm()
-            .text_color(t.item_description_color)
-            .child(SharedString::from("[Image preview unavailable]")),
+        img(image)
+            .w_full()
+            .h_full()
+            .object_fit(gpui::ObjectFit::Contain),
     )
 }
 
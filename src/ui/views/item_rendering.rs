@@ -15,6 +15,7 @@ pub fn render_item(item: &ListItem, selected: bool, row: usize) -> Stateful<Div>
     match item {
         ListItem::Application(app) => render_application(app, selected, row),
         ListItem::Window(win) => render_window(win, selected, row),
+        ListItem::Workspace(ws) => render_workspace(ws, selected, row),
         ListItem::Action(act) => render_action(act, selected, row),
         ListItem::Submenu(sub) => render_submenu(sub, selected, row),
         ListItem::Calculator(calc) => render_calculator(calc, selected, row),
@@ -62,6 +63,24 @@ fn render_window(win: &crate::items::WindowItem, selected: bool, row: usize) ->
     item
 }
 
+/// Render a workspace item.
+fn render_workspace(ws: &crate::items::WorkspaceItem, selected: bool, row: usize) -> Stateful<Div> {
+    let icon = ws.icon_name().and_then(PhosphorIcon::from_name);
+    let mut item = item_container(row, selected)
+        .child(render_phosphor_icon(icon))
+        .child(render_text_content(
+            &ws.name,
+            Some(&ws.description),
+            selected,
+        ));
+
+    if selected {
+        item = item.child(render_action_indicator("Switch"));
+    }
+
+    item
+}
+
 /// Render an action item.
 fn render_action(act: &crate::items::ActionItem, selected: bool, row: usize) -> Stateful<Div> {
     let icon = act.icon_name().and_then(PhosphorIcon::from_name);
@@ -0,0 +1,16 @@
+//! Launch usage tracking for frecency-based ranking.
+//!
+//! Records how often and how recently items are launched and persists the
+//! counts under the XDG state directory (`~/.local/state/zlaunch/usage.json`).
+//! The resulting frecency scores are folded into fuzzy matching and drive the
+//! "Recent" section shown on an empty query.
+//!
+//! The store also learns which item was chosen for a typed query, so the same
+//! item is strongly boosted the next time that query (or a longer one) is typed.
+
+mod store;
+
+pub use store::{
+    QuerySelection, UsageEntry, UsageStore, flush, frecency_scores, init, learned_selections,
+    list_selections, normalize_query, record_launch, record_selection, reset_selections,
+};
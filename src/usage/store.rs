@@ -0,0 +1,525 @@
+//! Persistent launch counts, frecency scoring and learned query selections.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Current usage store format version.
+const STORE_VERSION: u32 = 1;
+
+/// Launch count at which the frequency component saturates.
+const MAX_COUNT: u32 = 50;
+
+/// Seconds per day, used for recency buckets.
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Launch statistics unused for this many days score zero and are forgotten.
+const ENTRY_MAX_AGE_DAYS: u64 = 365;
+
+/// Maximum number of learned selections kept.
+const MAX_SELECTIONS: usize = 1000;
+
+/// Learned selections unused for this many days are forgotten.
+const SELECTION_MAX_AGE_DAYS: u64 = 180;
+
+/// Delay before changes are written, so a burst of records shares one write.
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// Global usage store.
+static USAGE: RwLock<Option<UsageStore>> = RwLock::new(None);
+
+/// Whether a write of the store is scheduled.
+static SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Launch statistics for a single item.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UsageEntry {
+    /// Number of times the item was launched.
+    pub count: u32,
+    /// Unix timestamp (seconds) of the most recent launch.
+    pub last_used: u64,
+}
+
+impl UsageEntry {
+    /// Compute the frecency of this entry, normalized to `0.0..=1.0`.
+    ///
+    /// Frequency grows logarithmically up to `MAX_COUNT` launches and is
+    /// scaled down the longer the item has gone unused, down to zero after
+    /// `ENTRY_MAX_AGE_DAYS`.
+    pub fn frecency(&self, now: u64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        let count = self.count.min(MAX_COUNT) as f64;
+        let frequency = (1.0 + count).ln() / (1.0 + MAX_COUNT as f64).ln();
+
+        let age_days = now.saturating_sub(self.last_used) / SECS_PER_DAY;
+        let recency = match age_days {
+            0..=3 => 1.0,
+            4..=14 => 0.7,
+            15..=31 => 0.5,
+            32..=90 => 0.3,
+            91..=ENTRY_MAX_AGE_DAYS => 0.1,
+            _ => 0.0,
+        };
+
+        frequency * recency
+    }
+}
+
+/// The item chosen for a typed query.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuerySelection {
+    /// Usage key of the chosen item.
+    pub key: String,
+    /// Number of consecutive times this item was chosen for the query.
+    pub count: u32,
+    /// Unix timestamp (seconds) of the most recent selection.
+    pub last_used: u64,
+}
+
+/// The full usage store persisted on disk.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UsageStore {
+    /// Store format version for compatibility checks.
+    pub version: u32,
+    /// Launch statistics keyed by item usage key (e.g. `app:firefox`).
+    pub entries: HashMap<String, UsageEntry>,
+    /// Learned selections keyed by normalized query.
+    #[serde(default)]
+    pub selections: HashMap<String, QuerySelection>,
+}
+
+impl Default for UsageStore {
+    fn default() -> Self {
+        Self {
+            version: STORE_VERSION,
+            entries: HashMap::new(),
+            selections: HashMap::new(),
+        }
+    }
+}
+
+impl UsageStore {
+    /// Load the store from disk.
+    pub fn load() -> Option<Self> {
+        let path = Self::store_path()?;
+        let data = fs::read_to_string(&path).ok()?;
+        let store: Self = serde_json::from_str(&data).ok()?;
+
+        if store.version != STORE_VERSION {
+            debug!("Usage store version mismatch, ignoring");
+            return None;
+        }
+
+        Some(store)
+    }
+
+    /// Replace the store file with serialized data.
+    ///
+    /// Written to a temporary file first, so an interrupted write never
+    /// leaves a truncated store behind.
+    fn write(data: &str) -> anyhow::Result<()> {
+        let path = Self::store_path().ok_or_else(|| anyhow::anyhow!("No state directory"))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Record a launch of the item with the given key.
+    pub fn record(&mut self, key: &str, now: u64) {
+        if !self.entries.contains_key(key) {
+            self.prune_entries(now);
+        }
+        let entry = self.entries.entry(key.to_string()).or_default();
+        entry.count = entry.count.saturating_add(1);
+        entry.last_used = now;
+    }
+
+    /// Forget launch statistics whose frecency has decayed to zero.
+    pub fn prune_entries(&mut self, now: u64) {
+        self.entries.retain(|_, entry| entry.frecency(now) > 0.0);
+    }
+
+    /// Remember the item chosen for a typed query.
+    ///
+    /// Choosing a different item for the same query replaces the mapping.
+    pub fn record_selection(&mut self, query: &str, key: &str, now: u64) {
+        let query = normalize_query(query);
+        if query.is_empty() {
+            return;
+        }
+
+        match self.selections.get_mut(&query) {
+            Some(selection) if selection.key == key => {
+                selection.count = selection.count.saturating_add(1);
+                selection.last_used = now;
+            }
+            _ => {
+                self.selections.insert(
+                    query,
+                    QuerySelection {
+                        key: key.to_string(),
+                        count: 1,
+                        last_used: now,
+                    },
+                );
+                self.prune_selections(now);
+            }
+        }
+    }
+
+    /// Forget stale selections and the least recently used ones beyond
+    /// `MAX_SELECTIONS`.
+    pub fn prune_selections(&mut self, now: u64) {
+        let max_age = SELECTION_MAX_AGE_DAYS * SECS_PER_DAY;
+        self.selections
+            .retain(|_, selection| now.saturating_sub(selection.last_used) <= max_age);
+
+        if self.selections.len() > MAX_SELECTIONS {
+            let mut by_age: Vec<(u64, String)> = self
+                .selections
+                .iter()
+                .map(|(query, selection)| (selection.last_used, query.clone()))
+                .collect();
+            by_age.sort();
+            let excess = self.selections.len() - MAX_SELECTIONS;
+            for (_, query) in by_age.into_iter().take(excess) {
+                self.selections.remove(&query);
+            }
+        }
+    }
+
+    /// Forget learned selections.
+    ///
+    /// Removes only the given query if provided, otherwise all of them.
+    /// Returns the number of removed mappings.
+    pub fn reset_selections(&mut self, query: Option<&str>) -> usize {
+        match query {
+            Some(query) => self
+                .selections
+                .remove(&normalize_query(query))
+                .map_or(0, |_| 1),
+            None => {
+                let count = self.selections.len();
+                self.selections.clear();
+                count
+            }
+        }
+    }
+
+    /// Compute frecency scores for all entries.
+    pub fn scores(&self, now: u64) -> HashMap<String, f64> {
+        self.entries
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.frecency(now)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect()
+    }
+
+    /// Get the store file path.
+    fn store_path() -> Option<PathBuf> {
+        dirs::state_dir().map(|d| d.join("zlaunch").join("usage.json"))
+    }
+}
+
+/// Load the usage store from disk (call once at daemon startup).
+pub fn init() {
+    let mut store = UsageStore::load().unwrap_or_default();
+    store.prune_entries(now_secs());
+    store.prune_selections(now_secs());
+    debug!("Loaded {} usage entries", store.entries.len());
+    *USAGE.write().unwrap() = Some(store);
+}
+
+/// Record a launch of the item with the given usage key and persist it.
+pub fn record_launch(key: &str) {
+    let mut usage = USAGE.write().unwrap();
+    let store = usage.get_or_insert_with(UsageStore::default);
+    store.record(key, now_secs());
+    schedule_save();
+}
+
+/// Remember the item chosen for a typed query and persist it.
+pub fn record_selection(query: &str, key: &str) {
+    let mut usage = USAGE.write().unwrap();
+    let store = usage.get_or_insert_with(UsageStore::default);
+    store.record_selection(query, key, now_secs());
+    schedule_save();
+}
+
+/// Write the store in the background after `SAVE_DELAY`.
+///
+/// Changes made in the meantime are included in the same write.
+fn schedule_save() {
+    if SAVE_PENDING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    thread::spawn(|| {
+        thread::sleep(SAVE_DELAY);
+        flush();
+    });
+}
+
+/// Write pending changes to disk now (call before the daemon exits).
+pub fn flush() {
+    if !SAVE_PENDING.swap(false, Ordering::AcqRel) {
+        return;
+    }
+
+    // Serialize under the lock, write without holding it
+    let data = {
+        let usage = USAGE.read().unwrap();
+        let Some(store) = usage.as_ref() else {
+            return;
+        };
+        serde_json::to_string(store)
+    };
+
+    if let Err(e) = data
+        .map_err(anyhow::Error::from)
+        .and_then(|data| UsageStore::write(&data))
+    {
+        warn!("Failed to save usage store: {}", e);
+    }
+}
+
+/// Get a snapshot of learned selections (normalized query to usage key).
+pub fn learned_selections() -> HashMap<String, String> {
+    USAGE
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|store| {
+            store
+                .selections
+                .iter()
+                .map(|(query, selection)| (query.clone(), selection.key.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// List all learned selections, sorted by query.
+pub fn list_selections() -> Vec<(String, QuerySelection)> {
+    let usage = USAGE.read().unwrap();
+    let mut selections: Vec<(String, QuerySelection)> = usage
+        .as_ref()
+        .map(|store| {
+            store
+                .selections
+                .iter()
+                .map(|(query, selection)| (query.clone(), selection.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    selections.sort_by(|a, b| a.0.cmp(&b.0));
+    selections
+}
+
+/// Forget learned selections (all, or only the given query) and persist.
+///
+/// Returns the number of removed mappings.
+pub fn reset_selections(query: Option<&str>) -> usize {
+    let mut usage = USAGE.write().unwrap();
+    let store = usage.get_or_insert_with(UsageStore::default);
+    let removed = store.reset_selections(query);
+
+    if removed > 0 {
+        schedule_save();
+    }
+
+    removed
+}
+
+/// Normalize a query for selection learning (trimmed, lowercase).
+pub fn normalize_query(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+/// Get a snapshot of the current frecency scores keyed by usage key.
+pub fn frecency_scores() -> HashMap<String, f64> {
+    USAGE
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|store| store.scores(now_secs()))
+        .unwrap_or_default()
+}
+
+/// Current time as a Unix timestamp in seconds.
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_unused_entry_has_no_frecency() {
+        let entry = UsageEntry::default();
+        assert_eq!(entry.frecency(NOW), 0.0);
+    }
+
+    #[test]
+    fn test_frecency_grows_with_count() {
+        let once = UsageEntry {
+            count: 1,
+            last_used: NOW,
+        };
+        let often = UsageEntry {
+            count: 20,
+            last_used: NOW,
+        };
+        assert!(often.frecency(NOW) > once.frecency(NOW));
+        assert!(often.frecency(NOW) <= 1.0);
+    }
+
+    #[test]
+    fn test_frecency_decays_with_age() {
+        let recent = UsageEntry {
+            count: 10,
+            last_used: NOW,
+        };
+        let stale = UsageEntry {
+            count: 10,
+            last_used: NOW - 60 * SECS_PER_DAY,
+        };
+        assert!(recent.frecency(NOW) > stale.frecency(NOW));
+    }
+
+    #[test]
+    fn test_frecency_saturates() {
+        let capped = UsageEntry {
+            count: MAX_COUNT,
+            last_used: NOW,
+        };
+        let beyond = UsageEntry {
+            count: MAX_COUNT * 10,
+            last_used: NOW,
+        };
+        assert!((capped.frecency(NOW) - 1.0).abs() < f64::EPSILON);
+        assert!((beyond.frecency(NOW) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_store_record() {
+        let mut store = UsageStore::default();
+        store.record("app:firefox", NOW - 10);
+        store.record("app:firefox", NOW);
+
+        let entry = &store.entries["app:firefox"];
+        assert_eq!(entry.count, 2);
+        assert_eq!(entry.last_used, NOW);
+
+        let scores = store.scores(NOW);
+        assert!(scores["app:firefox"] > 0.0);
+    }
+
+    #[test]
+    fn test_prune_stale_entries() {
+        let mut store = UsageStore::default();
+        store.record("app:gedit", NOW - 400 * SECS_PER_DAY);
+        store.record("app:vim", NOW - 200 * SECS_PER_DAY);
+        assert_eq!(store.entries["app:gedit"].frecency(NOW), 0.0);
+
+        store.record("app:kitty", NOW);
+        assert!(!store.entries.contains_key("app:gedit"));
+        assert!(store.entries.contains_key("app:vim"));
+        assert!(store.entries.contains_key("app:kitty"));
+    }
+
+    #[test]
+    fn test_record_selection() {
+        let mut store = UsageStore::default();
+        store.record_selection(" Te ", "app:kitty", NOW);
+        store.record_selection("te", "app:kitty", NOW);
+
+        let selection = &store.selections["te"];
+        assert_eq!(selection.key, "app:kitty");
+        assert_eq!(selection.count, 2);
+
+        // Choosing a different item replaces the mapping
+        store.record_selection("te", "app:gedit", NOW);
+        let selection = &store.selections["te"];
+        assert_eq!(selection.key, "app:gedit");
+        assert_eq!(selection.count, 1);
+    }
+
+    #[test]
+    fn test_record_selection_ignores_empty_query() {
+        let mut store = UsageStore::default();
+        store.record_selection("  ", "app:kitty", NOW);
+        assert!(store.selections.is_empty());
+    }
+
+    #[test]
+    fn test_reset_selections() {
+        let mut store = UsageStore::default();
+        store.record_selection("te", "app:kitty", NOW);
+        store.record_selection("fi", "app:firefox", NOW);
+
+        assert_eq!(store.reset_selections(Some("TE")), 1);
+        assert_eq!(store.reset_selections(Some("te")), 0);
+        assert_eq!(store.reset_selections(None), 1);
+        assert!(store.selections.is_empty());
+    }
+
+    #[test]
+    fn test_prune_stale_selections() {
+        let mut store = UsageStore::default();
+        store.record_selection("old", "app:gedit", NOW - 200 * SECS_PER_DAY);
+        store.record_selection("new", "app:kitty", NOW);
+        assert!(!store.selections.contains_key("old"));
+        assert!(store.selections.contains_key("new"));
+    }
+
+    #[test]
+    fn test_prune_selections_beyond_limit() {
+        let mut store = UsageStore::default();
+        for i in 0..MAX_SELECTIONS as u64 + 5 {
+            store.record_selection(&format!("q{}", i), "app:kitty", NOW + i);
+        }
+        assert_eq!(store.selections.len(), MAX_SELECTIONS);
+        // The least recently used ones are dropped
+        assert!(!store.selections.contains_key("q4"));
+        assert!(store.selections.contains_key("q5"));
+    }
+
+    #[test]
+    fn test_store_without_selections_loads() {
+        let json = r#"{"version":1,"entries":{}}"#;
+        let store: UsageStore = serde_json::from_str(json).unwrap();
+        assert!(store.selections.is_empty());
+    }
+
+    #[test]
+    fn test_store_serde_roundtrip() {
+        let mut store = UsageStore::default();
+        store.record("action:action-lock", NOW);
+
+        let json = serde_json::to_string(&store).unwrap();
+        let loaded: UsageStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.version, STORE_VERSION);
+        assert_eq!(loaded.entries["action:action-lock"].count, 1);
+    }
+}